@@ -1,10 +1,15 @@
 use crate::prelude::*;
 use beet::prelude::*;
+use std::time::Instant;
+use twilight_model::gateway::Intents;
+use twilight_model::id::Id;
+use twilight_model::id::marker::UserMarker;
 
 pub fn default_bot() -> impl Bundle {}
 
 #[derive(Component)]
 #[component(on_add=on_add)]
+#[require(HandlerLimit, GatewayIntents)]
 pub struct DiscordBot {
 	/// The bot's token, usually loaded from the environment at startup.
 	token: String,
@@ -30,3 +35,234 @@ impl Default for DiscordBot {
 		}
 	}
 }
+
+/// The bot identity and channels async handlers need, copied out of the
+/// bot entity by [`read_states`].
+#[derive(Debug, Clone)]
+pub struct BotStateSnapshot {
+	pub user_id: Id<UserMarker>,
+	pub start_time: Instant,
+	pub channels: BotChannels,
+}
+
+/// The [`GreetState`] of a bot entity, copied out by [`read_states`].
+#[derive(Debug, Clone, Default)]
+pub struct GreetStateSnapshot {
+	pub greeted_users: HashSet<Id<UserMarker>>,
+}
+
+/// Read the bot and greet state of `entity` in a single world access, so
+/// no other event is handled between the reads as with one await per
+/// component. Fails if the bot isn't ready yet, a bot without
+/// [`GreetState`] reads as having greeted nobody.
+pub async fn read_states(
+	world: &AsyncWorld,
+	entity: Entity,
+) -> Result<(BotStateSnapshot, GreetStateSnapshot)> {
+	world
+		.with_then(move |world| snapshot_states(world.entity(entity)))
+		.await
+}
+
+/// The synchronous part of [`read_states`].
+pub fn snapshot_states(
+	entity: EntityRef,
+) -> Result<(BotStateSnapshot, GreetStateSnapshot)> {
+	let state = entity
+		.get::<BotState>()
+		.ok_or_else(|| "bot has no BotState, is it ready?".to_string())?;
+	let bot = BotStateSnapshot {
+		user_id: state.user_id(),
+		start_time: state.start_time(),
+		channels: entity.get::<BotChannels>().cloned().unwrap_or_default(),
+	};
+	let greet = entity
+		.get::<GreetState>()
+		.map(|greet| GreetStateSnapshot {
+			greeted_users: greet.greeted_users.clone(),
+		})
+		.unwrap_or_default();
+	Ok((bot, greet))
+}
+
+/// Why a token can't be a bot token, caught before connecting instead of
+/// surfacing as a 4004 gateway close.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenFormatError {
+	/// The token is empty or only whitespace.
+	Empty,
+	/// The token includes the `Bot ` authorization prefix.
+	BotPrefix,
+	/// The token is a user account token, which bots can't use.
+	UserToken,
+	/// The token isn't three `.`-separated base64 segments.
+	Malformed,
+}
+
+impl std::fmt::Display for TokenFormatError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let reason = match self {
+			TokenFormatError::Empty => "DISCORD_TOKEN is empty",
+			TokenFormatError::BotPrefix => {
+				"DISCORD_TOKEN starts with `Bot `, remove the prefix"
+			}
+			TokenFormatError::UserToken => {
+				"DISCORD_TOKEN looks like a user token, use the bot token \
+				 from the Bot page of the Developer Portal"
+			}
+			TokenFormatError::Malformed => {
+				"DISCORD_TOKEN is not three `.`-separated base64 segments, \
+				 reset and copy the token from the Developer Portal"
+			}
+		};
+		f.write_str(reason)
+	}
+}
+
+impl std::error::Error for TokenFormatError {}
+
+/// Cheap sanity check of a bot token's shape. It doesn't prove the token
+/// is valid, only that Discord could accept it.
+pub fn validate_token_format(token: &str) -> Result<(), TokenFormatError> {
+	let token = token.trim();
+	if token.is_empty() {
+		return Err(TokenFormatError::Empty);
+	}
+	if token.starts_with("Bot ") {
+		return Err(TokenFormatError::BotPrefix);
+	}
+	// 2FA-enabled user tokens.
+	if token.starts_with("mfa.") {
+		return Err(TokenFormatError::UserToken);
+	}
+	let segments: Vec<&str> = token.split('.').collect();
+	let is_base64 = |segment: &str| {
+		!segment.is_empty()
+			&& segment.bytes().all(|b| {
+				b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'=')
+			})
+	};
+	if segments.len() != 3 || !segments.iter().all(|s| is_base64(s)) {
+		return Err(TokenFormatError::Malformed);
+	}
+	Ok(())
+}
+
+/// The intents a bot entity connects to the gateway with, read by the
+/// gateway listener on startup. Defaults to [`gateway_intents`].
+///
+/// Dropping a privileged intent the bot isn't approved for avoids a 4014
+/// close, at the cost of the events and fields it gates.
+///
+/// ```ignore
+/// commands.spawn((
+///     DiscordBot::default(),
+///     GatewayIntents(gateway_intents() - Intents::GUILD_PRESENCES),
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct GatewayIntents(pub Intents);
+
+impl Default for GatewayIntents {
+	fn default() -> Self { Self(gateway_intents()) }
+}
+
+/// Marks a bot entity as handling `!` prefix commands, which only work when
+/// the gateway is connected with the `MESSAGE_CONTENT` intent.
+///
+/// The gateway listener checks for this on startup and warns if the intent
+/// is missing, or refuses to connect when `strict` is set.
+#[derive(Debug, Default, Clone, Component)]
+pub struct PrefixCommands {
+	/// Fail startup instead of warning when `MESSAGE_CONTENT` is missing.
+	pub strict: bool,
+	/// How to respond to `!` messages that aren't a known command.
+	pub unknown_command: UnknownCommandPolicy,
+}
+
+impl PrefixCommands {
+	pub fn strict() -> Self {
+		Self {
+			strict: true,
+			..Default::default()
+		}
+	}
+
+	/// Set the [`UnknownCommandPolicy`].
+	pub fn with_unknown_command(
+		mut self,
+		policy: UnknownCommandPolicy,
+	) -> Self {
+		self.unknown_command = policy;
+		self
+	}
+}
+
+/// What to do when a `!`-prefixed message doesn't match a known command.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum UnknownCommandPolicy {
+	/// Ignore it. The default, since other bots in the same server often
+	/// share the `!` prefix.
+	#[default]
+	Silent,
+	/// Tell the author privately. Channel messages can't be ephemeral, so
+	/// this sends a DM.
+	DirectMessage,
+	/// Reply in the channel.
+	Reply,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn snapshot_reflects_current_state() {
+		let mut world = World::new();
+		let mut bot = world.spawn(GreetState::default());
+		assert!(snapshot_states(bot.as_readonly()).is_err());
+
+		bot.observe(common_handlers::init_bot_state);
+		bot.trigger(DiscordReady::create(crate::test_fixtures::ready("9")));
+		let (state, greet) = snapshot_states(bot.as_readonly()).unwrap();
+		assert_eq!(state.user_id, Id::new(9));
+		assert!(state.channels.is_empty());
+		assert!(greet.greeted_users.is_empty());
+
+		bot.get_mut::<BotChannels>()
+			.unwrap()
+			.insert(Id::new(1), Id::new(10));
+		bot.get_mut::<GreetState>()
+			.unwrap()
+			.greeted_users
+			.insert(Id::new(7));
+		let (state, greet) = snapshot_states(bot.as_readonly()).unwrap();
+		assert_eq!(state.channels.get(&Id::new(1)), Some(&Id::new(10)));
+		assert!(greet.greeted_users.contains(&Id::new(7)));
+	}
+
+	#[test]
+	fn token_format_validation() {
+		let token =
+			"MTIzNDU2Nzg5MDEyMzQ1Njc4.GabcDe.abcdefghijklmnopqrstuvwxyz_-0123";
+		assert_eq!(validate_token_format(token), Ok(()));
+		assert_eq!(validate_token_format(&format!(" {token}\n")), Ok(()));
+		assert_eq!(validate_token_format(""), Err(TokenFormatError::Empty));
+		assert_eq!(validate_token_format("  "), Err(TokenFormatError::Empty));
+		assert_eq!(
+			validate_token_format(&format!("Bot {token}")),
+			Err(TokenFormatError::BotPrefix)
+		);
+		assert_eq!(
+			validate_token_format("mfa.abcdefghijklmnop"),
+			Err(TokenFormatError::UserToken)
+		);
+		for malformed in ["abc.def", "a.b.c.d", "abc..def", "ab$.cd.ef"] {
+			assert_eq!(
+				validate_token_format(malformed),
+				Err(TokenFormatError::Malformed),
+				"{malformed}"
+			);
+		}
+	}
+}
@@ -3,7 +3,6 @@
 use crate::prelude::*;
 use beet::prelude::*;
 use tracing::info;
-use twilight_model::channel::ChannelType;
 use twilight_model::gateway::payload::incoming::GuildCreate;
 use twilight_model::id::Id;
 use twilight_model::id::marker::ChannelMarker;
@@ -27,8 +26,21 @@ pub struct JoinBotChannel {
 	pub channel: Id<ChannelMarker>,
 }
 
+/// The bot channel of a guild was found to be gone, usually deleted.
+/// Clears it so the next [`DiscordGuildCreate`] can select another.
+#[derive(EntityEvent)]
+pub struct LeaveBotChannel {
+	pub entity: Entity,
+	pub guild: Id<GuildMarker>,
+	pub channel: Id<ChannelMarker>,
+}
+
 fn on_add(mut world: DeferredWorld, cx: HookContext) {
-	world.commands().entity(cx.entity).observe(bot_channel);
+	world
+		.commands()
+		.entity(cx.entity)
+		.observe(bot_channel)
+		.observe(leave_bot_channel);
 }
 
 pub fn bot_channel(
@@ -51,9 +63,9 @@ pub fn bot_channel(
 	}
 
 	let bot_name_lower = bot_state.name().to_ascii_lowercase();
-	// find a text channel with the same name as the bot
+	// find a text or announcement channel with the same name as the bot
 	let Some(channel) = guild.channels.iter().find(|channel| {
-		channel.kind == ChannelType::GuildText
+		channel.kind.is_text_like()
 			&& channel.name.as_ref().map_or(false, |name| {
 				name.to_ascii_lowercase() == bot_name_lower
 			})
@@ -80,9 +92,27 @@ pub fn bot_channel(
 	Ok(())
 }
 
+fn leave_bot_channel(
+	ev: On<LeaveBotChannel>,
+	mut query: Query<&mut BotChannels>,
+) -> Result {
+	let mut bot_channels = query.get_mut(ev.event_target())?;
+	// A channel selected since the failed send is kept.
+	if bot_channels.get(&ev.guild) == Some(&ev.channel) {
+		bot_channels.remove(&ev.guild);
+		info!(
+			guild_id = %ev.guild,
+			channel_id = %ev.channel,
+			"left bot channel"
+		);
+	}
+	Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use twilight_model::channel::ChannelType;
 	use twilight_model::gateway::payload::incoming::GuildCreate;
 	use twilight_model::guild::UnavailableGuild;
 
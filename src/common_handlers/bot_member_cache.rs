@@ -0,0 +1,166 @@
+//! The bot's own member object per guild, needed for its roles when
+//! computing permissions.
+use crate::prelude::*;
+use beet::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use tracing::debug;
+use twilight_model::guild::Member;
+use twilight_model::id::Id;
+use twilight_model::id::marker::GuildMarker;
+use twilight_model::id::marker::UserMarker;
+
+/// Caches the bot's [`Member`] in each guild, fetched on first use.
+///
+/// An entry is dropped when a GUILD_MEMBER_UPDATE for the bot arrives, and
+/// the whole cache on READY, since updates may have been missed while
+/// disconnected. Clones share the cache, so it can be moved into async work.
+#[derive(Debug, Clone, Default, Component)]
+#[component(on_add=on_add)]
+pub struct BotMemberCache {
+	members: Arc<Mutex<HashMap<Id<GuildMarker>, Member>>>,
+}
+
+impl BotMemberCache {
+	/// The bot's member in `guild_id`, fetched with [`GetGuildMember`] unless
+	/// already cached.
+	pub async fn get(
+		&self,
+		http: &DiscordHttpClient,
+		guild_id: Id<GuildMarker>,
+		bot_user_id: Id<UserMarker>,
+	) -> Result<Member, HttpError> {
+		self.get_or_fetch(guild_id, || {
+			http.send(GetGuildMember::new(guild_id, bot_user_id))
+		})
+		.await
+	}
+
+	async fn get_or_fetch<F, Fut>(
+		&self,
+		guild_id: Id<GuildMarker>,
+		fetch: F,
+	) -> Result<Member, HttpError>
+	where
+		F: FnOnce() -> Fut,
+		Fut: Future<Output = Result<Member, HttpError>>,
+	{
+		if let Some(member) = self.cached(guild_id) {
+			return Ok(member);
+		}
+		let member = fetch().await?;
+		self.lock().insert(guild_id, member.clone());
+		Ok(member)
+	}
+
+	/// The cached member in `guild_id`, without fetching.
+	pub fn cached(&self, guild_id: Id<GuildMarker>) -> Option<Member> {
+		self.lock().get(&guild_id).cloned()
+	}
+
+	/// Drop the cached member in `guild_id`, so the next
+	/// [`get`](Self::get) fetches it again.
+	pub fn invalidate(&self, guild_id: Id<GuildMarker>) {
+		self.lock().remove(&guild_id);
+	}
+
+	/// Drop every cached member.
+	pub fn clear(&self) { self.lock().clear(); }
+
+	fn lock(&self) -> MutexGuard<'_, HashMap<Id<GuildMarker>, Member>> {
+		self.members
+			.lock()
+			.unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+}
+
+fn on_add(mut world: DeferredWorld, cx: HookContext) {
+	world
+		.commands()
+		.entity(cx.entity)
+		.observe(clear_bot_members)
+		.observe(invalidate_bot_member);
+}
+
+fn clear_bot_members(
+	ev: On<DiscordReady>,
+	query: Query<&BotMemberCache>,
+) -> Result {
+	query.get(ev.event_target())?.clear();
+	Ok(())
+}
+
+fn invalidate_bot_member(
+	ev: On<DiscordMemberUpdate>,
+	query: Query<(&BotMemberCache, &BotState)>,
+) -> Result {
+	let (cache, bot_state) = query.get(ev.event_target())?;
+	if ev.user.id == bot_state.user_id() {
+		debug!(guild_id = %ev.guild_id, "bot member updated, refetching");
+		cache.invalidate(ev.guild_id);
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::cell::Cell;
+
+	fn member(nick: &str) -> Member {
+		serde_json::from_value(serde_json::json!({
+			"deaf": false,
+			"flags": 0,
+			"joined_at": "2021-01-01T00:00:00.000000+00:00",
+			"mute": false,
+			"nick": nick,
+			"roles": ["300"],
+			"user": {
+				"id": "1",
+				"username": "bot",
+				"discriminator": "0",
+				"avatar": null,
+				"bot": true,
+			},
+		}))
+		.unwrap()
+	}
+
+	#[test]
+	fn cached_member_is_not_fetched_again() {
+		let cache = BotMemberCache::default();
+		let guild_id = Id::new(10);
+		let fetches = Cell::new(0);
+		let fetch = || {
+			fetches.set(fetches.get() + 1);
+			async { Ok(member("first")) }
+		};
+
+		futures_lite::future::block_on(async {
+			let first = cache.get_or_fetch(guild_id, fetch).await.unwrap();
+			let second = cache.get_or_fetch(guild_id, fetch).await.unwrap();
+			assert_eq!(first, second);
+			assert_eq!(fetches.get(), 1);
+
+			// An update for the bot drops the entry.
+			cache.invalidate(guild_id);
+			assert!(cache.cached(guild_id).is_none());
+			cache.get_or_fetch(guild_id, fetch).await.unwrap();
+			assert_eq!(fetches.get(), 2);
+		});
+	}
+
+	#[test]
+	fn failed_fetch_is_not_cached() {
+		let cache = BotMemberCache::default();
+		let result = futures_lite::future::block_on(
+			cache.get_or_fetch(Id::new(10), || async {
+				Err(HttpError::Transport("503".into()))
+			}),
+		);
+		assert!(result.is_err());
+		assert!(cache.cached(Id::new(10)).is_none());
+	}
+}
@@ -0,0 +1,451 @@
+//! Per-invocation context for slash command handlers.
+//!
+//! Bundles the HTTP client, the interaction, its parsed options and the
+//! async world so handlers take a single `&CommandContext` instead of
+//! threading each piece through separately.
+
+use crate::prelude::*;
+use beet::prelude::*;
+use twilight_model::application::interaction::Interaction;
+use twilight_model::application::interaction::InteractionData;
+use twilight_model::application::interaction::application_command::CommandDataOption;
+use twilight_model::channel::message::MessageFlags;
+use twilight_model::http::interaction::InteractionResponse;
+use twilight_model::http::interaction::InteractionResponseData;
+use twilight_model::id::Id;
+use twilight_model::id::marker::GuildMarker;
+use twilight_model::user::User;
+
+/// Most characters Discord accepts in a single message's content.
+pub const MESSAGE_CONTENT_MAX: usize = 2000;
+
+/// Everything a slash command handler needs to read its input and reply.
+///
+/// ```ignore
+/// async fn ping(ctx: &CommandContext<'_>) -> Result<(), HttpError> {
+///     ctx.respond("🏓 Pong!").await
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CommandContext<'a> {
+	pub http: &'a DiscordHttpClient,
+	pub interaction: &'a Interaction,
+	/// Options of the invoked command, empty for non-command interactions.
+	pub options: &'a [CommandDataOption],
+	/// The async world, for handlers that read or change bot components.
+	/// Set by the interaction dispatcher with
+	/// [`with_world`](Self::with_world), `None` in a context built inside a
+	/// synchronous observer.
+	pub world: Option<&'a AsyncWorld>,
+}
+
+impl<'a> CommandContext<'a> {
+	/// Build a context, pulling the options out of the interaction data.
+	pub fn new(
+		http: &'a DiscordHttpClient,
+		interaction: &'a Interaction,
+	) -> Self {
+		let options: &[CommandDataOption] = match interaction.data.as_ref() {
+			Some(InteractionData::ApplicationCommand(data)) => {
+				&data.options[..]
+			}
+			_ => &[],
+		};
+		Self {
+			http,
+			interaction,
+			options,
+			world: None,
+		}
+	}
+
+	/// Give handlers access to the async world.
+	pub fn with_world(mut self, world: &'a AsyncWorld) -> Self {
+		self.world = Some(world);
+		self
+	}
+
+	/// Name of the invoked command, if this is a command interaction.
+	pub fn command_name(&self) -> Option<&'a str> {
+		match self.interaction.data.as_ref()? {
+			InteractionData::ApplicationCommand(data) => Some(&data.name),
+			_ => None,
+		}
+	}
+
+	/// The guild the command was invoked in, `None` in DMs.
+	pub fn guild_id(&self) -> Option<Id<GuildMarker>> {
+		self.interaction.guild_id
+	}
+
+	/// The invoking user, whether in a guild or a DM.
+	pub fn author(&self) -> Option<&'a User> { self.interaction.author() }
+
+	// ------------------------------------------------------------------
+	// Responses
+	// ------------------------------------------------------------------
+
+	/// Reply with a plain text message.
+	pub async fn respond(
+		&self,
+		content: impl Into<String>,
+	) -> Result<(), HttpError> {
+		self.http.send(self.respond_request(content)).await
+	}
+
+	/// Reply with a text message only the invoking user can see.
+	pub async fn respond_ephemeral(
+		&self,
+		content: impl Into<String>,
+	) -> Result<(), HttpError> {
+		self.http
+			.send(self.respond_ephemeral_request(content))
+			.await
+	}
+
+	/// Acknowledge the interaction, showing a "thinking…" state until a
+	/// follow-up or edit of the original response is sent.
+	pub async fn defer(&self) -> Result<(), HttpError> {
+		self.http.send(self.defer_request()).await
+	}
+
+	/// Reply with content of any length: defer, edit the first
+	/// [`MESSAGE_CONTENT_MAX`] chars into the original response, then send
+	/// the rest as follow-ups. Only the initial response can't be chunked.
+	pub async fn respond_long(
+		&self,
+		content: impl AsRef<str>,
+	) -> Result<(), HttpError> {
+		self.send_long_response(self.long_response_requests(content.as_ref()))
+			.await
+	}
+
+	/// [`respond_long`](Self::respond_long), with every message only
+	/// visible to the invoking user.
+	pub async fn respond_long_ephemeral(
+		&self,
+		content: impl AsRef<str>,
+	) -> Result<(), HttpError> {
+		self.send_long_response(
+			self.ephemeral_long_response_requests(content.as_ref()),
+		)
+		.await
+	}
+
+	async fn send_long_response(
+		&self,
+		requests: LongResponse,
+	) -> Result<(), HttpError> {
+		self.http.send(requests.defer).await?;
+		self.http.send(requests.edit).await?;
+		for followup in requests.followups {
+			self.http.send(followup).await?;
+		}
+		Ok(())
+	}
+
+	/// Send an arbitrary [`InteractionResponse`].
+	pub async fn respond_with(
+		&self,
+		response: InteractionResponse,
+	) -> Result<(), HttpError> {
+		self.http.send(self.response_request(response)).await
+	}
+
+	/// The request [`respond`](Self::respond) sends.
+	pub fn respond_request(
+		&self,
+		content: impl Into<String>,
+	) -> CreateInteractionResponse {
+		self.response_request(InteractionResponse::text(content))
+	}
+
+	/// The request [`respond_ephemeral`](Self::respond_ephemeral) sends.
+	pub fn respond_ephemeral_request(
+		&self,
+		content: impl Into<String>,
+	) -> CreateInteractionResponse {
+		self.response_request(InteractionResponse::message(
+			InteractionResponseData::default()
+				.with_content(content)
+				.with_flags(MessageFlags::EPHEMERAL),
+		))
+	}
+
+	/// The request [`defer`](Self::defer) sends.
+	pub fn defer_request(&self) -> CreateInteractionResponse {
+		self.response_request(InteractionResponse::defer())
+	}
+
+	/// The requests [`respond_long`](Self::respond_long) sends.
+	pub fn long_response_requests(&self, content: &str) -> LongResponse {
+		self.long_response(content, None)
+	}
+
+	/// The requests
+	/// [`respond_long_ephemeral`](Self::respond_long_ephemeral) sends.
+	///
+	/// The deferral decides whether the original response is ephemeral,
+	/// and an edit can't change that, so the flag is set there and kept
+	/// on the edit and each follow-up.
+	pub fn ephemeral_long_response_requests(
+		&self,
+		content: &str,
+	) -> LongResponse {
+		self.long_response(content, Some(MessageFlags::EPHEMERAL))
+	}
+
+	fn long_response(
+		&self,
+		content: &str,
+		flags: Option<MessageFlags>,
+	) -> LongResponse {
+		let app_id = self.interaction.application_id;
+		let token = &self.interaction.token;
+		let mut chunks =
+			split_content(content, MESSAGE_CONTENT_MAX).into_iter();
+		let first = chunks.next().unwrap_or_default();
+		let defer = match flags {
+			Some(flags) => self.response_request(InteractionResponse {
+				data: Some(
+					InteractionResponseData::default().with_flags(flags),
+				),
+				..InteractionResponse::defer()
+			}),
+			None => self.defer_request(),
+		};
+		let mut edit =
+			EditOriginalInteractionResponse::new(app_id, token.clone())
+				.content(first);
+		edit.flags = flags.map(|flags| flags.bits());
+		let followups = chunks
+			.map(|chunk| {
+				let mut followup =
+					CreateFollowup::new(app_id, token.clone()).content(chunk);
+				followup.flags = flags.map(|flags| flags.bits());
+				followup
+			})
+			.collect();
+		LongResponse {
+			defer,
+			edit,
+			followups,
+		}
+	}
+
+	/// Wrap `response` in a callback request for this interaction.
+	pub fn response_request(
+		&self,
+		response: InteractionResponse,
+	) -> CreateInteractionResponse {
+		CreateInteractionResponse::new(
+			self.interaction.id,
+			self.interaction.token.clone(),
+			response,
+		)
+	}
+}
+
+/// The requests making up a response longer than one message, in the order
+/// they must be sent.
+#[derive(Debug, Clone)]
+pub struct LongResponse {
+	pub defer: CreateInteractionResponse,
+	/// Fills the deferred response with the first chunk.
+	pub edit: EditOriginalInteractionResponse,
+	/// One per remaining chunk.
+	pub followups: Vec<CreateFollowup>,
+}
+
+/// Split `content` into chunks of at most `max_chars` chars, breaking after
+/// the last newline in each chunk where there is one.
+pub fn split_content(content: &str, max_chars: usize) -> Vec<String> {
+	let max_chars = max_chars.max(1);
+	let mut chunks = Vec::new();
+	let mut rest = content;
+	while !rest.is_empty() {
+		// Byte offset of the first char past the limit, or the end.
+		let end = rest
+			.char_indices()
+			.nth(max_chars)
+			.map_or(rest.len(), |(i, _)| i);
+		let split = if end == rest.len() {
+			end
+		} else {
+			rest[..end].rfind('\n').map_or(end, |i| i + 1)
+		};
+		chunks.push(rest[..split].to_string());
+		rest = &rest[split..];
+	}
+	chunks
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use beet::prelude::*;
+
+	fn make_interaction() -> Interaction {
+		serde_json::from_value(serde_json::json!({
+			"id": "1",
+			"application_id": "2",
+			"type": 2,
+			"token": "tok",
+			"authorizing_integration_owners": {},
+			"entitlements": [],
+			"guild_id": "10",
+			"data": {
+				"id": "3",
+				"name": "ping",
+				"type": 1,
+				"options": [
+					{ "name": "sides", "type": 4, "value": 20 },
+				],
+			},
+			"user": {
+				"id": "789",
+				"username": "alice",
+				"discriminator": "0",
+				"avatar": null,
+			},
+		}))
+		.expect("valid interaction JSON")
+	}
+
+	fn body_of(req: CreateInteractionResponse) -> serde_json::Value {
+		match req.into_discord_request().unwrap().body {
+			RequestBody::Json(value) => value,
+			other => panic!("expected JSON body, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn new_reads_command_data() {
+		let http = DiscordHttpClient::new("token");
+		let interaction = make_interaction();
+		let ctx = CommandContext::new(&http, &interaction);
+		assert_eq!(ctx.command_name(), Some("ping"));
+		assert_eq!(ctx.options.len(), 1);
+		assert_eq!(ctx.guild_id().map(|id| id.get()), Some(10));
+		assert_eq!(ctx.author().map(|u| u.id.get()), Some(789));
+	}
+
+	#[test]
+	fn respond_targets_interaction_callback() {
+		let http = DiscordHttpClient::new("token");
+		let interaction = make_interaction();
+		let ctx = CommandContext::new(&http, &interaction);
+		let req = ctx.respond_request("x").into_discord_request().unwrap();
+		assert!(matches!(req.method, HttpMethod::Post));
+		assert_eq!(req.path, "interactions/1/tok/callback");
+	}
+
+	#[test]
+	fn respond_sends_channel_message() {
+		let http = DiscordHttpClient::new("token")
+			.dry_run(true)
+			.with_dry_run_log(true);
+		let interaction = make_interaction();
+		let ctx = CommandContext::new(&http, &interaction);
+		futures_lite::future::block_on(ctx.respond("x")).unwrap();
+
+		let sent = http.take_dry_run_log();
+		assert_eq!(sent.len(), 1);
+		assert_eq!(sent[0].path, "interactions/1/tok/callback");
+		let RequestBody::Json(body) = &sent[0].body else {
+			panic!("expected a JSON body, got {:?}", sent[0].body);
+		};
+		assert_eq!(body["type"], 4);
+		assert_eq!(body["data"]["content"], "x");
+	}
+
+	#[test]
+	fn respond_produces_channel_message() {
+		let http = DiscordHttpClient::new("token");
+		let interaction = make_interaction();
+		let ctx = CommandContext::new(&http, &interaction);
+		let body = body_of(ctx.respond_request("x"));
+		assert_eq!(body["type"], 4);
+		assert_eq!(body["data"]["content"], "x");
+		assert!(body["data"].get("flags").is_none());
+	}
+
+	#[test]
+	fn respond_ephemeral_sets_flag() {
+		let http = DiscordHttpClient::new("token");
+		let interaction = make_interaction();
+		let ctx = CommandContext::new(&http, &interaction);
+		let body = body_of(ctx.respond_ephemeral_request("secret"));
+		assert_eq!(body["data"]["content"], "secret");
+		assert_eq!(body["data"]["flags"], MessageFlags::EPHEMERAL.bits());
+	}
+
+	#[test]
+	fn defer_uses_deferred_type() {
+		let http = DiscordHttpClient::new("token");
+		let interaction = make_interaction();
+		let ctx = CommandContext::new(&http, &interaction);
+		let body = body_of(ctx.defer_request());
+		assert_eq!(body["type"], 5);
+	}
+
+	#[test]
+	fn split_content_prefers_newlines() {
+		assert_eq!(split_content("aaa\nbb\ncccc", 7), ["aaa\nbb\n", "cccc"]);
+		assert_eq!(split_content("abcdef", 4), ["abcd", "ef"]);
+		assert!(split_content("", 4).is_empty());
+		// Counts chars, never splitting inside one.
+		assert_eq!(split_content("éééé", 3), ["ééé", "é"]);
+	}
+
+	#[test]
+	fn long_response_chains_followups() {
+		let http = DiscordHttpClient::new("token");
+		let interaction = make_interaction();
+		let ctx = CommandContext::new(&http, &interaction);
+		let response = ctx.long_response_requests(&"x".repeat(5000));
+
+		assert_eq!(body_of(response.defer)["type"], 5);
+		let edit = response.edit.content.as_deref().unwrap();
+		assert_eq!(edit.len(), MESSAGE_CONTENT_MAX);
+		let req = response.edit.into_discord_request().unwrap();
+		assert_eq!(req.path, "webhooks/2/tok/messages/@original");
+
+		let followups: Vec<_> = response
+			.followups
+			.iter()
+			.map(|f| f.content.as_deref().unwrap().len())
+			.collect();
+		assert_eq!(followups, [2000, 1000]);
+	}
+
+	#[test]
+	fn ephemeral_long_response_keeps_flag_on_edit() {
+		let http = DiscordHttpClient::new("token");
+		let interaction = make_interaction();
+		let ctx = CommandContext::new(&http, &interaction);
+		let response = ctx.ephemeral_long_response_requests(&"x".repeat(2500));
+		let ephemeral = MessageFlags::EPHEMERAL.bits();
+
+		let defer = body_of(response.defer);
+		assert_eq!(defer["type"], 5);
+		assert_eq!(defer["data"]["flags"], ephemeral);
+		let RequestBody::Json(edit) =
+			response.edit.into_discord_request().unwrap().body
+		else {
+			panic!("expected a JSON edit body");
+		};
+		assert_eq!(edit["flags"], ephemeral);
+		assert_eq!(response.followups.len(), 1);
+		assert_eq!(response.followups[0].flags, Some(ephemeral));
+
+		// Regular long responses don't send flags.
+		let response = ctx.long_response_requests("short");
+		let RequestBody::Json(edit) =
+			response.edit.into_discord_request().unwrap().body
+		else {
+			panic!("expected a JSON edit body");
+		};
+		assert!(edit.get("flags").is_none());
+	}
+}
@@ -0,0 +1,219 @@
+//! `/admin register` and `/admin clear` — owner-only slash command management
+//! for the current guild, to try out command changes without a restart.
+//! Commands needing a guild feature the server lacks aren't registered.
+//! `/admin cleanup count:N` deletes the bot's last N messages in the channel,
+//! e.g. test messages left by a deploy. `/admin stats` lists the most used
+//! commands.
+use super::RollConfig;
+use super::register_commands::guild_slash_commands;
+use crate::prelude::*;
+use tracing::info;
+use twilight_model::application::interaction::application_command::CommandDataOption;
+use twilight_model::application::interaction::application_command::CommandOptionValue;
+use twilight_model::id::Id;
+use twilight_model::id::marker::UserMarker;
+use twilight_model::oauth::Application;
+
+/// Whether `user_id` owns `app`: the owning user, or the team owner for
+/// team-owned applications.
+pub fn is_application_owner(
+	app: &Application,
+	user_id: Id<UserMarker>,
+) -> bool {
+	match &app.team {
+		Some(team) => team.owner_user_id == user_id,
+		None => app.owner.as_ref().is_some_and(|owner| owner.id == user_id),
+	}
+}
+
+/// Messages `/admin cleanup` deletes when no count is given.
+const CLEANUP_DEFAULT_COUNT: usize = 10;
+
+/// Commands `/admin stats` lists.
+const STATS_TOP_COMMANDS: usize = 10;
+
+/// Handles `/admin <register|clear|cleanup|stats>`, replying ephemerally.
+pub(super) async fn admin(
+	ctx: &CommandContext<'_>,
+	bot_user_id: Id<UserMarker>,
+	roll: RollConfig,
+	metrics: &CommandMetrics,
+) -> Result<(), HttpError> {
+	let reply = admin_reply(ctx, bot_user_id, roll, metrics).await?;
+	ctx.respond_ephemeral(reply).await
+}
+
+async fn admin_reply(
+	ctx: &CommandContext<'_>,
+	bot_user_id: Id<UserMarker>,
+	roll: RollConfig,
+	metrics: &CommandMetrics,
+) -> Result<String, HttpError> {
+	let Some(user) = ctx.author() else {
+		return Ok("❌ Couldn't determine your user info.".into());
+	};
+	let app = ctx.http.send(GetCurrentApplication).await?;
+	if !is_application_owner(&app, user.id) {
+		info!(user_id = %user.id, "rejected /admin from non-owner");
+		return Ok("❌ Only the bot owner can use `/admin`.".into());
+	}
+	let Some(guild_id) = ctx.guild_id() else {
+		return Ok("❌ `/admin` only works in a server.".into());
+	};
+
+	let app_id = ctx.interaction.application_id;
+	let reply = match subcommand(ctx) {
+		Some(("cleanup", options)) => {
+			#[allow(deprecated)]
+			let Some(channel_id) = ctx.interaction.channel_id else {
+				return Ok("❌ No channel context.".into());
+			};
+			let count = cleanup_count(options);
+			let deleted = ctx
+				.http
+				.delete_recent_messages_by(channel_id, bot_user_id, count)
+				.await?;
+			info!(deleted, %channel_id, "cleaned up bot messages");
+			format!("🧹 Deleted {} of my messages.", deleted)
+		}
+		Some(("register", _)) => {
+			let guild = ctx.http.send(GetGuild::new(guild_id)).await?;
+			let registered = ctx
+				.http
+				.send(SetGuildCommands::new(
+					app_id,
+					guild_id,
+					guild_slash_commands(roll, &guild.feature_names()),
+				))
+				.await?;
+			info!(
+				count = registered.len(),
+				%guild_id,
+				"registered guild commands"
+			);
+			format!("✅ Registered {} guild commands.", registered.len())
+		}
+		Some(("clear", _)) => {
+			let existing = ctx
+				.http
+				.send(GetGuildCommands::new(app_id, guild_id))
+				.await?;
+			ctx.http
+				.send(SetGuildCommands::new(app_id, guild_id, Vec::new()))
+				.await?;
+			info!(
+				count = existing.len(),
+				%guild_id,
+				"cleared guild commands"
+			);
+			format!("🧹 Cleared {} guild commands.", existing.len())
+		}
+		Some(("stats", _)) => stats_text(&metrics.top(STATS_TOP_COMMANDS)),
+		_ => "❌ Unknown `/admin` subcommand.".to_string(),
+	};
+	Ok(reply)
+}
+
+/// The most used commands with their invocation counts.
+fn stats_text(top: &[(String, u64)]) -> String {
+	if top.is_empty() {
+		return "📊 No commands used yet.".into();
+	}
+	let mut lines = vec!["📊 **Top commands**".to_string()];
+	for (i, (command, count)) in top.iter().enumerate() {
+		lines.push(format!("{}. `{}` — {}", i + 1, command, count));
+	}
+	lines.join("\n")
+}
+
+/// Name and options of the invoked subcommand, e.g. `register`.
+fn subcommand<'a>(
+	ctx: &CommandContext<'a>,
+) -> Option<(&'a str, &'a [CommandDataOption])> {
+	ctx.options.iter().find_map(|o| match &o.value {
+		CommandOptionValue::SubCommand(options) => {
+			Some((o.name.as_str(), options.as_slice()))
+		}
+		_ => None,
+	})
+}
+
+/// The `count` option of `/admin cleanup`.
+fn cleanup_count(options: &[CommandDataOption]) -> usize {
+	options
+		.iter()
+		.find(|o| o.name == "count")
+		.and_then(|o| match o.value {
+			CommandOptionValue::Integer(count) => usize::try_from(count).ok(),
+			_ => None,
+		})
+		.unwrap_or(CLEANUP_DEFAULT_COUNT)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn application(owner_id: u64, team_owner_id: Option<u64>) -> Application {
+		let team = team_owner_id.map(|id| {
+			serde_json::json!({
+				"icon": null,
+				"id": "50",
+				"members": [],
+				"name": "team",
+				"owner_user_id": id.to_string(),
+			})
+		});
+		serde_json::from_value(serde_json::json!({
+			"bot_public": true,
+			"bot_require_code_grant": false,
+			"description": "",
+			"id": "1",
+			"name": "hello-discord",
+			"verify_key": "key",
+			"owner": {
+				"id": owner_id.to_string(),
+				"username": "owner",
+				"discriminator": "0",
+				"avatar": null,
+			},
+			"team": team,
+		}))
+		.unwrap()
+	}
+
+	#[test]
+	fn owner_check_matches_owner_id() {
+		let app = application(10, None);
+		assert!(is_application_owner(&app, Id::new(10)));
+		assert!(!is_application_owner(&app, Id::new(11)));
+	}
+
+	#[test]
+	fn owner_check_uses_team_owner() {
+		// For team apps `owner` is a placeholder team user.
+		let app = application(10, Some(20));
+		assert!(is_application_owner(&app, Id::new(20)));
+		assert!(!is_application_owner(&app, Id::new(10)));
+	}
+
+	#[test]
+	fn cleanup_count_defaults_when_missing() {
+		assert_eq!(cleanup_count(&[]), CLEANUP_DEFAULT_COUNT);
+		let options = [CommandDataOption {
+			name: "count".into(),
+			value: CommandOptionValue::Integer(25),
+		}];
+		assert_eq!(cleanup_count(&options), 25);
+	}
+
+	#[test]
+	fn stats_text_lists_top_commands() {
+		let top = [("/ping".to_string(), 3), ("!roll".to_string(), 1)];
+		assert_eq!(
+			stats_text(&top),
+			"📊 **Top commands**\n1. `/ping` — 3\n2. `!roll` — 1"
+		);
+		assert!(stats_text(&[]).contains("No commands"));
+	}
+}
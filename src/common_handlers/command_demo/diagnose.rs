@@ -0,0 +1,161 @@
+//! `/diagnose` — why isn't the bot responding here? Reports the bot's
+//! permissions in the current channel and whether the privileged intents
+//! it relies on are requested and enabled in the Developer Portal.
+use crate::prelude::*;
+use twilight_model::gateway::Intents;
+use twilight_model::guild::Permissions;
+use twilight_model::id::Id;
+use twilight_model::id::marker::UserMarker;
+use twilight_model::oauth::ApplicationFlags;
+
+/// Permissions the bot needs to read a channel and reply in it.
+const REQUIRED_PERMISSIONS: Permissions = Permissions::VIEW_CHANNEL
+	.union(Permissions::SEND_MESSAGES)
+	.union(Permissions::EMBED_LINKS)
+	.union(Permissions::READ_MESSAGE_HISTORY);
+
+/// Privileged intents, with the application flags that enable them.
+const PRIVILEGED_INTENTS: [(&str, Intents, ApplicationFlags); 3] = [
+	(
+		"Message Content",
+		Intents::MESSAGE_CONTENT,
+		ApplicationFlags::GATEWAY_MESSAGE_CONTENT
+			.union(ApplicationFlags::GATEWAY_MESSAGE_CONTENT_LIMITED),
+	),
+	(
+		"Server Members",
+		Intents::GUILD_MEMBERS,
+		ApplicationFlags::GATEWAY_GUILD_MEMBERS
+			.union(ApplicationFlags::GATEWAY_GUILD_MEMBERS_LIMITED),
+	),
+	(
+		"Presence",
+		Intents::GUILD_PRESENCES,
+		ApplicationFlags::GATEWAY_PRESENCE
+			.union(ApplicationFlags::GATEWAY_PRESENCE_LIMITED),
+	),
+];
+
+/// Where the reported permissions came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PermissionSource {
+	/// `app_permissions` sent with the interaction.
+	Interaction,
+	/// Computed from the guild's roles and the channel's overwrites.
+	Computed,
+}
+
+/// Handles `/diagnose` for a bot connected with `intents`, replying
+/// ephemerally.
+pub(super) async fn diagnose(
+	ctx: &CommandContext<'_>,
+	bot_user_id: Id<UserMarker>,
+	bot_members: &BotMemberCache,
+	intents: Intents,
+) -> Result<(), HttpError> {
+	let permissions = match ctx.interaction.app_permissions {
+		Some(permissions) => Some((permissions, PermissionSource::Interaction)),
+		None => computed_permissions(ctx, bot_user_id, bot_members)
+			.await?
+			.map(|permissions| (permissions, PermissionSource::Computed)),
+	};
+	let app = ctx.http.send(GetCurrentApplication).await?;
+	let flags = app.flags.unwrap_or(ApplicationFlags::empty());
+	ctx.respond_ephemeral(diagnose_text(permissions, intents, flags))
+		.await
+}
+
+/// The bot's permissions in the interaction's channel, `None` outside a
+/// server. Discord normally sends these as `app_permissions`.
+async fn computed_permissions(
+	ctx: &CommandContext<'_>,
+	bot_user_id: Id<UserMarker>,
+	bot_members: &BotMemberCache,
+) -> Result<Option<Permissions>, HttpError> {
+	#[allow(deprecated)]
+	let (Some(guild_id), Some(channel_id)) =
+		(ctx.guild_id(), ctx.interaction.channel_id)
+	else {
+		return Ok(None);
+	};
+	let guild = ctx.http.send(GetGuild::new(guild_id)).await?;
+	let member = bot_members.get(ctx.http, guild_id, bot_user_id).await?;
+	let channel = ctx.http.send(GetChannel::new(channel_id)).await?;
+	let overwrites = channel.permission_overwrites.unwrap_or_default();
+	Ok(Some(guild.channel_permissions(&member, &overwrites)))
+}
+
+fn diagnose_text(
+	permissions: Option<(Permissions, PermissionSource)>,
+	intents: Intents,
+	flags: ApplicationFlags,
+) -> String {
+	let mut lines = vec!["🩺 **Diagnostics**".to_string()];
+	match permissions {
+		Some((permissions, source)) => {
+			let source = match source {
+				PermissionSource::Interaction => "reported by Discord",
+				PermissionSource::Computed => "computed from roles",
+			};
+			lines.push(format!(
+				"**Permissions here** ({}): {}",
+				source,
+				format_permissions(permissions)
+			));
+			let missing = REQUIRED_PERMISSIONS - permissions;
+			if missing.is_empty() {
+				lines.push("✅ I can read and reply in this channel.".into());
+			} else {
+				lines.push(format!(
+					"⚠️ **Missing:** {}",
+					format_permissions(missing)
+				));
+			}
+		}
+		None => lines.push("**Permissions:** not in a server channel.".into()),
+	}
+
+	lines.push("**Privileged intents:**".into());
+	for (name, intent, enabled_by) in PRIVILEGED_INTENTS {
+		let requested = intents.contains(intent);
+		let status = match (requested, flags.intersects(enabled_by)) {
+			(true, true) => "✅ requested and enabled",
+			(true, false) => {
+				"⚠️ requested but not enabled in the Developer Portal"
+			}
+			(false, _) => "➖ not requested",
+		};
+		lines.push(format!("• {}: {}", name, status));
+	}
+	lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn diagnose_lists_missing_permissions_and_intents() {
+		let text = diagnose_text(
+			Some((
+				Permissions::VIEW_CHANNEL | Permissions::READ_MESSAGE_HISTORY,
+				PermissionSource::Interaction,
+			)),
+			Intents::MESSAGE_CONTENT | Intents::GUILD_MEMBERS,
+			ApplicationFlags::GATEWAY_GUILD_MEMBERS_LIMITED,
+		);
+		assert!(text.contains("**Missing:** Send Messages, Embed Links"));
+		assert!(text.contains(
+			"Message Content: ⚠️ requested but not enabled in the Developer Portal"
+		));
+		assert!(text.contains("Server Members: ✅ requested and enabled"));
+		assert!(text.contains("Presence: ➖ not requested"));
+	}
+
+	#[test]
+	fn diagnose_outside_a_server() {
+		let text =
+			diagnose_text(None, Intents::empty(), ApplicationFlags::empty());
+		assert!(text.contains("not in a server channel"));
+	}
+}
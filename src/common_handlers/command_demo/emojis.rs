@@ -0,0 +1,175 @@
+//! `/emojis` and `!emojis` — page through the server's custom emojis, each
+//! with its CDN URL. The `emojis:<page>` buttons edit the list in place.
+use crate::prelude::*;
+use twilight_model::channel::message::Component;
+use twilight_model::guild::Emoji;
+use twilight_model::http::interaction::InteractionResponse;
+use twilight_model::http::interaction::InteractionResponseData;
+
+/// Emojis listed per page, keeping a page well under 2000 characters.
+const EMOJIS_PER_PAGE: usize = 15;
+
+/// One page of the emoji listing.
+#[derive(Debug)]
+pub(super) struct EmojiPage {
+	pub content: String,
+	/// Previous and next buttons, `None` when everything fits on one page.
+	pub buttons: Option<Component>,
+}
+
+impl EmojiPage {
+	/// Page `page` of `emojis`, clamped to the last page.
+	pub fn new(emojis: &[Emoji], page: usize) -> Self {
+		if emojis.is_empty() {
+			return Self {
+				content: "This server has no custom emojis.".into(),
+				buttons: None,
+			};
+		}
+		let pages = emojis.len().div_ceil(EMOJIS_PER_PAGE);
+		let page = page.min(pages - 1);
+		let mut lines = vec![format!(
+			"😀 **Server emojis** ({} total, page {}/{})",
+			emojis.len(),
+			page + 1,
+			pages
+		)];
+		lines.extend(
+			emojis
+				.iter()
+				.skip(page * EMOJIS_PER_PAGE)
+				.take(EMOJIS_PER_PAGE)
+				.map(|emoji| {
+					format!(
+						"{} `{}` {}",
+						emoji.mention(),
+						emoji.name,
+						emoji.url()
+					)
+				}),
+		);
+		let buttons = (pages > 1).then(|| {
+			action_row(vec![
+				page_button("◀ Prev", page.saturating_sub(1), page == 0),
+				page_button("Next ▶", page + 1, page + 1 == pages),
+			])
+		});
+		Self {
+			content: lines.join("\n"),
+			buttons,
+		}
+	}
+
+	fn into_data(self) -> InteractionResponseData {
+		let data =
+			InteractionResponseData::default().with_content(self.content);
+		match self.buttons {
+			Some(row) => data.with_components(vec![row]),
+			None => data,
+		}
+	}
+}
+
+fn page_button(label: &str, page: usize, disabled: bool) -> Component {
+	let mut button = button(2, label, format!("emojis:{}", page));
+	if let Component::Button(inner) = &mut button {
+		inner.disabled = disabled;
+	}
+	button
+}
+
+/// Handles `/emojis`, listing the first page.
+pub(super) async fn emojis(ctx: &CommandContext<'_>) -> Result<(), HttpError> {
+	let Some(guild_id) = ctx.guild_id() else {
+		return ctx
+			.respond_ephemeral("❌ This command only works in a server.")
+			.await;
+	};
+	let emojis = ctx.http.list_guild_emojis(guild_id).await?;
+	ctx.respond_with(InteractionResponse::message(
+		EmojiPage::new(&emojis, 0).into_data(),
+	))
+	.await
+}
+
+/// `emojis:<page>` — show another page, editing the listing.
+pub(super) fn emoji_page_clicked(ctx: ComponentContext) -> ComponentFuture {
+	Box::pin(async move {
+		let Some(guild_id) = ctx.interaction.guild_id else {
+			return Ok(());
+		};
+		let page = ctx.args().parse().unwrap_or(0);
+		// Refetched so added or removed emojis show up.
+		let emojis = ctx.http.list_guild_emojis(guild_id).await?;
+		ctx.respond(EmojiPage::new(&emojis, page).into_data()).await
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn emojis(count: u64) -> Vec<Emoji> {
+		(1..=count)
+			.map(|id| {
+				serde_json::from_value(serde_json::json!({
+					"id": id.to_string(),
+					"name": format!("emoji{}", id),
+					"animated": id % 2 == 0,
+				}))
+				.unwrap()
+			})
+			.collect()
+	}
+
+	fn button_states(page: &EmojiPage) -> Vec<(String, bool)> {
+		let Some(Component::ActionRow(row)) = &page.buttons else {
+			panic!("expected a button row");
+		};
+		row.components
+			.iter()
+			.map(|component| match component {
+				Component::Button(button) => (
+					button.custom_id.clone().unwrap_or_default(),
+					button.disabled,
+				),
+				other => panic!("expected a button, got {:?}", other),
+			})
+			.collect()
+	}
+
+	#[test]
+	fn listing_pages_through_emojis() {
+		let emojis = emojis(35);
+		let first = EmojiPage::new(&emojis, 0);
+		assert!(first.content.starts_with(
+			"😀 **Server emojis** (35 total, page 1/3)\n<:emoji1:1> `emoji1` \
+			 https://cdn.discordapp.com/emojis/1.png\n<a:emoji2:2>"
+		));
+		assert_eq!(first.content.lines().count(), 1 + EMOJIS_PER_PAGE);
+		assert_eq!(button_states(&first), [
+			("emojis:0".to_string(), true),
+			("emojis:1".to_string(), false),
+		]);
+
+		// Out-of-range pages show the last one.
+		let last = EmojiPage::new(&emojis, 7);
+		assert!(last.content.contains("page 3/3"));
+		assert_eq!(last.content.lines().count(), 1 + 5);
+		assert!(last.content.ends_with("emojis/35.png"));
+		assert_eq!(button_states(&last), [
+			("emojis:1".to_string(), false),
+			("emojis:3".to_string(), true),
+		]);
+	}
+
+	#[test]
+	fn single_page_and_empty_listings_have_no_buttons() {
+		let page = EmojiPage::new(&emojis(3), 0);
+		assert!(page.content.contains("page 1/1"));
+		assert!(page.buttons.is_none());
+		let empty = EmojiPage::new(&[], 0);
+		assert_eq!(empty.content, "This server has no custom emojis.");
+		assert!(empty.buttons.is_none());
+	}
+}
@@ -1,8 +1,12 @@
 use crate::prelude::*;
 use beet::prelude::*;
+use twilight_model::channel::message::MessageFlags;
+use twilight_model::gateway::presence::Activity;
+use twilight_model::gateway::presence::ActivityType;
 use twilight_model::gateway::presence::Status;
 use twilight_model::gateway::presence::UserOrId;
 use twilight_model::id::Id;
+use twilight_model::id::marker::ChannelMarker;
 use twilight_model::id::marker::UserMarker;
 
 /// State for the "greet users who come online" feature.
@@ -34,6 +38,7 @@ fn greet_users_coming_online(
 		&BotChannels,
 		&mut GreetState,
 		&DiscordHttpClient,
+		&HandlerLimit,
 	)>,
 ) -> Result {
 	if ev.status != Status::Online {
@@ -42,12 +47,14 @@ fn greet_users_coming_online(
 
 	let entity = ev.event_target();
 
+	// Presences with an empty or malformed user id fail to deserialize in
+	// `parse_gateway_event`, so this is always a valid snowflake.
 	let user_id = match &ev.user {
 		UserOrId::User(u) => u.id,
 		UserOrId::UserId { id } => *id,
 	};
 
-	let (bot_state, bot_channel, mut greet_state, http) =
+	let (bot_state, bot_channel, mut greet_state, http, limit) =
 		query.get_mut(entity)?;
 	// Skip if this is the bot itself.
 	if bot_state.user_id() == user_id {
@@ -64,22 +71,152 @@ fn greet_users_coming_online(
 
 	greet_state.greeted_users.insert(user_id);
 
+	let greeting = greeting_message(channel_id, user_id, ev.primary_activity());
 	let http = http.clone();
+	let limit = limit.clone();
 	info!(
 		user_id = %user_id,
 		channel_id = %channel_id,
 		"greeting user coming online"
 	);
 
-	commands.queue_async(async move |_| {
-		let greeting = format!(
-			"Welcome online, <@{}>! 🎉 Hope you're having a great day!",
-			user_id
-		);
-		http.send(CreateMessage::new(channel_id).content(&greeting))
-			.await?;
-		Ok(())
+	let guild_id = ev.guild_id;
+	commands.entity(entity).queue_async(async move |entity| {
+		let _permit = limit.acquire().await;
+		match http.send(greeting).await {
+			Err(err) if greet_channel_gone(&err) => {
+				warn!(%channel_id, "greet channel is gone, clearing it");
+				entity.trigger(move |entity| LeaveBotChannel {
+					entity,
+					guild: guild_id,
+					channel: channel_id,
+				});
+				Ok(())
+			}
+			Err(err) => Err(err.into()),
+			Ok(_) => Ok(()),
+		}
 	});
 
 	Ok(())
 }
+
+/// Whether a greeting failed because its channel no longer exists, in
+/// which case the channel is cleared rather than failing every greeting.
+fn greet_channel_gone(err: &HttpError) -> bool {
+	err.code() == Some(UNKNOWN_CHANNEL)
+}
+
+/// The greeting message for a user coming online. It is sent `@silent`, so
+/// the mention is highlighted without a push notification.
+fn greeting_message(
+	channel_id: Id<ChannelMarker>,
+	user_id: Id<UserMarker>,
+	activity: Option<&Activity>,
+) -> CreateMessage {
+	CreateMessage::new(channel_id)
+		.content(greeting_text(user_id, activity))
+		.flags(MessageFlags::SUPPRESS_NOTIFICATIONS.bits() as u32)
+}
+
+/// The greeting sent to a user coming online, mentioning what they're
+/// playing or streaming when known.
+fn greeting_text(
+	user_id: Id<UserMarker>,
+	activity: Option<&Activity>,
+) -> String {
+	match activity {
+		Some(a) if a.kind == ActivityType::Streaming => format!(
+			"Welcome online, <@{}>! 🎉 I see you're streaming **{}**!",
+			user_id, a.name
+		),
+		Some(a) => format!(
+			"Welcome online, <@{}>! 🎉 I see you're playing **{}**!",
+			user_id, a.name
+		),
+		None => format!(
+			"Welcome online, <@{}>! 🎉 Hope you're having a great day!",
+			user_id
+		),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn activity(name: &str, kind: u8) -> Activity {
+		serde_json::from_value(serde_json::json!({
+			"name": name,
+			"type": kind,
+		}))
+		.expect("valid activity JSON")
+	}
+
+	#[test]
+	fn greeting_without_activity() {
+		let text = greeting_text(Id::new(42), None);
+		assert!(text.contains("<@42>"));
+		assert!(text.contains("great day"));
+	}
+
+	#[test]
+	fn greeting_mentions_game() {
+		let game = activity("Minecraft", 0);
+		let text = greeting_text(Id::new(42), Some(&game));
+		assert!(text.contains("playing **Minecraft**"));
+	}
+
+	#[test]
+	fn greeting_mentions_stream() {
+		let stream = activity("Speedrun", 1);
+		let text = greeting_text(Id::new(42), Some(&stream));
+		assert!(text.contains("streaming **Speedrun**"));
+	}
+
+	#[test]
+	fn greeting_suppresses_notifications() {
+		let req = greeting_message(Id::new(1), Id::new(42), None)
+			.into_discord_request()
+			.unwrap();
+		let RequestBody::Json(body) = req.body else {
+			panic!("expected JSON body");
+		};
+		assert_eq!(body["flags"], 4096);
+		assert!(body["content"].as_str().unwrap().contains("<@42>"));
+	}
+
+	#[test]
+	fn unknown_channel_on_greet_clears_bot_channel() {
+		let err = HttpError::Api {
+			status: StatusCode::NOT_FOUND,
+			body: r#"{"message": "Unknown Channel", "code": 10003}"#.into(),
+			route: "POST /channels/10/messages".into(),
+			hint: None,
+		};
+		assert!(greet_channel_gone(&err));
+
+		let mut world = World::new();
+		let mut channels = BotChannels::default();
+		channels.insert(Id::new(1), Id::new(10));
+		let mut bot = world.spawn(channels);
+		bot.trigger(|entity| LeaveBotChannel {
+			entity,
+			guild: Id::new(1),
+			channel: Id::new(10),
+		});
+		assert!(bot.get::<BotChannels>().unwrap().is_empty());
+	}
+
+	#[test]
+	fn other_greet_errors_keep_bot_channel() {
+		let err = HttpError::Api {
+			status: StatusCode::FORBIDDEN,
+			body: r#"{"message": "Missing Access", "code": 50001}"#.into(),
+			route: "POST /channels/10/messages".into(),
+			hint: None,
+		};
+		assert!(!greet_channel_gone(&err));
+		assert!(!greet_channel_gone(&HttpError::Transport("reset".into())));
+	}
+}
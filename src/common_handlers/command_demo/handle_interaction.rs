@@ -1,5 +1,8 @@
 use crate::prelude::*;
 use beet::prelude::*;
+use super::RollConfig;
+use super::register_commands::feature_available;
+use super::register_commands::required_feature;
 use tracing::error;
 use tracing::info;
 use tracing::warn;
@@ -8,31 +11,61 @@ use twilight_model::application::interaction::InteractionData;
 use twilight_model::application::interaction::InteractionType;
 use twilight_model::application::interaction::application_command::CommandDataOption;
 use twilight_model::application::interaction::application_command::CommandOptionValue;
-use twilight_model::application::interaction::modal::ModalInteractionComponent;
 use twilight_model::channel::message::MessageFlags;
 use twilight_model::channel::message::component::SelectMenuOption;
 use twilight_model::channel::message::embed::Embed;
 use twilight_model::guild::Guild;
 use twilight_model::http::interaction::InteractionResponse;
 use twilight_model::http::interaction::InteractionResponseData;
+use twilight_model::id::Id;
+use twilight_model::id::marker::ChannelMarker;
 use twilight_model::user::User;
 
+/// Longest first-message preview `/first` and `!first` show, leaving room
+/// for the header and author line within a 2000 char message.
+pub(super) const FIRST_MESSAGE_PREVIEW_MAX: usize = 1800;
+/// Pages `/count full:true` scans, up to 100 000 messages.
+const COUNT_FULL_MAX_PAGES: usize = 1000;
+
 /// Observer called when any interaction (slash command, component, modal) is received.
 pub fn handle_interaction(
 	ev: On<DiscordInteraction>,
 	mut commands: Commands,
-	query: Query<(&BotState, &DiscordHttpClient)>,
+	query: Query<(
+		&BotState,
+		&DiscordHttpClient,
+		&HandlerLimit,
+		Option<&GatewayIntents>,
+		Option<&RollConfig>,
+		Option<&BotMemberCache>,
+		Option<&CommandMetrics>,
+	)>,
 ) -> Result {
 	let entity = ev.event_target();
 	let interaction = ev.interaction.clone();
 
-	let (bot_state, http) = query.get(entity)?;
-	let start_time = bot_state.start_time();
+	let (bot_state, http, limit, intents, roll, bot_members, metrics) =
+		query.get(entity)?;
+	let bot_state = bot_state.clone();
 	let http = http.clone();
-
-	commands.queue_async(async move |_| {
-		if let Err(e) =
-			dispatch_interaction(&http, &interaction, start_time).await
+	let limit = limit.clone();
+	let intents = intents.copied().unwrap_or_default();
+	let roll = roll.copied().unwrap_or_default();
+	let bot_members = bot_members.cloned().unwrap_or_default();
+	let metrics = metrics.cloned().unwrap_or_default();
+
+	commands.queue_async(async move |world| {
+		let _permit = limit.acquire().await;
+		let ctx = CommandContext::new(&http, &interaction).with_world(&world);
+		if let Err(e) = dispatch_interaction(
+			ctx,
+			&bot_state,
+			intents,
+			roll,
+			&bot_members,
+			&metrics,
+		)
+		.await
 		{
 			error!(error = %e, "failed to handle interaction");
 		}
@@ -42,13 +75,25 @@ pub fn handle_interaction(
 }
 
 async fn dispatch_interaction(
-	http: &DiscordHttpClient,
-	interaction: &Interaction,
-	start_time: std::time::Instant,
+	ctx: CommandContext<'_>,
+	bot_state: &BotState,
+	intents: GatewayIntents,
+	roll: RollConfig,
+	bot_members: &BotMemberCache,
+	metrics: &CommandMetrics,
 ) -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>> {
+	let (http, interaction) = (ctx.http, ctx.interaction);
 	match interaction.kind {
 		InteractionType::ApplicationCommand => {
-			handle_slash_command(http, interaction, start_time).await
+			handle_slash_command(
+				ctx,
+				bot_state,
+				intents,
+				roll,
+				bot_members,
+				metrics,
+			)
+			.await
 		}
 		InteractionType::MessageComponent => {
 			handle_component(http, interaction).await
@@ -85,6 +130,16 @@ fn command_info(
 	}
 }
 
+fn get_option_bool(options: &[CommandDataOption], name: &str) -> Option<bool> {
+	options
+		.iter()
+		.find(|o| o.name == name)
+		.and_then(|o| match &o.value {
+			CommandOptionValue::Boolean(v) => Some(*v),
+			_ => None,
+		})
+}
+
 fn get_option_u64(options: &[CommandDataOption], name: &str) -> Option<u64> {
 	options
 		.iter()
@@ -97,18 +152,55 @@ fn get_option_u64(options: &[CommandDataOption], name: &str) -> Option<u64> {
 }
 
 async fn handle_slash_command(
-	http: &DiscordHttpClient,
-	interaction: &Interaction,
-	start_time: std::time::Instant,
+	ctx: CommandContext<'_>,
+	bot_state: &BotState,
+	intents: GatewayIntents,
+	roll: RollConfig,
+	bot_members: &BotMemberCache,
+	metrics: &CommandMetrics,
 ) -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>> {
+	let (http, interaction) = (ctx.http, ctx.interaction);
 	let (name, options) =
 		command_info(interaction).ok_or("missing interaction data")?;
+	metrics.record(&format!("/{}", name));
+
+	// Globally registered commands reach guilds without the feature they
+	// need, so check it here. This costs a guild fetch per invocation of
+	// a gated command, which are few and rarely used.
+	if let Some(feature) = required_feature(name) {
+		let features = match interaction.guild_id {
+			Some(guild_id) => match http.send(GetGuild::new(guild_id)).await {
+				Ok(guild) => guild.feature_names(),
+				Err(e) => {
+					warn!(error = %e, %guild_id, "failed to fetch guild features");
+					ctx.respond_with(error_response(format!(
+						"❌ Couldn't check whether `/{}` is available here.",
+						name
+					)))
+					.await?;
+					return Ok(());
+				}
+			},
+			None => Vec::new(),
+		};
+		if !feature_available(Some(feature), &features) {
+			ctx.respond_ephemeral(format!(
+				"❌ `/{}` isn't available in this server.",
+				name
+			))
+			.await?;
+			return Ok(());
+		}
+	}
 
 	let response = match name {
-		"ping" => text_response("🏓 Pong!"),
+		"ping" => {
+			ping(&ctx).await?;
+			return Ok(());
+		}
 
 		"uptime" => {
-			let elapsed = start_time.elapsed();
+			let elapsed = bot_state.start_time().elapsed();
 			let secs = elapsed.as_secs();
 			text_response(format!(
 				"⏱️ Bot uptime: {}h {}m {}s",
@@ -120,7 +212,7 @@ async fn handle_slash_command(
 
 		"roll" => {
 			let sides = get_option_u64(options, "sides").unwrap_or(6) as u32;
-			let sides = sides.max(2).min(1000);
+			let sides = roll.clamp(sides);
 			let result = (rand::random::<u32>() % sides) + 1;
 			let text = format!("🎲 Rolling a d{}... **{}**!", sides, result);
 
@@ -135,47 +227,31 @@ async fn handle_slash_command(
 			)
 		}
 
-		"serverinfo" => {
-			let text = if let Some(guild_id) = interaction.guild_id {
-				match http.send(GetGuild::new(guild_id)).await {
-					Ok(guild) => format_guild_info(&guild),
-					Err(e) => format!("❌ Error: {}", e),
-				}
-			} else {
-				"❌ This command only works in a server.".to_string()
-			};
-			text_response(text)
-		}
+		"serverinfo" => match interaction.guild_id {
+			Some(guild_id) => match http.send(GetGuild::new(guild_id)).await {
+				Ok(guild) => InteractionResponse::message(
+					InteractionResponseData::default()
+						.with_embeds(vec![guild_info_embed(&guild)]),
+				),
+				Err(e) => error_response(format!("❌ Error: {}", e)),
+			},
+			None => error_response("❌ This command only works in a server."),
+		},
 
 		"whoami" => {
-			let text = match interaction.author() {
-				Some(user) => format_whoami(user),
-				None => "❌ Couldn't determine your user info.".to_string(),
-			};
-			text_response(text)
+			whoami(&ctx).await?;
+			return Ok(());
 		}
 
 		"count" => {
-			#[allow(deprecated)]
-			let text = if let Some(ch_id) = interaction.channel_id {
-				match http.count_messages(ch_id).await {
-					Ok(count) => {
-						format!(
-							"📊 This channel has approximately **{}** messages.",
-							count
-						)
-					}
-					Err(e) => format!("❌ Error: {}", e),
-				}
-			} else {
-				"❌ No channel context.".to_string()
-			};
-			text_response(text)
+			count(&ctx).await?;
+			return Ok(());
 		}
 
 		"first" => {
 			#[allow(deprecated)]
-			let text = if let Some(ch_id) = interaction.channel_id {
+			let channel_id = interaction.channel_id;
+			if let Some(ch_id) = channel_id {
 				match http.get_first_message(ch_id).await {
 					Ok(first_msg) => {
 						let ts_str = first_msg.timestamp.iso_8601().to_string();
@@ -186,40 +262,54 @@ async fn handle_slash_command(
 						} else {
 							ts_str
 						};
-						format!(
+						text_response(format!(
 							"📜 **First message in this channel:**\n> {}\n— *{}* on {}",
-							first_msg.content, first_msg.author.name, ts
-						)
+							truncate_markdown_safe(
+								&first_msg.content,
+								FIRST_MESSAGE_PREVIEW_MAX,
+							),
+							first_msg.author.name,
+							ts
+						))
 					}
-					Err(e) => format!("❌ Error: {}", e),
+					Err(e) => error_response(format!("❌ Error: {}", e)),
 				}
 			} else {
-				"❌ No channel context.".to_string()
-			};
-			text_response(text)
+				error_response("❌ No channel context.")
+			}
 		}
 
 		"help" => text_response(help_text()),
 
-		"report" => InteractionResponse::modal(
-			InteractionResponseData::default()
-				.with_title("📝 Submit a Report")
-				.with_custom_id("report_modal")
-				.with_components(vec![
-					action_row(vec![text_input(
-						"report_subject",
-						"Subject",
-						1,
-						true,
-					)]),
-					action_row(vec![text_input(
-						"report_body",
-						"Description",
-						2,
-						true,
-					)]),
-				]),
-		),
+		// Needs the `Reminders` component, handled by `schedule_reminder`.
+		"remindme" => return Ok(()),
+
+		"admin" => {
+			super::admin::admin(&ctx, bot_state.user_id(), roll, metrics)
+				.await?;
+			return Ok(());
+		}
+
+		"emojis" => {
+			super::emojis::emojis(&ctx).await?;
+			return Ok(());
+		}
+
+		"diagnose" => {
+			super::diagnose::diagnose(
+				&ctx,
+				bot_state.user_id(),
+				bot_members,
+				intents.0,
+			)
+			.await?;
+			return Ok(());
+		}
+
+		"report" => ModalBuilder::new("report_modal", "📝 Submit a Report")
+			.text_input("report_subject", "Subject", 1, true)
+			.text_input("report_body", "Description", 2, true)
+			.build()?,
 
 		"send-logo" => {
 			let ack = InteractionResponse::defer();
@@ -315,7 +405,7 @@ async fn handle_slash_command(
 
 		_ => {
 			info!(command = name, "unknown slash command");
-			text_response(format!("Unknown command: `/{}`", name))
+			error_response(format!("Unknown command: `/{}`", name))
 		}
 	};
 
@@ -332,34 +422,35 @@ async fn handle_slash_command(
 // Component interaction handler
 // ---------------------------------------------------------------------------
 
-fn component_info(interaction: &Interaction) -> Option<(&str, &[String])> {
-	match interaction.data.as_ref()? {
-		InteractionData::MessageComponent(data) => {
-			Some((&data.custom_id, &data.values))
-		}
-		_ => None,
-	}
+/// Routes component custom ids to their handlers.
+fn component_registry() -> ComponentRegistry {
+	ComponentRegistry::default()
+		.with("reroll", reroll)
+		.with("emojis", super::emojis::emoji_page_clicked)
+		.with("language_select", show_selection)
+		.with_fallback(show_selection)
 }
 
 async fn handle_component(
 	http: &DiscordHttpClient,
 	interaction: &Interaction,
 ) -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>> {
-	let (custom_id, values) =
-		component_info(interaction).ok_or("missing interaction data")?;
+	let ctx = ComponentContext::new(http.clone(), interaction.clone())
+		.ok_or("missing interaction data")?;
+	component_registry().dispatch(ctx).await?;
+	Ok(())
+}
 
-	if custom_id.starts_with("reroll:") {
-		let sides: u32 = custom_id
-			.strip_prefix("reroll:")
-			.and_then(|s| s.parse().ok())
-			.unwrap_or(6)
-			.max(2)
-			.min(1000);
+/// `reroll:<sides>` — roll again, replacing the original message.
+fn reroll(ctx: ComponentContext) -> ComponentFuture {
+	Box::pin(async move {
+		// The id comes from a roll already clamped to the bot's RollConfig.
+		let sides: u32 = ctx.args().parse().unwrap_or(6).max(1);
 
 		let result = (rand::random::<u32>() % sides) + 1;
 		let text = format!("🎲 Rolling a d{}... **{}**!", sides, result);
 
-		let response = InteractionResponse::update(
+		ctx.respond(
 			InteractionResponseData::default()
 				.with_content(text)
 				.with_components(vec![action_row(vec![button(
@@ -367,82 +458,41 @@ async fn handle_component(
 					"🎲 Reroll",
 					format!("reroll:{}", sides),
 				)])]),
-		);
-
-		http.send(CreateInteractionResponse::new(
-			interaction.id,
-			interaction.token.clone(),
-			response,
-		))
-		.await?;
-	} else if !values.is_empty() {
-		let selected = values.join(", ");
-		let text = format!("You selected: **{}**", selected);
-		let response = InteractionResponse::message(
-			InteractionResponseData::default()
-				.with_content(text)
-				.with_flags(MessageFlags::EPHEMERAL),
-		);
-		http.send(CreateInteractionResponse::new(
-			interaction.id,
-			interaction.token.clone(),
-			response,
-		))
-		.await?;
-	} else {
-		info!(custom_id, "unhandled component interaction");
-	}
+		)
+		.await
+	})
+}
 
-	Ok(())
+/// Echo the chosen values of any select menu back to the user.
+fn show_selection(ctx: ComponentContext) -> ComponentFuture {
+	Box::pin(async move {
+		if ctx.values.is_empty() {
+			info!(
+				custom_id = ctx.custom_id,
+				"unhandled component interaction"
+			);
+			return Ok(());
+		}
+		let selected = ctx.values.join(", ");
+		ctx.respond_ephemeral(format!("You selected: **{}**", selected))
+			.await
+	})
 }
 
 // ---------------------------------------------------------------------------
 // Modal submit handler
 // ---------------------------------------------------------------------------
 
-fn modal_text_inputs(
-	interaction: &Interaction,
-) -> Option<(String, Vec<(String, String)>)> {
-	match interaction.data.as_ref()? {
-		InteractionData::ModalSubmit(data) => {
-			let custom_id = data.custom_id.clone();
-			let mut inputs = Vec::new();
-			for row in &data.components {
-				if let ModalInteractionComponent::ActionRow(action_row) = row {
-					for component in &action_row.components {
-						if let ModalInteractionComponent::TextInput(ti) =
-							component
-						{
-							inputs
-								.push((ti.custom_id.clone(), ti.value.clone()));
-						}
-					}
-				}
-			}
-			Some((custom_id, inputs))
-		}
-		_ => None,
-	}
-}
-
 async fn handle_modal_submit(
 	http: &DiscordHttpClient,
 	interaction: &Interaction,
 ) -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>> {
-	let (custom_id, text_inputs) =
-		modal_text_inputs(interaction).ok_or("missing interaction data")?;
-
-	if custom_id == "report_modal" {
-		let mut subject = String::new();
-		let mut body = String::new();
-
-		for (id, value) in &text_inputs {
-			match id.as_str() {
-				"report_subject" => subject = value.clone(),
-				"report_body" => body = value.clone(),
-				_ => {}
-			}
-		}
+	let values = ModalValues::from_interaction(interaction)
+		.ok_or("missing interaction data")?;
+
+	if values.custom_id == "report_modal" {
+		let subject = values.text("report_subject").unwrap_or_default();
+		let body = values.text("report_body").unwrap_or_default();
 
 		let author_name = interaction
 			.author()
@@ -450,9 +500,15 @@ async fn handle_modal_submit(
 			.unwrap_or_else(|| "Unknown".to_string());
 
 		let embed = Embed::new()
-			.with_title(format!("📝 Report: {}", subject))
-			.with_description(&body)
-			.with_color(0xFF6600)
+			.with_title(truncate_markdown_safe(
+				&format!("📝 Report: {}", subject),
+				EMBED_TITLE_MAX,
+			))
+			.with_description(truncate_markdown_safe(
+				body,
+				EMBED_DESCRIPTION_MAX,
+			))
+			.with_color(colors::WARNING_ORANGE)
 			.with_footer(format!("Submitted by {}", author_name))
 			.with_timestamp(chrono::Utc::now().to_rfc3339());
 
@@ -477,14 +533,164 @@ async fn handle_modal_submit(
 // Response helpers
 // ---------------------------------------------------------------------------
 
+async fn ping(ctx: &CommandContext<'_>) -> Result<(), HttpError> {
+	ctx.respond("🏓 Pong!").await
+}
+
+async fn whoami(ctx: &CommandContext<'_>) -> Result<(), HttpError> {
+	match ctx.author() {
+		Some(user) => ctx.respond(format_whoami(user)).await,
+		None => {
+			ctx.respond_ephemeral("❌ Couldn't determine your user info.")
+				.await
+		}
+	}
+}
+
+/// `/count [full]`. A full count lifts the 10 000 message cap, so it
+/// warns first and edits the result in when the scan finishes.
+async fn count(ctx: &CommandContext<'_>) -> Result<(), HttpError> {
+	#[allow(deprecated)]
+	let Some(channel_id) = ctx.interaction.channel_id else {
+		return ctx.respond_ephemeral("❌ No channel context.").await;
+	};
+	if get_option_bool(ctx.options, "all").unwrap_or(false) {
+		return count_guild(ctx).await;
+	}
+	if !get_option_bool(ctx.options, "full").unwrap_or(false) {
+		let result = ctx.http.count_messages(channel_id).await;
+		let failed = result.is_err();
+		let text = count_text(result);
+		return if failed {
+			ctx.respond_ephemeral(text).await
+		} else {
+			ctx.respond(text).await
+		};
+	}
+
+	ctx.respond(
+		"⏳ Counting every message. On a large channel this can take \
+		 minutes and uses a lot of API requests.",
+	)
+	.await?;
+	let result = ctx
+		.http
+		.count_messages_with_limit(channel_id, COUNT_FULL_MAX_PAGES)
+		.await;
+	ctx.http
+		.send(
+			EditOriginalInteractionResponse::new(
+				ctx.interaction.application_id,
+				ctx.interaction.token.clone(),
+			)
+			.content(count_text(result)),
+		)
+		.await?;
+	Ok(())
+}
+
+/// `/count all:true` — sum the counts of the server's text channels,
+/// several channels at a time.
+async fn count_guild(ctx: &CommandContext<'_>) -> Result<(), HttpError> {
+	let Some(guild_id) = ctx.guild_id() else {
+		return ctx.respond_ephemeral("❌ `all` only works in a server.").await;
+	};
+	ctx.respond("⏳ Counting messages in every channel of this server.")
+		.await?;
+	let text = match ctx.http.send(GetGuildChannels::new(guild_id)).await {
+		Ok(channels) => {
+			let channel_ids: Vec<_> = channels
+				.iter()
+				.filter(|channel| channel.kind.is_text_like())
+				.map(|channel| channel.id)
+				.collect();
+			let counts = ctx
+				.http
+				.count_messages_in_channels(
+					&channel_ids,
+					COUNT_DEFAULT_CONCURRENCY,
+				)
+				.await;
+			guild_count_text(&counts)
+		}
+		Err(e) => format!("❌ Error: {}", e),
+	};
+	ctx.http
+		.send(
+			EditOriginalInteractionResponse::new(
+				ctx.interaction.application_id,
+				ctx.interaction.token.clone(),
+			)
+			.content(text),
+		)
+		.await?;
+	Ok(())
+}
+
+/// Total of the channels that could be counted, noting the ones that
+/// couldn't, usually for lack of access.
+fn guild_count_text(
+	counts: &[(Id<ChannelMarker>, Result<usize, HttpError>)],
+) -> String {
+	let total: usize =
+		counts.iter().filter_map(|(_, count)| count.as_ref().ok()).sum();
+	let skipped = counts.iter().filter(|(_, count)| count.is_err()).count();
+	let mut text = format!(
+		"📊 This server has approximately **{}** messages across {} channels.",
+		total,
+		counts.len() - skipped
+	);
+	if skipped > 0 {
+		text.push_str(&format!(
+			"\n⚠️ Skipped {} channels I couldn't read.",
+			skipped
+		));
+	}
+	text
+}
+
+fn count_text(result: Result<usize, HttpError>) -> String {
+	match result {
+		Ok(count) => {
+			format!("📊 This channel has approximately **{}** messages.", count)
+		}
+		Err(e) => format!("❌ Error: {}", e),
+	}
+}
+
 fn text_response(text: impl Into<String>) -> InteractionResponse {
 	InteractionResponse::text(text)
 }
 
+/// A failed command's reply, a red embed that is ephemeral so errors
+/// don't clutter the channel or show their details to everyone.
+fn error_response(text: impl Into<String>) -> InteractionResponse {
+	let embed = Embed::new()
+		.with_description(text)
+		.with_color(colors::ERROR_RED);
+	InteractionResponse::message(
+		InteractionResponseData::default()
+			.with_embeds(vec![embed])
+			.with_flags(MessageFlags::EPHEMERAL),
+	)
+}
+
 // ---------------------------------------------------------------------------
 // Formatting helpers
 // ---------------------------------------------------------------------------
 
+/// The server info card for `!serverinfo` and `/serverinfo`, with the
+/// guild's icon as the thumbnail.
+pub(super) fn guild_info_embed(guild: &Guild) -> Embed {
+	let embed = Embed::new()
+		.with_description(format_guild_info(guild))
+		.with_color(colors::DISCORD_BLURPLE);
+	match guild.icon_url() {
+		Some(icon_url) => embed.with_thumbnail(icon_url),
+		None => embed,
+	}
+}
+
 fn format_guild_info(guild: &Guild) -> String {
 	let member_count = guild
 		.approximate_member_count
@@ -501,13 +707,17 @@ fn format_guild_info(guild: &Guild) -> String {
 		.map(|dt| dt.format("%B %d, %Y").to_string())
 		.unwrap_or_else(|| "unknown".to_string());
 
-	format!(
+	let mut text = format!(
 		"🏰 **Server Info: {}**\n\
          • **Members:** {} ({} online)\n\
          • **Owner:** <@{}>\n\
          • **Created:** {}",
 		guild.name, member_count, online_count, owner_str, created_at
-	)
+	);
+	if let Some(vanity_url) = guild.vanity_url() {
+		text.push_str(&format!("\n• **Invite:** {}", vanity_url));
+	}
+	text
 }
 
 fn format_whoami(user: &User) -> String {
@@ -525,7 +735,8 @@ fn format_whoami(user: &User) -> String {
 	)
 }
 
-fn help_text() -> String {
+/// Shared by `/help` and `!help` so the two lists can't drift apart.
+pub(super) fn help_text() -> String {
 	"🤖 **Available Commands:**\n\
      *Prefix commands (! or @mention):*\n\
      • `!hello` — Say hello!\n\
@@ -536,13 +747,24 @@ fn help_text() -> String {
      • `!first` — Show the first message ever sent in this channel\n\
      • `!serverinfo` — Show server information\n\
      • `!whoami` — Show info about yourself\n\
+     • `!roles [@user]` — List a member's roles\n\
+     • `!avatar [@user]` — Show a full-size avatar\n\
+     • `!emojis` — List this server's custom emojis\n\
+     • `!poll \"Q\" \"A1\" \"A2\"` — Start a poll\n\
      • `!help` — Show this help message\n\
      \n\
      *Slash commands:*\n\
-     • `/ping` `/uptime` `/roll` `/serverinfo` `/whoami` `/count` `/first` `/help`\n\
+     • `/ping` `/uptime` `/roll` `/serverinfo` `/whoami` `/count` `/first` `/emojis` `/help`\n\
+     • `/count full:true` — Count past the 10 000 message cap (slow)\n\
+     • `/count all:true` — Count every channel in this server\n\
      • `/report` — Submit a report via a pop-up form\n\
+     • `/remindme duration text` — Get reminded after `10m`, `2h`, `1d`…\n\
      • `/send-logo` — Send the bot logo\n\
-     • `/demo-select` — Demo the select menu component"
+     • `/demo-select` — Demo the select menu component\n\
+     • `/diagnose` — Check my permissions here and enabled intents\n\
+     • `/admin register|clear` — Owner only: manage this server's commands\n\
+     • `/admin cleanup count` — Owner only: delete my last messages here\n\
+     • `/admin stats` — Owner only: show the most used commands"
 		.to_string()
 }
 
@@ -584,6 +806,35 @@ mod tests {
 		assert!(data.flags.is_none());
 	}
 
+	// -- error_response() --------------------------------------------------
+
+	#[test]
+	fn error_response_is_ephemeral() {
+		let resp = error_response("❌ Error: boom");
+		assert_eq!(resp.kind, InteractionResponseType::ChannelMessageWithSource);
+		let data = resp.data.unwrap();
+		let embed = &data.embeds.as_ref().unwrap()[0];
+		assert_eq!(embed.description.as_deref(), Some("❌ Error: boom"));
+		assert_eq!(embed.color, Some(colors::ERROR_RED));
+		assert_eq!(data.flags, Some(MessageFlags::EPHEMERAL));
+		assert!(text_response("ok").data.unwrap().flags.is_none());
+	}
+
+	// -- guild_count_text() ------------------------------------------------
+
+	#[test]
+	fn guild_count_text_sums_readable_channels() {
+		let counts = [
+			(Id::new(1), Ok(120)),
+			(Id::new(2), Err(HttpError::Transport("403".into()))),
+			(Id::new(3), Ok(30)),
+		];
+		let text = guild_count_text(&counts);
+		assert!(text.contains("**150** messages across 2 channels"), "{}", text);
+		assert!(text.contains("Skipped 1 channels"), "{}", text);
+		assert!(!guild_count_text(&counts[..1]).contains("Skipped"));
+	}
+
 	// -- format_guild_info() -----------------------------------------------
 
 	#[test]
@@ -619,6 +870,71 @@ mod tests {
 		assert!(text.contains("<@456>"), "missing owner mention");
 	}
 
+	#[test]
+	fn format_guild_info_shows_vanity_url() {
+		let mut guild: Guild = serde_json::from_value(serde_json::json!({
+			"id": "1",
+			"name": "Vanity",
+			"icon": null,
+			"owner_id": "1",
+			"channels": [],
+			"members": [],
+			"roles": [],
+			"emojis": [],
+			"features": ["VANITY_URL"],
+			"vanity_url_code": "rust",
+			"afk_timeout": 300,
+			"preferred_locale": "en-US",
+			"premium_progress_bar_enabled": false,
+			"verification_level": 0,
+			"default_message_notifications": 0,
+			"explicit_content_filter": 0,
+			"mfa_level": 0,
+			"premium_tier": 0,
+			"nsfw_level": 0,
+			"system_channel_flags": 0,
+		}))
+		.expect("valid guild JSON");
+		let text = format_guild_info(&guild);
+		assert!(text.contains("https://discord.gg/rust"), "{}", text);
+		guild.features.clear();
+		assert!(!format_guild_info(&guild).contains("discord.gg"));
+	}
+
+	#[test]
+	fn guild_info_embed_uses_icon_as_thumbnail() {
+		let mut guild: Guild = serde_json::from_value(serde_json::json!({
+			"id": "1",
+			"name": "Iconic",
+			"icon": "a_1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d",
+			"owner_id": "1",
+			"channels": [],
+			"members": [],
+			"roles": [],
+			"emojis": [],
+			"features": [],
+			"afk_timeout": 300,
+			"preferred_locale": "en-US",
+			"premium_progress_bar_enabled": false,
+			"verification_level": 0,
+			"default_message_notifications": 0,
+			"explicit_content_filter": 0,
+			"mfa_level": 0,
+			"premium_tier": 0,
+			"nsfw_level": 0,
+			"system_channel_flags": 0,
+		}))
+		.expect("valid guild JSON");
+		let embed = guild_info_embed(&guild);
+		assert_eq!(
+			embed.thumbnail.unwrap().url,
+			"https://cdn.discordapp.com/icons/1/a_1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d.gif"
+		);
+		assert!(embed.description.unwrap().contains("Iconic"));
+		guild.icon = None;
+		assert!(guild_info_embed(&guild).thumbnail.is_none());
+	}
+
 	#[test]
 	fn format_guild_info_handles_missing_counts() {
 		let guild: Guild = serde_json::from_value(serde_json::json!({
@@ -668,6 +984,104 @@ mod tests {
 		assert!(text.contains("789"), "missing user id");
 	}
 
+	// -- CommandMetrics ----------------------------------------------------
+
+	fn ping_interaction() -> Interaction {
+		serde_json::from_value(serde_json::json!({
+			"id": "1",
+			"application_id": "2",
+			"type": 2,
+			"token": "tok",
+			"authorizing_integration_owners": {},
+			"entitlements": [],
+			"data": { "id": "3", "name": "ping", "type": 1 },
+		}))
+		.expect("valid interaction JSON")
+	}
+
+	#[test]
+	fn dispatching_ping_twice_counts_two() {
+		let interaction = ping_interaction();
+		let http = DiscordHttpClient::new("token")
+			.dry_run(true)
+			.with_dry_run_log(true);
+		let metrics = CommandMetrics::default();
+		let mut world = World::new();
+		let mut bot = world.spawn_empty();
+		bot.observe(common_handlers::init_bot_state);
+		bot.trigger(DiscordReady::create(crate::test_fixtures::ready("9")));
+		let bot_state = bot.get::<BotState>().unwrap().clone();
+		for _ in 0..2 {
+			futures_lite::future::block_on(dispatch_interaction(
+				CommandContext::new(&http, &interaction),
+				&bot_state,
+				GatewayIntents::default(),
+				RollConfig::default(),
+				&BotMemberCache::default(),
+				&metrics,
+			))
+			.unwrap();
+		}
+		assert_eq!(metrics.count("/ping"), 2);
+		let sent = http.take_dry_run_log();
+		assert_eq!(sent.len(), 2);
+		assert!(
+			sent.iter()
+				.all(|req| req.path == "interactions/1/tok/callback")
+		);
+	}
+
+	// -- HandlerLimit ------------------------------------------------------
+
+	/// Update `app` until `done`, giving queued async work time to run.
+	fn update_until(app: &mut App, done: impl Fn() -> bool) {
+		for _ in 0..100 {
+			if done() {
+				return;
+			}
+			app.update();
+			std::thread::sleep(std::time::Duration::from_millis(1));
+		}
+	}
+
+	#[test]
+	fn interactions_run_concurrently_up_to_handler_limit() {
+		let mut app = App::new();
+		app.add_plugins((MinimalPlugins, AsyncPlugin::default()));
+		let limit = HandlerLimit::new(2);
+		let metrics = CommandMetrics::default();
+		let mut bot = app.world_mut().spawn((
+			DiscordHttpClient::new("token").dry_run(true),
+			limit.clone(),
+			metrics.clone(),
+		));
+		bot.observe(common_handlers::init_bot_state);
+		bot.trigger(DiscordReady::create(crate::test_fixtures::ready("9")));
+		bot.observe(handle_interaction);
+		let bot = bot.id();
+		let ping = |app: &mut App| {
+			app.world_mut()
+				.entity_mut(bot)
+				.trigger(DiscordInteraction::create(ping_interaction()));
+		};
+
+		// A slow handler holds one slot, the next event still runs.
+		let slow = futures_lite::future::block_on(limit.acquire());
+		ping(&mut app);
+		update_until(&mut app, || metrics.count("/ping") == 1);
+		assert_eq!(metrics.count("/ping"), 1);
+
+		// With every slot busy the handler waits for one to free up.
+		let slower = futures_lite::future::block_on(limit.acquire());
+		ping(&mut app);
+		update_until(&mut app, || false);
+		assert_eq!(metrics.count("/ping"), 1);
+
+		drop((slow, slower));
+		update_until(&mut app, || metrics.count("/ping") == 2);
+		assert_eq!(metrics.count("/ping"), 2);
+	}
+
 	// -- help_text() -------------------------------------------------------
 
 	#[test]
@@ -682,12 +1096,24 @@ mod tests {
 			"!first",
 			"!serverinfo",
 			"!whoami",
+			"!roles",
+			"!avatar",
+			"!emojis",
+			"!poll",
 			"!help",
 		] {
 			assert!(text.contains(cmd), "help text missing {}", cmd);
 		}
 	}
 
+	#[test]
+	fn help_text_mentions_slash_only_commands() {
+		let text = help_text();
+		for cmd in &["/report", "/remindme", "/diagnose", "/admin"] {
+			assert!(text.contains(cmd), "help text missing {}", cmd);
+		}
+	}
+
 	// -- get_option_u64() --------------------------------------------------
 
 	#[test]
@@ -716,12 +1142,8 @@ mod tests {
 
 	#[test]
 	fn reroll_sides_clamped_correctly() {
-		let raw: u32 = 1u32;
-		let clamped = raw.max(2).min(1000);
-		assert_eq!(clamped, 2);
-
-		let raw: u32 = 5000u32;
-		let clamped = raw.max(2).min(1000);
-		assert_eq!(clamped, 1000);
+		let roll = RollConfig::default();
+		assert_eq!(roll.clamp(1), 2);
+		assert_eq!(roll.clamp(5000), 1000);
 	}
 }
@@ -0,0 +1,67 @@
+use crate::prelude::*;
+use beet::prelude::*;
+use tracing::debug;
+use tracing::info;
+use twilight_model::id::Id;
+use twilight_model::id::marker::UserMarker;
+
+/// Observer for dispatches twilight-model can't parse yet.
+///
+/// An example of forward-compatible handling: most user-scoped events carry
+/// a `user_id` (like `TYPING_START`) or a `user` object, so log who
+/// triggered the event even without a typed model for it.
+pub fn log_unknown_dispatch(ev: On<DiscordUnknownDispatch>) {
+	match dispatch_user_id(&ev) {
+		Some(user_id) => info!(
+			event = %ev.event_name,
+			user_id = %user_id,
+			"unknown dispatch from user"
+		),
+		None => debug!(event = %ev.event_name, "unknown dispatch"),
+	}
+}
+
+/// The user behind an unknown dispatch, read from `d.user_id` or
+/// `d.user.id`.
+fn dispatch_user_id(unknown: &UnknownDispatch) -> Option<Id<UserMarker>> {
+	let data = unknown.data.as_ref()?;
+	let raw = data
+		.get("user_id")
+		.or_else(|| data.get("user")?.get("id"))?
+		.as_str()?;
+	raw.parse::<u64>().ok().and_then(Id::new_checked)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn unknown(data: serde_json::Value) -> UnknownDispatch {
+		UnknownDispatch {
+			event_name: "SOME_FUTURE_EVENT".to_string(),
+			sequence: Some(1),
+			data: Some(data),
+		}
+	}
+
+	#[test]
+	fn dispatch_user_id_reads_user_id_field() {
+		let unknown = unknown(serde_json::json!({"user_id": "42"}));
+		assert_eq!(dispatch_user_id(&unknown), Some(Id::new(42)));
+	}
+
+	#[test]
+	fn dispatch_user_id_reads_user_object() {
+		let unknown = unknown(serde_json::json!({"user": {"id": "7"}}));
+		assert_eq!(dispatch_user_id(&unknown), Some(Id::new(7)));
+	}
+
+	#[test]
+	fn dispatch_user_id_missing() {
+		assert_eq!(dispatch_user_id(&unknown(serde_json::json!({}))), None);
+		assert_eq!(
+			dispatch_user_id(&unknown(serde_json::json!({"user_id": "0"}))),
+			None
+		);
+	}
+}
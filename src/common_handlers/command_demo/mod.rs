@@ -1,3 +1,6 @@
+mod admin;
+mod diagnose;
+mod emojis;
 mod handle_interaction;
 mod parse_bang_command;
 mod register_commands;
@@ -7,15 +10,33 @@ use handle_interaction::*;
 use parse_bang_command::*;
 use register_commands::*;
 mod greet_state;
-use greet_state::*;
+pub use greet_state::*;
+mod log_unknown_dispatch;
+use log_unknown_dispatch::*;
+mod reminders;
+use reminders::*;
+mod roll_config;
+pub use roll_config::*;
 
 
 /// Startup system that spawns the discord bot.
 pub fn spawn_command_demo(mut commands: Commands) {
-	commands
-		.spawn((DiscordBot::default(), GreetState::default()))
-		.observe(common_handlers::init_bot_state)
+	let mut bot = commands.spawn((
+		DiscordBot::default(),
+		BotMemberCache::default(),
+		CommandMetrics::default(),
+		GreetState::default(),
+		GuildHydration::default(),
+		PrefixCommands::default(),
+		Reminders::with_store("reminders.json"),
+		RollConfig::default(),
+	));
+	bot.observe(common_handlers::init_bot_state)
 		.observe(register_commands)
 		.observe(parse_bang_command)
-		.observe(handle_interaction);
+		.observe(handle_interaction)
+		.observe(log_unknown_dispatch);
+	if let Some(reaction_roles) = ReactionRoles::from_env() {
+		bot.insert(reaction_roles);
+	}
 }
@@ -1,25 +1,45 @@
 use crate::prelude::*;
 use beet::prelude::*;
+use super::RollConfig;
+use super::emojis::EmojiPage;
+use super::handle_interaction::FIRST_MESSAGE_PREVIEW_MAX;
+use super::handle_interaction::guild_info_embed;
+use super::handle_interaction::help_text;
+use tracing::debug;
 use tracing::error;
 use tracing::info;
 use tracing::warn;
+use twilight_model::channel::message::Mention;
+use twilight_model::channel::message::Message;
+use twilight_model::guild::Member;
 use twilight_model::id::Id;
 use twilight_model::id::marker::ChannelMarker;
+use twilight_model::id::marker::MessageMarker;
 use twilight_model::id::marker::UserMarker;
+use twilight_model::user::User;
+use twilight_model::util::ImageHash;
 
-/// Observer called when a non-bot user sends a message.
+/// Observer called when a message is sent, skipping bots and webhooks.
 ///
 /// Handles `!` prefix commands and @-mention commands.
 pub fn parse_bang_command(
 	msg: On<DiscordMessage>,
 	mut commands: Commands,
-	query: Query<(&BotState, &DiscordHttpClient)>,
+	query: Query<(
+		&BotState,
+		&DiscordHttpClient,
+		&HandlerLimit,
+		Option<&PrefixCommands>,
+		Option<&RollConfig>,
+		Option<&CommandMetrics>,
+	)>,
 ) -> Result {
-	if msg.author.bot {
-		return Ok(());
-	}
-
 	let entity = msg.event_target();
+	let (bot_state, http, limit, prefix, roll, metrics) = query.get(entity)?;
+	let bot_user_id = bot_state.user_id();
+	let Some(command_text) = command_text(&msg, bot_user_id) else {
+		return Ok(());
+	};
 
 	info!(
 		message_id = %msg.id,
@@ -30,13 +50,54 @@ pub fn parse_bang_command(
 	);
 
 	let channel_id = msg.channel_id;
+	let unknown_policy =
+		prefix.map(|p| p.unknown_command).unwrap_or_default();
 
-	let (bot_state, http) = query.get(entity)?;
-
-	let bot_user_id = bot_state.user_id();
+	let roll = roll.copied().unwrap_or_default();
+	let metrics = metrics.cloned().unwrap_or_default();
 	let start_time = bot_state.start_time();
 	let http = http.clone();
-	let content = msg.content.trim().to_string();
+	let limit = limit.clone();
+
+	let msg_id = msg.id;
+	let guild_id = msg.guild_id;
+	let author_id = msg.author.id;
+	// The author and mentioned users, so `!avatar` can usually skip a fetch.
+	let known_users: Vec<AvatarUser> =
+		std::iter::once(AvatarUser::from(&msg.author))
+			.chain(msg.mentions.iter().map(AvatarUser::from))
+			.collect();
+
+	commands.queue_async(async move |_| {
+		let _permit = limit.acquire().await;
+		dispatch_message_command(
+			&http,
+			channel_id,
+			msg_id,
+			guild_id,
+			author_id,
+			&known_users,
+			bot_user_id,
+			start_time,
+			unknown_policy,
+			roll,
+			&metrics,
+			&command_text,
+		)
+		.await;
+	});
+
+	Ok(())
+}
+
+/// The `!command args` text `msg` invokes, from a `!` prefix or an
+/// @mention of the bot. `None` for other messages, and for messages from
+/// bots and webhooks so automated posts never trigger commands.
+fn command_text(msg: &Message, bot_user_id: Id<UserMarker>) -> Option<String> {
+	if msg.is_automated() {
+		return None;
+	}
+	let content = msg.content.trim();
 
 	// Determine effective command text from @mention or ! prefix.
 	let effective_content = {
@@ -54,46 +115,20 @@ pub fn parse_bang_command(
 				.unwrap_or("")
 				.trim()
 				.to_string()
-		} else if msg.mentions_user(bot_user_id) {
-			String::new()
 		} else {
 			String::new()
 		}
 	};
 
-	let command_text = if content.starts_with('!') {
-		content.clone()
-	} else if !effective_content.is_empty() {
-		if effective_content.starts_with('!') {
-			effective_content.clone()
-		} else {
-			format!("!{}", effective_content)
-		}
+	if content.starts_with('!') {
+		Some(content.to_string())
+	} else if effective_content.is_empty() {
+		None
+	} else if effective_content.starts_with('!') {
+		Some(effective_content)
 	} else {
-		String::new()
-	};
-
-	if command_text.is_empty() {
-		return Ok(());
+		Some(format!("!{}", effective_content))
 	}
-
-	let msg_id = msg.id;
-	let guild_id = msg.guild_id;
-
-	commands.queue_async(async move |_| {
-		dispatch_message_command(
-			&http,
-			channel_id,
-			msg_id,
-			guild_id,
-			bot_user_id,
-			start_time,
-			&command_text,
-		)
-		.await;
-	});
-
-	Ok(())
 }
 
 async fn dispatch_message_command(
@@ -103,8 +138,13 @@ async fn dispatch_message_command(
 	guild_id: Option<
 		twilight_model::id::Id<twilight_model::id::marker::GuildMarker>,
 	>,
+	author_id: Id<UserMarker>,
+	known_users: &[AvatarUser],
 	bot_user_id: Id<UserMarker>,
 	start_time: std::time::Instant,
+	unknown_policy: UnknownCommandPolicy,
+	roll: RollConfig,
+	metrics: &CommandMetrics,
 	command_text: &str,
 ) {
 	let parts: Vec<&str> = command_text.splitn(2, ' ').collect();
@@ -162,7 +202,7 @@ async fn dispatch_message_command(
 		}
 
 		"!roll" => {
-			let sides: u32 = args.trim().parse().unwrap_or(6).max(2).min(1000);
+			let sides = roll.clamp(args.trim().parse().unwrap_or(6));
 			let result = (rand::random::<u32>() % sides) + 1;
 			let text = format!("🎲 Rolling a d{}... **{}**!", sides, result);
 			let body = reply(text).component_row(action_row(vec![button(
@@ -176,6 +216,10 @@ async fn dispatch_message_command(
 		}
 
 		"!count" => {
+			// Paginating can take a while, show that something is happening.
+			if let Err(e) = http.trigger_typing(channel_id).await {
+				debug!(error = %e, "failed to trigger typing for !count");
+			}
 			let text = match http.count_messages(channel_id).await {
 				Ok(count) => {
 					format!(
@@ -204,7 +248,12 @@ async fn dispatch_message_command(
 					};
 					format!(
 						"📜 **First message in this channel:**\n> {}\n— *{}* on {}",
-						first_msg.content, first_msg.author.name, ts
+						truncate_markdown_safe(
+							&first_msg.content,
+							FIRST_MESSAGE_PREVIEW_MAX,
+						),
+						first_msg.author.name,
+						ts
 					)
 				}
 				Err(e) => format!("❌ Error fetching first message: {}", e),
@@ -216,20 +265,45 @@ async fn dispatch_message_command(
 		}
 
 		"!serverinfo" => {
-			let text = if let Some(gid) = guild_id {
+			let body = if let Some(gid) = guild_id {
 				match http.send(GetGuild::new(gid)).await {
-					Ok(guild) => format_guild_info(&guild),
-					Err(e) => format!("❌ Error fetching server info: {}", e),
+					Ok(guild) => CreateMessage::new(channel_id)
+						.embed(guild_info_embed(&guild))
+						.reply_to(msg_id),
+					Err(e) => {
+						reply(format!("❌ Error fetching server info: {}", e))
+					}
 				}
 			} else {
-				"❌ This command only works in a server.".to_string()
+				reply("❌ This command only works in a server.".to_string())
 			};
-			let body = reply(text);
 			if let Err(e) = http.send(body).await {
 				error!(error = %e, "failed to send !serverinfo reply");
 			}
 		}
 
+		"!emojis" => {
+			let body = match guild_id {
+				Some(gid) => match http.list_guild_emojis(gid).await {
+					Ok(emojis) => {
+						let page = EmojiPage::new(&emojis, 0);
+						let body = reply(page.content);
+						match page.buttons {
+							Some(row) => body.component_row(row),
+							None => body,
+						}
+					}
+					Err(e) => reply(format!("❌ Error fetching emojis: {}", e)),
+				},
+				None => {
+					reply("❌ This command only works in a server.".to_string())
+				}
+			};
+			if let Err(e) = http.send(body).await {
+				error!(error = %e, "failed to send !emojis reply");
+			}
+		}
+
 		"!whoami" => {
 			// We don't have the full author here; fall back to a mention.
 			let text = format!(
@@ -244,6 +318,56 @@ async fn dispatch_message_command(
 			}
 		}
 
+		"!roles" => {
+			let text = if let Some(gid) = guild_id {
+				let user_id = parse_user_mention(args).unwrap_or(author_id);
+				match http.send(GetGuildMember::new(gid, user_id)).await {
+					Ok(member) => format_member_roles(&member),
+					Err(e) => format!("❌ Error fetching member: {}", e),
+				}
+			} else {
+				"❌ This command only works in a server.".to_string()
+			};
+			let body = reply(text);
+			if let Err(e) = http.send(body).await {
+				error!(error = %e, "failed to send !roles reply");
+			}
+		}
+
+		"!avatar" => {
+			let user_id = parse_user_mention(args).unwrap_or(author_id);
+			let user = match known_users.iter().find(|u| u.id == user_id) {
+				Some(user) => Ok(user.clone()),
+				None => http
+					.send(GetUser::new(user_id))
+					.await
+					.map(|user| AvatarUser::from(&user)),
+			};
+			let text = match user {
+				Ok(user) => format_avatar(&user),
+				Err(e) => format!("❌ Error fetching user: {}", e),
+			};
+			let body = reply(text);
+			if let Err(e) = http.send(body).await {
+				error!(error = %e, "failed to send !avatar reply");
+			}
+		}
+
+		"!poll" => {
+			let body = match build_poll(args) {
+				Ok(poll) => CreateMessage::new(channel_id)
+					.reply_to(msg_id)
+					.poll(poll),
+				Err(e) => reply(format!(
+					"❌ {}\nUsage: `!poll \"Question\" \"Answer 1\" \"Answer 2\"`",
+					e
+				)),
+			};
+			if let Err(e) = http.send(body).await {
+				error!(error = %e, "failed to send !poll reply");
+			}
+		}
+
 		"!help" => {
 			let text = help_text();
 			let body = reply(text);
@@ -253,18 +377,39 @@ async fn dispatch_message_command(
 		}
 
 		other if other.starts_with('!') => {
-			info!(command = other, "unhandled command");
-			let text = format!("Not sure what that means: `{}`", other);
-			let body = reply(text);
-			if let Err(e) = http.send(body).await {
+			info!(
+				command = other,
+				policy = ?unknown_policy,
+				"unhandled command"
+			);
+			let result = match unknown_command_reply(
+				unknown_policy,
+				channel_id,
+				msg_id,
+				author_id,
+				other,
+			) {
+				None => Ok(()),
+				Some(UnknownCommandReply::Channel(body)) => {
+					http.send(body).await.map(drop)
+				}
+				Some(UnknownCommandReply::Direct { user_id, content }) => {
+					http.send_dm(user_id, content).await.map(drop)
+				}
+			};
+			if let Err(e) = result {
 				warn!(error = %e, "failed to send unknown-command reply");
 			}
+			return;
 		}
 
 		unhandled => {
 			info!(command = unhandled, "not a command, ignoring");
+			return;
 		}
 	}
+	// Only known commands are counted, so typos can't grow the metrics.
+	metrics.record(command);
 }
 
 // ---------------------------------------------------------------------------
@@ -272,48 +417,319 @@ async fn dispatch_message_command(
 // shared helpers live in handlers.rs until that file is removed)
 // ---------------------------------------------------------------------------
 
-fn format_guild_info(guild: &twilight_model::guild::Guild) -> String {
-	let member_count = guild
-		.approximate_member_count
-		.map(|n| n.to_string())
-		.unwrap_or_else(|| "unknown".to_string());
-	let online_count = guild
-		.approximate_presence_count
-		.map(|n| n.to_string())
-		.unwrap_or_else(|| "unknown".to_string());
-	let owner_str = guild.owner_id.to_string();
-	let created_at = guild
-		.created_at_ms()
-		.and_then(|ms| chrono::DateTime::from_timestamp_millis(ms as i64))
-		.map(|dt| dt.format("%B %d, %Y").to_string())
-		.unwrap_or_else(|| "unknown".to_string());
-
-	format!(
-		"🏰 **Server Info: {}**\n\
-         • **Members:** {} ({} online)\n\
-         • **Owner:** <@{}>\n\
-         • **Created:** {}",
-		guild.name, member_count, online_count, owner_str, created_at
-	)
+/// The message to send for an unknown command, see [`UnknownCommandPolicy`].
+#[derive(Debug)]
+enum UnknownCommandReply {
+	/// Reply in the channel the command was sent in.
+	Channel(CreateMessage),
+	/// DM the author.
+	Direct {
+		user_id: Id<UserMarker>,
+		content: String,
+	},
+}
+
+/// Decide how to answer an unknown command, `None` when staying silent.
+fn unknown_command_reply(
+	policy: UnknownCommandPolicy,
+	channel_id: Id<ChannelMarker>,
+	msg_id: Id<MessageMarker>,
+	author_id: Id<UserMarker>,
+	command: &str,
+) -> Option<UnknownCommandReply> {
+	let content = format!("Not sure what that means: `{}`", command);
+	match policy {
+		UnknownCommandPolicy::Silent => None,
+		UnknownCommandPolicy::DirectMessage => {
+			Some(UnknownCommandReply::Direct {
+				user_id: author_id,
+				content,
+			})
+		}
+		UnknownCommandPolicy::Reply => Some(UnknownCommandReply::Channel(
+			CreateMessage::new(channel_id)
+				.content(content)
+				.reply_to(msg_id),
+		)),
+	}
+}
+
+/// The parts of a user or mention `!avatar` needs.
+#[derive(Debug, Clone)]
+struct AvatarUser {
+	id: Id<UserMarker>,
+	name: String,
+	avatar: Option<ImageHash>,
+}
+
+impl From<&User> for AvatarUser {
+	fn from(user: &User) -> Self {
+		Self {
+			id: user.id,
+			name: user.name.clone(),
+			avatar: user.avatar,
+		}
+	}
+}
+
+impl From<&Mention> for AvatarUser {
+	fn from(mention: &Mention) -> Self {
+		Self {
+			id: mention.id,
+			name: mention.name.clone(),
+			avatar: mention.avatar,
+		}
+	}
+}
+
+/// Largest size the CDN serves, so `!avatar` shows the full resolution.
+const AVATAR_SIZE: u16 = 4096;
+
+fn format_avatar(user: &AvatarUser) -> String {
+	let url = user.avatar.as_ref().map(|hash| {
+		user_avatar_url(user.id, hash, AVATAR_SIZE, CdnImageFormat::Png)
+	});
+	match url {
+		Some(Ok(url)) => format!("🖼️ **{}'s avatar:**\n{}", user.name, url),
+		Some(Err(e)) => format!("❌ {}", e),
+		None => format!("🖼️ **{}** has no avatar set.", user.name),
+	}
+}
+
+/// Parse a user from a `<@id>` / `<@!id>` mention or a bare snowflake.
+fn parse_user_mention(arg: &str) -> Option<Id<UserMarker>> {
+	let arg = arg.trim();
+	let raw = arg
+		.strip_prefix("<@")
+		.and_then(|rest| rest.strip_suffix('>'))
+		.map(|inner| inner.trim_start_matches('!'))
+		.unwrap_or(arg);
+	raw.parse::<u64>().ok().and_then(Id::new_checked)
+}
+
+fn format_member_roles(member: &Member) -> String {
+	if member.roles.is_empty() {
+		return format!("🎭 <@{}> has no roles.", member.user.id);
+	}
+	let roles = member
+		.roles
+		.iter()
+		.map(|id| format!("<@&{}> (`{}`)", id, id))
+		.collect::<Vec<_>>()
+		.join("\n• ");
+	format!("🎭 **Roles for <@{}>:**\n• {}", member.user.id, roles)
+}
+
+/// Split command arguments on whitespace, keeping `"quoted text"` together.
+fn parse_quoted_args(args: &str) -> Vec<String> {
+	let mut out = Vec::new();
+	let mut current = String::new();
+	let mut in_quotes = false;
+	for ch in args.chars() {
+		match ch {
+			'"' => {
+				if in_quotes || !current.is_empty() {
+					out.push(std::mem::take(&mut current));
+				}
+				in_quotes = !in_quotes;
+			}
+			c if c.is_whitespace() && !in_quotes => {
+				if !current.is_empty() {
+					out.push(std::mem::take(&mut current));
+				}
+			}
+			c => current.push(c),
+		}
+	}
+	if !current.is_empty() {
+		out.push(current);
+	}
+	out
 }
 
-fn help_text() -> String {
-	"🤖 **Available Commands:**\n\
-     *Prefix commands (! or @mention):*\n\
-     • `!hello` — Say hello!\n\
-     • `!ping` — Check bot latency\n\
-     • `!uptime` — See how long the bot has been running\n\
-     • `!roll [sides]` — Roll a dice (default: 6 sides)\n\
-     • `!count` — Count messages in this channel\n\
-     • `!first` — Show the first message ever sent in this channel\n\
-     • `!serverinfo` — Show server information\n\
-     • `!whoami` — Show info about yourself\n\
-     • `!help` — Show this help message\n\
-     \n\
-     *Slash commands:*\n\
-     • `/ping` `/uptime` `/roll` `/serverinfo` `/whoami` `/count` `/first` `/help`\n\
-     • `/report` — Submit a report via a pop-up form\n\
-     • `/send-logo` — Send the bot logo\n\
-     • `/demo-select` — Demo the select menu component"
-		.to_string()
+/// Build a poll from `"Question" "Answer 1" "Answer 2" …`.
+fn build_poll(args: &str) -> Result<CreatePoll, JsonError> {
+	let mut parts = parse_quoted_args(args).into_iter();
+	let question = parts
+		.next()
+		.ok_or_else(|| JsonError("poll requires a question".to_string()))?;
+	let poll = parts.fold(CreatePoll::new(question), CreatePoll::answer);
+	poll.validate()?;
+	Ok(poll)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_fixtures::*;
+
+	fn make_member(roles: &[&str]) -> Member {
+		serde_json::from_value(serde_json::json!({
+			"deaf": false,
+			"mute": false,
+			"flags": 0,
+			"joined_at": "2021-01-01T00:00:00.000000+00:00",
+			"nick": null,
+			"communication_disabled_until": null,
+			"roles": roles,
+			"user": {
+				"id": "789",
+				"username": "alice",
+				"discriminator": "0",
+				"avatar": null,
+			},
+		}))
+		.expect("valid member JSON")
+	}
+
+	fn message(content: &str, webhook_id: Option<&str>) -> Message {
+		let mut json = message_json("1", content);
+		json["webhook_id"] = webhook_id.into();
+		parse_message(json)
+	}
+
+	// -- command_text() ----------------------------------------------------
+
+	#[test]
+	fn command_text_from_prefix_and_mention() {
+		let bot = Id::new(42);
+		let text = |content| command_text(&message(content, None), bot);
+		assert_eq!(text(" !ping "), Some("!ping".into()));
+		assert_eq!(text("<@42> roll 6"), Some("!roll 6".into()));
+		assert_eq!(text("<@!42> !help"), Some("!help".into()));
+		assert_eq!(text("hello"), None);
+	}
+
+	#[test]
+	fn command_text_ignores_webhook_messages() {
+		let message = message("!ping", Some("300"));
+		assert_eq!(command_text(&message, Id::new(42)), None);
+	}
+
+	// -- parse_user_mention() ----------------------------------------------
+
+	#[test]
+	fn parse_user_mention_forms() {
+		assert_eq!(parse_user_mention("<@123>"), Some(Id::new(123)));
+		assert_eq!(parse_user_mention("<@!123>"), Some(Id::new(123)));
+		assert_eq!(parse_user_mention(" 123 "), Some(Id::new(123)));
+		assert_eq!(parse_user_mention(""), None);
+		assert_eq!(parse_user_mention("<@0>"), None);
+		assert_eq!(parse_user_mention("bob"), None);
+	}
+
+	// -- format_member_roles() ---------------------------------------------
+
+	#[test]
+	fn format_member_roles_lists_ids() {
+		let text = format_member_roles(&make_member(&["111", "222"]));
+		assert!(text.contains("<@789>"));
+		assert!(text.contains("<@&111>"));
+		assert!(text.contains("`222`"));
+	}
+
+	#[test]
+	fn format_member_roles_empty() {
+		let text = format_member_roles(&make_member(&[]));
+		assert!(text.contains("has no roles"));
+	}
+
+	// -- format_avatar() ---------------------------------------------------
+
+	#[test]
+	fn format_avatar_full_size() {
+		let user = AvatarUser {
+			id: Id::new(789),
+			name: "alice".into(),
+			avatar: Some("a_1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d".parse().unwrap()),
+		};
+		let text = format_avatar(&user);
+		assert!(text.contains("alice"));
+		assert!(text.ends_with(
+			"/avatars/789/a_1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d.gif?size=4096"
+		));
+		let text = format_avatar(&AvatarUser {
+			avatar: None,
+			..user
+		});
+		assert!(text.contains("no avatar"));
+	}
+
+	// -- parse_quoted_args() -----------------------------------------------
+
+	#[test]
+	fn parse_quoted_args_groups_quotes() {
+		assert_eq!(
+			parse_quoted_args(r#""Best pet?" "Cat" dog  "Red panda""#),
+			vec!["Best pet?", "Cat", "dog", "Red panda"]
+		);
+		assert!(parse_quoted_args("   ").is_empty());
+	}
+
+	#[test]
+	fn parse_quoted_args_keeps_empty_quotes() {
+		assert_eq!(parse_quoted_args(r#""Q" """#), vec!["Q", ""]);
+	}
+
+	// -- build_poll() ------------------------------------------------------
+
+	#[test]
+	fn build_poll_reads_question_and_answers() {
+		let poll = build_poll(r#""Lunch?" "Pizza" "Tacos""#).unwrap();
+		assert_eq!(poll.question.text.as_deref(), Some("Lunch?"));
+		assert_eq!(poll.answers.len(), 2);
+		assert_eq!(poll.answers[1].poll_media.text.as_deref(), Some("Tacos"));
+	}
+
+	#[test]
+	fn build_poll_rejects_missing_parts() {
+		assert!(build_poll("").is_err());
+		assert!(build_poll(r#""Lunch?""#).is_err());
+	}
+
+	// -- unknown_command_reply() -------------------------------------------
+
+	fn unknown_reply(
+		policy: UnknownCommandPolicy,
+	) -> Option<UnknownCommandReply> {
+		unknown_command_reply(
+			policy,
+			Id::new(1),
+			Id::new(2),
+			Id::new(3),
+			"!nope",
+		)
+	}
+
+	#[test]
+	fn unknown_command_silent_sends_nothing() {
+		assert!(unknown_reply(UnknownCommandPolicy::Silent).is_none());
+		assert!(unknown_reply(UnknownCommandPolicy::default()).is_none());
+	}
+
+	#[test]
+	fn unknown_command_reply_targets_channel() {
+		match unknown_reply(UnknownCommandPolicy::Reply) {
+			Some(UnknownCommandReply::Channel(body)) => {
+				assert!(body.content.unwrap().contains("`!nope`"));
+				assert!(body.message_reference.is_some());
+			}
+			other => panic!("expected channel reply, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn unknown_command_direct_message_targets_author() {
+		match unknown_reply(UnknownCommandPolicy::DirectMessage) {
+			Some(UnknownCommandReply::Direct { user_id, content }) => {
+				assert_eq!(user_id.get(), 3);
+				assert!(content.contains("`!nope`"));
+			}
+			other => panic!("expected DM, got {:?}", other),
+		}
+	}
 }
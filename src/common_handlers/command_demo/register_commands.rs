@@ -1,67 +1,240 @@
+use super::RollConfig;
 use crate::prelude::CommandExt;
 use crate::prelude::*;
+use beet::core::time_ext;
 use beet::prelude::*;
+use std::time::Duration;
 use tracing::warn;
 use twilight_model::application::command::Command;
+
+/// Wait before the first registration retry, doubled after each failure.
+const REGISTER_RETRY_INITIAL: Duration = Duration::from_secs(5);
+/// Longest wait between registration retries.
+const REGISTER_RETRY_MAX: Duration = Duration::from_secs(5 * 60);
+
 /// Called when the bot receives the READY event from the gateway.
 ///
 /// Stores identity information in [`BotState`] and registers slash commands
-/// globally (once per session).
+/// globally (once per session). A registration failing with a transient
+/// error, e.g. a 503, is retried with backoff until it succeeds, any other
+/// failure is logged and not retried.
 pub fn register_commands(
 	ev: On<DiscordReady>,
 	mut commands: Commands,
-	query: Populated<&DiscordHttpClient, Without<BotState>>,
+	query: Populated<
+		(&DiscordHttpClient, Option<&RollConfig>),
+		Without<BotState>,
+	>,
 ) -> Result {
 	let entity = ev.event_target();
 
-	let client = query.get(entity)?.clone();
+	let (client, roll) = query.get(entity)?;
+	let client = client.clone();
+	let roll = roll.copied().unwrap_or_default();
 	let app_id = ev.application.id;
 	commands.queue_async(async move |_| {
-		let cmds = slash_commands();
-		match client.send(SetGlobalCommands::new(app_id, cmds)).await {
-			Ok(registered) => {
-				info!(
-					count = registered.len(),
-					"registered global slash commands"
+		let cmds = slash_commands(roll);
+		for cmd in &cmds {
+			if let Err(e) = cmd.validate() {
+				warn!(
+					command = %cmd.name,
+					error = %e,
+					"slash command definition is invalid"
 				);
 			}
-			Err(e) => {
-				warn!(error = %e, "failed to register global commands");
-			}
+		}
+		if let Some(count) = register_until_ok(
+			|| client.send(SetGlobalCommands::new(app_id, cmds.clone())),
+			retry_delay,
+		)
+		.await
+		{
+			info!(count, "registered global slash commands");
 		}
 	});
 
 	Ok(())
 }
 
+/// Run `register` until it succeeds, sleeping `delay(failures)` after each
+/// [transient](HttpError::is_transient) failure. Returns the number of
+/// commands registered, or `None` if it failed in a way retrying won't fix,
+/// e.g. a 400 for an invalid command.
+async fn register_until_ok<F, Fut>(
+	mut register: F,
+	delay: impl Fn(u32) -> Duration,
+) -> Option<usize>
+where
+	F: FnMut() -> Fut,
+	Fut: Future<Output = Result<Vec<Command>, HttpError>>,
+{
+	let mut failures = 0;
+	loop {
+		match register().await {
+			Ok(registered) => return Some(registered.len()),
+			Err(e) if !e.is_transient() => {
+				error!(
+					error = %e,
+					"failed to register global commands, not retrying"
+				);
+				return None;
+			}
+			Err(e) => {
+				failures += 1;
+				let delay = delay(failures);
+				warn!(
+					error = %e,
+					failures,
+					retry_in_secs = delay.as_secs(),
+					"failed to register global commands, retrying"
+				);
+				time_ext::sleep(delay).await;
+			}
+		}
+	}
+}
+
+/// Exponential backoff for the `failures`th failed registration.
+fn retry_delay(failures: u32) -> Duration {
+	REGISTER_RETRY_INITIAL
+		.saturating_mul(2u32.saturating_pow(failures.saturating_sub(1)))
+		.min(REGISTER_RETRY_MAX)
+}
+
 // ---------------------------------------------------------------------------
 // Slash command definitions
 // ---------------------------------------------------------------------------
 
-/// Returns the list of slash commands to register with Discord.
-fn slash_commands() -> Vec<Command> {
+/// Commands that only work in guilds with a feature, e.g.
+/// `("announce", "COMMUNITY")` for one posting to announcement channels.
+///
+/// Global registration can't tell guilds apart, so these are registered
+/// everywhere and reply "not available" in guilds without the feature.
+/// `/admin register` leaves them out of such guilds instead.
+const FEATURE_GATED_COMMANDS: &[(&str, &str)] = &[];
+
+/// The guild feature `command` requires, if any.
+pub(super) fn required_feature(command: &str) -> Option<&'static str> {
+	gated_feature(FEATURE_GATED_COMMANDS, command)
+}
+
+fn gated_feature<'a>(
+	gates: &[(&str, &'a str)],
+	command: &str,
+) -> Option<&'a str> {
+	gates
+		.iter()
+		.find(|(name, _)| *name == command)
+		.map(|(_, feature)| *feature)
+}
+
+/// Whether a command requiring `required` is usable in a guild with
+/// `features`, as listed by [`GuildExt::feature_names`].
+pub(super) fn feature_available(
+	required: Option<&str>,
+	features: &[String],
+) -> bool {
+	required.is_none_or(|required| features.iter().any(|f| f == required))
+}
+
+/// [`slash_commands`] usable in a guild with `features`, for registering
+/// commands per guild.
+pub(super) fn guild_slash_commands(
+	roll: RollConfig,
+	features: &[String],
+) -> Vec<Command> {
+	slash_commands(roll)
+		.into_iter()
+		.filter(|cmd| feature_available(required_feature(&cmd.name), features))
+		.collect()
+}
+
+/// Returns the list of slash commands to register with Discord, with `/roll`
+/// limited to `roll`'s bounds.
+pub(super) fn slash_commands(roll: RollConfig) -> Vec<Command> {
 	use twilight_model::application::command::CommandOptionType;
+	use twilight_model::application::command::CommandOptionValue;
 
 	vec![
 		Command::chat_input("ping", "Check bot latency"),
 		Command::chat_input("uptime", "See how long the bot has been running"),
-		Command::chat_input("roll", "Roll a dice").with_simple_option(
+		Command::chat_input("roll", "Roll a dice").with_ranged_option(
 			CommandOptionType::Integer,
 			"sides",
 			"Number of sides (default: 6)",
 			false,
+			CommandOptionValue::Integer(roll.min_sides as i64),
+			CommandOptionValue::Integer(roll.max_sides as i64),
 		),
 		Command::chat_input("serverinfo", "Show server information"),
 		Command::chat_input("whoami", "Show info about yourself"),
-		Command::chat_input("count", "Count messages in this channel"),
+		Command::chat_input("emojis", "List this server's custom emojis"),
+		Command::chat_input("count", "Count messages in this channel")
+			.with_simple_option(
+				CommandOptionType::Boolean,
+				"full",
+				"Count past the 10 000 message cap (slow)",
+				false,
+			)
+			.with_simple_option(
+				CommandOptionType::Boolean,
+				"all",
+				"Count every channel in this server",
+				false,
+			),
 		Command::chat_input(
 			"first",
 			"Show the first message ever sent in this channel",
 		),
 		Command::chat_input("help", "Show available commands"),
 		Command::chat_input("report", "Submit a report via a pop-up form"),
+		Command::chat_input("remindme", "Get a reminder after a delay")
+			.with_simple_option(
+				CommandOptionType::String,
+				"duration",
+				"Delay such as 10m, 2h or 1d",
+				true,
+			)
+			.with_simple_option(
+				CommandOptionType::String,
+				"text",
+				"What to remind you about",
+				true,
+			),
 		Command::chat_input("send-logo", "Send the bot logo"),
 		Command::chat_input("demo-select", "Demo the select menu component"),
+		Command::chat_input(
+			"diagnose",
+			"Check my permissions here and enabled intents",
+		),
+		Command::chat_input("admin", "Owner-only command management")
+			.with_subcommand(
+				"register",
+				"Register the slash commands in this server",
+				Vec::new(),
+			)
+			.with_subcommand(
+				"clear",
+				"Remove this server's slash commands",
+				Vec::new(),
+			)
+			.with_subcommand(
+				"cleanup",
+				"Delete my last messages in this channel",
+				vec![ranged_command_option(
+					CommandOptionType::Integer,
+					"count",
+					"How many messages to delete (default: 10)",
+					false,
+					CommandOptionValue::Integer(1),
+					CommandOptionValue::Integer(100),
+				)],
+			)
+			.with_subcommand(
+				"stats",
+				"Show the most used commands",
+				Vec::new(),
+			),
 	]
 }
 
@@ -70,16 +243,66 @@ fn slash_commands() -> Vec<Command> {
 mod tests {
 	use super::*;
 	use twilight_model::application::command::CommandOptionType;
+	use twilight_model::application::command::CommandOptionValue;
+
+	#[test]
+	fn registration_retries_after_failure() {
+		let mut attempts = 0;
+		let count = futures_lite::future::block_on(register_until_ok(
+			|| {
+				attempts += 1;
+				let result = if attempts == 1 {
+					Err(HttpError::Transport("503".into()))
+				} else {
+					Ok(slash_commands(RollConfig::default()))
+				};
+				async move { result }
+			},
+			|_| Duration::ZERO,
+		));
+		assert_eq!(attempts, 2);
+		assert_eq!(count, Some(slash_commands(RollConfig::default()).len()));
+	}
+
+	#[test]
+	fn registration_gives_up_on_client_errors() {
+		let mut attempts = 0;
+		let count = futures_lite::future::block_on(register_until_ok(
+			|| {
+				attempts += 1;
+				async {
+					Err(HttpError::Api {
+						status: StatusCode::BAD_REQUEST,
+						body: "{}".into(),
+						route: "PUT /applications/1/commands".into(),
+						hint: None,
+					})
+				}
+			},
+			|_| Duration::ZERO,
+		));
+		assert_eq!(attempts, 1);
+		assert_eq!(count, None);
+	}
+
+	#[test]
+	fn retry_delay_backs_off_and_caps() {
+		assert_eq!(retry_delay(1), REGISTER_RETRY_INITIAL);
+		assert_eq!(retry_delay(2), REGISTER_RETRY_INITIAL * 2);
+		assert_eq!(retry_delay(3), REGISTER_RETRY_INITIAL * 4);
+		assert_eq!(retry_delay(20), REGISTER_RETRY_MAX);
+		assert_eq!(retry_delay(u32::MAX), REGISTER_RETRY_MAX);
+	}
 
 	#[test]
 	fn slash_commands_returns_expected_count() {
-		let cmds = slash_commands();
-		assert_eq!(cmds.len(), 11);
+		let cmds = slash_commands(RollConfig::default());
+		assert_eq!(cmds.len(), 15);
 	}
 
 	#[test]
 	fn slash_commands_names_are_unique() {
-		let cmds = slash_commands();
+		let cmds = slash_commands(RollConfig::default());
 		let mut names: Vec<&str> =
 			cmds.iter().map(|c| c.name.as_str()).collect();
 		names.sort();
@@ -89,7 +312,7 @@ mod tests {
 
 	#[test]
 	fn slash_commands_all_have_descriptions() {
-		for cmd in slash_commands() {
+		for cmd in slash_commands(RollConfig::default()) {
 			assert!(
 				!cmd.description.is_empty(),
 				"command '{}' has empty description",
@@ -98,13 +321,67 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn slash_commands_all_validate() {
+		for cmd in slash_commands(RollConfig::default()) {
+			if let Err(e) = cmd.validate() {
+				panic!("command '{}' is invalid: {}", cmd.name, e);
+			}
+		}
+	}
+
+	#[test]
+	fn feature_gating_checks_guild_features() {
+		let gates = [("announce", "COMMUNITY")];
+		let community = vec!["COMMUNITY".to_string(), "NEWS".to_string()];
+		let required = gated_feature(&gates, "announce");
+		assert_eq!(required, Some("COMMUNITY"));
+		assert!(feature_available(required, &community));
+		assert!(!feature_available(required, &[]));
+		// Ungated commands are available everywhere.
+		assert_eq!(gated_feature(&gates, "ping"), None);
+		assert!(feature_available(None, &[]));
+	}
+
+	#[test]
+	fn guild_slash_commands_keeps_ungated_commands() {
+		let all = slash_commands(RollConfig::default());
+		let guild = guild_slash_commands(RollConfig::default(), &[]);
+		for cmd in &all {
+			let gated = required_feature(&cmd.name).is_some();
+			assert_eq!(guild.iter().any(|c| c.name == cmd.name), !gated);
+		}
+	}
+
 	#[test]
 	fn roll_command_has_sides_option() {
-		let cmds = slash_commands();
+		let cmds = slash_commands(RollConfig::default());
 		let roll = cmds.iter().find(|c| c.name == "roll").expect("no /roll");
 		assert_eq!(roll.options.len(), 1);
 		assert_eq!(roll.options[0].name, "sides");
 		assert!(matches!(roll.options[0].kind, CommandOptionType::Integer));
 		assert_eq!(roll.options[0].required, Some(false));
+		assert!(matches!(
+			roll.options[0].min_value,
+			Some(CommandOptionValue::Integer(2))
+		));
+		assert!(matches!(
+			roll.options[0].max_value,
+			Some(CommandOptionValue::Integer(1000))
+		));
+	}
+
+	#[test]
+	fn roll_option_uses_configured_bounds() {
+		let cmds = slash_commands(RollConfig::new(1, 10000));
+		let roll = cmds.iter().find(|c| c.name == "roll").expect("no /roll");
+		assert!(matches!(
+			roll.options[0].min_value,
+			Some(CommandOptionValue::Integer(1))
+		));
+		assert!(matches!(
+			roll.options[0].max_value,
+			Some(CommandOptionValue::Integer(10000))
+		));
 	}
 }
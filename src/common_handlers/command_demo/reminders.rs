@@ -0,0 +1,387 @@
+//! `/remindme` — schedule a message to be posted back after a delay.
+//!
+//! Pending reminders live in the [`Reminders`] component. When it has a
+//! store path they are written there as JSON on every change and reloaded on
+//! startup, so reminders survive a restart.
+use crate::prelude::*;
+use beet::core::time_ext;
+use beet::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::info;
+use tracing::warn;
+use twilight_model::application::interaction::application_command::CommandDataOption;
+use twilight_model::application::interaction::application_command::CommandOptionValue;
+use twilight_model::id::Id;
+use twilight_model::id::marker::ChannelMarker;
+use twilight_model::id::marker::UserMarker;
+
+/// How often the background task checks for due reminders.
+const REMINDER_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Longest delay `/remindme` accepts.
+const REMINDER_MAX_DELAY: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// A single scheduled reminder.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reminder {
+	/// Unix timestamp in seconds at which to deliver.
+	pub due_at: i64,
+	pub channel_id: Id<ChannelMarker>,
+	pub user_id: Id<UserMarker>,
+	pub text: String,
+}
+
+/// Pending reminders for a bot, optionally persisted to a JSON file.
+#[derive(Debug, Default, Component)]
+#[component(on_add=on_add)]
+pub struct Reminders {
+	pending: Vec<Reminder>,
+	/// Where to persist [`Self::pending`], if anywhere.
+	store: Option<PathBuf>,
+}
+
+impl Reminders {
+	/// Persist reminders to `path`, loading any already stored there.
+	pub fn with_store(path: impl Into<PathBuf>) -> Self {
+		let path = path.into();
+		let pending = match std::fs::read(&path) {
+			Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+				warn!(
+					error = %e,
+					path = %path.display(),
+					"ignoring corrupt reminder store"
+				);
+				Vec::new()
+			}),
+			Err(_) => Vec::new(),
+		};
+		Self {
+			pending,
+			store: Some(path),
+		}
+	}
+
+	/// Schedule a reminder.
+	pub fn add(&mut self, reminder: Reminder) {
+		self.pending.push(reminder);
+		self.persist();
+	}
+
+	/// Remove and return every reminder due at or before `now`, oldest first.
+	pub fn take_due(&mut self, now: i64) -> Vec<Reminder> {
+		let (mut due, pending): (Vec<_>, Vec<_>) =
+			std::mem::take(&mut self.pending)
+				.into_iter()
+				.partition(|r| r.due_at <= now);
+		self.pending = pending;
+		if !due.is_empty() {
+			due.sort_by_key(|r| r.due_at);
+			self.persist();
+		}
+		due
+	}
+
+	/// Put back reminders taken by [`Self::take_due`] that couldn't be
+	/// delivered.
+	pub fn restore(&mut self, reminders: Vec<Reminder>) {
+		if reminders.is_empty() {
+			return;
+		}
+		self.pending.extend(reminders);
+		self.persist();
+	}
+
+	fn persist(&self) {
+		let Some(path) = &self.store else {
+			return;
+		};
+		let result = serde_json::to_vec(&self.pending)
+			.map_err(|e| e.to_string())
+			.and_then(|bytes| {
+				std::fs::write(path, bytes).map_err(|e| e.to_string())
+			});
+		if let Err(e) = result {
+			warn!(
+				error = %e,
+				path = %path.display(),
+				"failed to save reminders"
+			);
+		}
+	}
+}
+
+/// Fired on the bot entity every [`REMINDER_POLL_INTERVAL`] to deliver due
+/// reminders.
+#[derive(EntityEvent)]
+pub struct ReminderTick {
+	pub entity: Entity,
+}
+
+/// Fired on the bot entity with reminders that failed to send, so they are
+/// stored again and retried on a later [`ReminderTick`].
+#[derive(EntityEvent)]
+pub struct ReminderRetry {
+	pub entity: Entity,
+	pub reminders: Vec<Reminder>,
+}
+
+fn on_add(mut world: DeferredWorld, cx: HookContext) {
+	let entity = cx.entity;
+	world
+		.commands()
+		.entity(entity)
+		.observe(schedule_reminder)
+		.observe(deliver_due_reminders)
+		.observe(retry_reminders);
+	world.commands().queue_async(async move |world| {
+		let entity = world.entity(entity);
+		// Stop once the bot or its reminders are removed.
+		while entity.get::<Reminders, _>(|_| ()).await.is_ok() {
+			time_ext::sleep(REMINDER_POLL_INTERVAL).await;
+			entity.trigger(|entity| ReminderTick { entity });
+		}
+		Ok(())
+	});
+}
+
+/// Handles `/remindme duration:<10m|2h|1d> text:<...>`.
+fn schedule_reminder(
+	ev: On<DiscordInteraction>,
+	mut commands: Commands,
+	mut query: Query<(&mut Reminders, &DiscordHttpClient)>,
+) -> Result {
+	let entity = ev.event_target();
+	let (mut reminders, http) = query.get_mut(entity)?;
+	let http = http.clone();
+	let interaction = ev.interaction.clone();
+	let ctx = CommandContext::new(&http, &interaction);
+	if ctx.command_name() != Some("remindme") {
+		return Ok(());
+	}
+
+	#[allow(deprecated)]
+	let channel_id = interaction.channel_id;
+	let reply = match (
+		channel_id,
+		ctx.author().map(|user| user.id),
+		option_str(ctx.options, "duration").and_then(parse_duration),
+	) {
+		(Some(channel_id), Some(user_id), Some(delay)) => {
+			let due_at =
+				chrono::Utc::now().timestamp() + delay.as_secs() as i64;
+			reminders.add(Reminder {
+				due_at,
+				channel_id,
+				user_id,
+				text: option_str(ctx.options, "text")
+					.unwrap_or_default()
+					.to_string(),
+			});
+			info!(user_id = %user_id, due_at, "scheduled reminder");
+			format!("⏰ Okay, I'll remind you <t:{}:R>.", due_at)
+		}
+		(_, _, None) => {
+			"❌ Invalid duration, try `10m`, `2h` or `1d`.".to_string()
+		}
+		_ => "❌ Reminders need a channel and a user.".to_string(),
+	};
+
+	commands.queue_async(async move |_| {
+		CommandContext::new(&http, &interaction)
+			.respond_ephemeral(reply)
+			.await?;
+		Ok(())
+	});
+	Ok(())
+}
+
+/// Posts every reminder that has come due, handing any that fail to send
+/// back via [`ReminderRetry`].
+fn deliver_due_reminders(
+	ev: On<ReminderTick>,
+	mut commands: Commands,
+	mut query: Query<(&mut Reminders, &DiscordHttpClient)>,
+) -> Result {
+	let entity = ev.event_target();
+	// The HTTP client is only inserted once the gateway connects.
+	let Ok((mut reminders, http)) = query.get_mut(entity) else {
+		return Ok(());
+	};
+	let due = reminders.take_due(chrono::Utc::now().timestamp());
+	if due.is_empty() {
+		return Ok(());
+	}
+	let http = http.clone();
+	commands.queue_async(async move |world| {
+		let mut failed = Vec::new();
+		for reminder in due {
+			let result = http
+				.send(
+					CreateMessage::new(reminder.channel_id)
+						.content(reminder_text(&reminder)),
+				)
+				.await;
+			if let Err(e) = result {
+				warn!(
+					error = %e,
+					channel_id = %reminder.channel_id,
+					"failed to deliver reminder, will retry"
+				);
+				failed.push(reminder);
+			}
+		}
+		if !failed.is_empty() {
+			world.entity(entity).trigger(move |entity| ReminderRetry {
+				entity,
+				reminders: failed,
+			});
+		}
+		Ok(())
+	});
+	Ok(())
+}
+
+/// Stores reminders that failed to send so the next tick retries them.
+fn retry_reminders(
+	ev: On<ReminderRetry>,
+	mut query: Query<&mut Reminders>,
+) -> Result {
+	let mut reminders = query.get_mut(ev.event_target())?;
+	reminders.restore(ev.reminders.clone());
+	Ok(())
+}
+
+fn reminder_text(reminder: &Reminder) -> String {
+	format!("⏰ <@{}> reminder: {}", reminder.user_id, reminder.text)
+}
+
+fn option_str<'a>(
+	options: &'a [CommandDataOption],
+	name: &str,
+) -> Option<&'a str> {
+	options
+		.iter()
+		.find(|o| o.name == name)
+		.and_then(|o| match &o.value {
+			CommandOptionValue::String(s) => Some(s.as_str()),
+			_ => None,
+		})
+}
+
+/// Parse a delay like `30s`, `10m`, `2h`, `1d` or a combination such as
+/// `1h30m`. Returns `None` for empty, zero or over-long delays.
+pub fn parse_duration(input: &str) -> Option<Duration> {
+	let input = input.trim();
+	let mut total: u64 = 0;
+	let mut digits = String::new();
+	for c in input.chars() {
+		if c.is_ascii_digit() {
+			digits.push(c);
+			continue;
+		}
+		let unit = match c.to_ascii_lowercase() {
+			's' => 1,
+			'm' => 60,
+			'h' => 60 * 60,
+			'd' => 24 * 60 * 60,
+			_ => return None,
+		};
+		let value: u64 = digits.parse().ok()?;
+		digits.clear();
+		total = total.checked_add(value.checked_mul(unit)?)?;
+	}
+	let duration = Duration::from_secs(total);
+	if !digits.is_empty() || total == 0 || duration > REMINDER_MAX_DELAY {
+		return None;
+	}
+	Some(duration)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn reminder(due_at: i64, text: &str) -> Reminder {
+		Reminder {
+			due_at,
+			channel_id: Id::new(1),
+			user_id: Id::new(2),
+			text: text.into(),
+		}
+	}
+
+	#[test]
+	fn parse_duration_units() {
+		assert_eq!(parse_duration("30s"), Some(Duration::from_secs(30)));
+		assert_eq!(parse_duration("10m"), Some(Duration::from_secs(600)));
+		assert_eq!(parse_duration("2h"), Some(Duration::from_secs(7200)));
+		assert_eq!(parse_duration("1d"), Some(Duration::from_secs(86400)));
+		assert_eq!(parse_duration(" 1H30m "), Some(Duration::from_secs(5400)));
+	}
+
+	#[test]
+	fn parse_duration_rejects_invalid() {
+		assert_eq!(parse_duration(""), None);
+		assert_eq!(parse_duration("10"), None);
+		assert_eq!(parse_duration("m"), None);
+		assert_eq!(parse_duration("0m"), None);
+		assert_eq!(parse_duration("5w"), None);
+		assert_eq!(parse_duration("31d"), None);
+		assert_eq!(parse_duration("99999999999999999999d"), None);
+	}
+
+	#[test]
+	fn take_due_selects_only_due_reminders() {
+		let mut reminders = Reminders::default();
+		reminders.add(reminder(300, "later"));
+		reminders.add(reminder(200, "second"));
+		reminders.add(reminder(100, "first"));
+
+		let due = reminders.take_due(200);
+		let texts: Vec<_> = due.iter().map(|r| r.text.as_str()).collect();
+		assert_eq!(texts, ["first", "second"]);
+		assert_eq!(reminders.pending, [reminder(300, "later")]);
+		assert!(reminders.take_due(299).is_empty());
+	}
+
+	#[test]
+	fn store_round_trips_pending_reminders() {
+		let path = std::env::temp_dir().join(format!(
+			"hello-discord-reminders-{}.json",
+			std::process::id()
+		));
+		let mut reminders = Reminders::with_store(&path);
+		reminders.add(reminder(100, "a"));
+		reminders.add(reminder(200, "b"));
+		reminders.take_due(100);
+
+		let reloaded = Reminders::with_store(&path);
+		assert_eq!(reloaded.pending, [reminder(200, "b")]);
+		std::fs::remove_file(&path).ok();
+	}
+
+	#[test]
+	fn restore_stores_undelivered_reminders_again() {
+		let path = std::env::temp_dir().join(format!(
+			"hello-discord-reminders-restore-{}.json",
+			std::process::id()
+		));
+		let mut reminders = Reminders::with_store(&path);
+		reminders.add(reminder(100, "a"));
+		let due = reminders.take_due(100);
+		assert!(Reminders::with_store(&path).pending.is_empty());
+
+		reminders.restore(due);
+		let reloaded = Reminders::with_store(&path);
+		assert_eq!(reloaded.pending, [reminder(100, "a")]);
+		std::fs::remove_file(&path).ok();
+	}
+
+	#[test]
+	fn reminder_text_mentions_user() {
+		let text = reminder_text(&reminder(0, "stretch"));
+		assert_eq!(text, "⏰ <@2> reminder: stretch");
+	}
+}
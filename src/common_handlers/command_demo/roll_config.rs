@@ -0,0 +1,74 @@
+//! Bounds on the number of sides `/roll` and `!roll` accept.
+use beet::prelude::*;
+
+/// Fewest sides a roll allows by default.
+pub const DEFAULT_ROLL_MIN_SIDES: u32 = 2;
+/// Most sides a roll allows by default.
+pub const DEFAULT_ROLL_MAX_SIDES: u32 = 1000;
+
+/// Clamp bounds for dice rolls, read from the bot entity. Also sets the
+/// `sides` option's `min_value`/`max_value` when `/roll` is registered.
+///
+/// ```ignore
+/// // Allow coin flips and big dice.
+/// commands.spawn((DiscordBot::default(), RollConfig::new(1, 10000)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct RollConfig {
+	pub min_sides: u32,
+	pub max_sides: u32,
+}
+
+impl Default for RollConfig {
+	fn default() -> Self {
+		Self {
+			min_sides: DEFAULT_ROLL_MIN_SIDES,
+			max_sides: DEFAULT_ROLL_MAX_SIDES,
+		}
+	}
+}
+
+impl RollConfig {
+	/// Bounds of `min_sides..=max_sides`, raising `min_sides` to 1 and
+	/// `max_sides` to `min_sides` if needed.
+	pub fn new(min_sides: u32, max_sides: u32) -> Self {
+		let min_sides = min_sides.max(1);
+		Self {
+			min_sides,
+			max_sides: max_sides.max(min_sides),
+		}
+	}
+
+	/// Clamp a requested number of sides into the configured bounds.
+	pub fn clamp(&self, sides: u32) -> u32 {
+		sides.clamp(self.min_sides, self.max_sides)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default_bounds() {
+		let config = RollConfig::default();
+		assert_eq!(config.clamp(1), 2);
+		assert_eq!(config.clamp(20), 20);
+		assert_eq!(config.clamp(5000), 1000);
+	}
+
+	#[test]
+	fn custom_bounds_are_enforced() {
+		let config = RollConfig::new(1, 10000);
+		assert_eq!(config.clamp(0), 1);
+		assert_eq!(config.clamp(1), 1);
+		assert_eq!(config.clamp(5000), 5000);
+		assert_eq!(config.clamp(20000), 10000);
+	}
+
+	#[test]
+	fn new_keeps_bounds_usable() {
+		assert_eq!(RollConfig::new(0, 0), RollConfig::new(1, 1));
+		assert_eq!(RollConfig::new(10, 5).max_sides, 10);
+	}
+}
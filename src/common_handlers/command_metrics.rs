@@ -0,0 +1,74 @@
+//! Per-command invocation counts, to see which commands are popular.
+use beet::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+/// Counts command invocations by name, keyed with their prefix so slash
+/// and prefix commands stay apart, e.g. `/ping` and `!ping`.
+///
+/// The lock is only held to bump or read a counter. Clones share the
+/// counts, so they can be moved into async work.
+#[derive(Debug, Clone, Default, Component)]
+pub struct CommandMetrics {
+	counts: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+impl CommandMetrics {
+	/// Count one invocation of `command`.
+	pub fn record(&self, command: &str) {
+		let mut counts = self.lock();
+		match counts.get_mut(command) {
+			Some(count) => *count += 1,
+			None => {
+				counts.insert(command.to_string(), 1);
+			}
+		}
+	}
+
+	/// Invocations of `command` so far.
+	pub fn count(&self, command: &str) -> u64 {
+		self.lock().get(command).copied().unwrap_or(0)
+	}
+
+	/// The `limit` most used commands, most used first, ties by name.
+	pub fn top(&self, limit: usize) -> Vec<(String, u64)> {
+		let mut counts: Vec<_> = self
+			.lock()
+			.iter()
+			.map(|(command, count)| (command.clone(), *count))
+			.collect();
+		counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+		counts.truncate(limit);
+		counts
+	}
+
+	fn lock(&self) -> MutexGuard<'_, HashMap<String, u64>> {
+		self.counts
+			.lock()
+			.unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn top_orders_by_count_then_name() {
+		let metrics = CommandMetrics::default();
+		for command in ["/ping", "!roll", "/ping", "/help", "!roll", "/ping"] {
+			metrics.record(command);
+		}
+		// Clones share counts.
+		metrics.clone().record("/help");
+		assert_eq!(metrics.count("/ping"), 3);
+		assert_eq!(metrics.count("/uptime"), 0);
+		assert_eq!(metrics.top(2), [
+			("/ping".to_string(), 3),
+			("!roll".to_string(), 2),
+		]);
+		assert_eq!(metrics.top(10).len(), 3);
+	}
+}
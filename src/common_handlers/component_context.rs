@@ -0,0 +1,485 @@
+//! Context and routing for message component interactions (buttons,
+//! select menus).
+//!
+//! Component custom ids follow a `prefix:args` scheme, e.g. `reroll:20`.
+//! A [`ComponentRegistry`] maps each prefix to a handler, which reads the
+//! rest of the id through [`ComponentContext::args`].
+
+use crate::prelude::*;
+use std::pin::Pin;
+use tracing::info;
+use twilight_model::application::interaction::Interaction;
+use twilight_model::application::interaction::InteractionData;
+use twilight_model::channel::message::Component;
+use twilight_model::channel::message::EmojiReactionType;
+use twilight_model::channel::message::MessageFlags;
+use twilight_model::http::interaction::InteractionResponse;
+use twilight_model::http::interaction::InteractionResponseData;
+
+/// Future returned by a [`ComponentHandler`].
+pub type ComponentFuture =
+	Pin<Box<dyn Future<Output = Result<(), HttpError>> + Send>>;
+
+/// Handles one component custom-id prefix.
+///
+/// ```ignore
+/// fn reroll(ctx: ComponentContext) -> ComponentFuture {
+///     Box::pin(async move { ctx.respond_ephemeral(ctx.args()).await })
+/// }
+/// ```
+pub type ComponentHandler = fn(ComponentContext) -> ComponentFuture;
+
+/// A reply to a component interaction. Edits the message the component
+/// is attached to unless [`as_new_message`](Self::as_new_message) is set.
+///
+/// ```ignore
+/// // Replace the clicked message.
+/// ctx.respond(data.clone()).await?;
+/// // Post a separate message instead.
+/// ctx.respond(ComponentReply::new(data).as_new_message()).await?;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentReply {
+	data: InteractionResponseData,
+	new_message: bool,
+}
+
+impl ComponentReply {
+	pub fn new(data: InteractionResponseData) -> Self {
+		Self {
+			data,
+			new_message: false,
+		}
+	}
+
+	/// Post the reply as a new message, leaving the original untouched.
+	pub fn as_new_message(mut self) -> Self {
+		self.new_message = true;
+		self
+	}
+
+	/// The `UpdateMessage` or `ChannelMessageWithSource` response.
+	pub fn into_response(self) -> InteractionResponse {
+		if self.new_message {
+			InteractionResponse::message(self.data)
+		} else {
+			InteractionResponse::update(self.data)
+		}
+	}
+}
+
+impl From<InteractionResponseData> for ComponentReply {
+	fn from(data: InteractionResponseData) -> Self { Self::new(data) }
+}
+
+/// Everything a component handler needs to read the click and reply.
+#[derive(Debug, Clone)]
+pub struct ComponentContext {
+	pub http: DiscordHttpClient,
+	pub interaction: Interaction,
+	/// The full custom id of the clicked component.
+	pub custom_id: String,
+	/// Selected values, empty for buttons.
+	pub values: Vec<String>,
+}
+
+impl ComponentContext {
+	/// Build a context, `None` if `interaction` isn't a component interaction.
+	pub fn new(
+		http: DiscordHttpClient,
+		interaction: Interaction,
+	) -> Option<Self> {
+		let (custom_id, values) = match interaction.data.as_ref()? {
+			InteractionData::MessageComponent(data) => {
+				(data.custom_id.clone(), data.values.clone())
+			}
+			_ => return None,
+		};
+		Some(Self {
+			http,
+			interaction,
+			custom_id,
+			values,
+		})
+	}
+
+	/// The custom id up to the first `:`, used for routing.
+	pub fn prefix(&self) -> &str { custom_id_prefix(&self.custom_id) }
+
+	/// The custom id after the first `:`, empty if there is none.
+	pub fn args(&self) -> &str {
+		self.custom_id.split_once(':').map_or("", |(_, args)| args)
+	}
+
+	/// Send an arbitrary [`InteractionResponse`].
+	pub async fn respond_with(
+		&self,
+		response: InteractionResponse,
+	) -> Result<(), HttpError> {
+		self.http
+			.send(CreateInteractionResponse::new(
+				self.interaction.id,
+				self.interaction.token.clone(),
+				response,
+			))
+			.await
+	}
+
+	/// Reply to the click, by default editing the message the component
+	/// is attached to. See [`ComponentReply`].
+	pub async fn respond(
+		&self,
+		reply: impl Into<ComponentReply>,
+	) -> Result<(), HttpError> {
+		self.respond_with(reply.into().into_response()).await
+	}
+
+	/// Edit the message the component is attached to.
+	pub async fn update(
+		&self,
+		data: InteractionResponseData,
+	) -> Result<(), HttpError> {
+		self.respond(ComponentReply::new(data)).await
+	}
+
+	/// Acknowledge a slow click right away, see
+	/// [`acknowledge_component_loading`].
+	pub async fn acknowledge_loading(&self) -> Result<(), HttpError> {
+		acknowledge_component_loading(&self.http, &self.interaction).await
+	}
+
+	/// Reply with a text message only the clicking user can see.
+	pub async fn respond_ephemeral(
+		&self,
+		content: impl Into<String>,
+	) -> Result<(), HttpError> {
+		self.respond_with(InteractionResponse::message(
+			InteractionResponseData::default()
+				.with_content(content)
+				.with_flags(MessageFlags::EPHEMERAL),
+		))
+		.await
+	}
+}
+
+/// Edit the clicked message to disable its components, marking the
+/// clicked one with ⏳, for handlers that take a while. The content is
+/// kept. Replace the message when done with
+/// [`EditOriginalInteractionResponse`].
+///
+/// ```ignore
+/// acknowledge_component_loading(&ctx.http, &ctx.interaction).await?;
+/// let result = slow_work().await;
+/// ctx.http.send(
+///     EditOriginalInteractionResponse::new(app_id, token).content(result)
+/// ).await?;
+/// ```
+pub async fn acknowledge_component_loading(
+	http: &DiscordHttpClient,
+	interaction: &Interaction,
+) -> Result<(), HttpError> {
+	http.send(CreateInteractionResponse::new(
+		interaction.id,
+		interaction.token.clone(),
+		loading_response(interaction),
+	))
+	.await
+}
+
+/// The `UpdateMessage` sent by [`acknowledge_component_loading`].
+fn loading_response(interaction: &Interaction) -> InteractionResponse {
+	let clicked = match &interaction.data {
+		Some(InteractionData::MessageComponent(data)) => {
+			data.custom_id.as_str()
+		}
+		_ => "",
+	};
+	let mut components = interaction
+		.message
+		.as_ref()
+		.map(|message| message.components.clone())
+		.unwrap_or_default();
+	for component in &mut components {
+		disable_component(component, clicked);
+	}
+	// Omitted content is left as it is.
+	InteractionResponse::update(InteractionResponseData {
+		components: Some(components),
+		..Default::default()
+	})
+}
+
+/// Disable `component` and any it contains, adding ⏳ to the button with
+/// `clicked` as its custom id.
+fn disable_component(component: &mut Component, clicked: &str) {
+	match component {
+		Component::ActionRow(row) => {
+			for child in &mut row.components {
+				disable_component(child, clicked);
+			}
+		}
+		Component::Container(container) => {
+			for child in &mut container.components {
+				disable_component(child, clicked);
+			}
+		}
+		Component::Section(section) => {
+			disable_component(&mut section.accessory, clicked)
+		}
+		Component::Button(button) => {
+			button.disabled = true;
+			if button.custom_id.as_deref() == Some(clicked) {
+				button.emoji =
+					Some(EmojiReactionType::Unicode { name: "⏳".into() });
+			}
+		}
+		Component::SelectMenu(select) => select.disabled = true,
+		_ => {}
+	}
+}
+
+fn custom_id_prefix(custom_id: &str) -> &str {
+	custom_id
+		.split_once(':')
+		.map_or(custom_id, |(prefix, _)| prefix)
+}
+
+/// Routes component interactions to handlers by custom-id prefix.
+///
+/// ```ignore
+/// let registry = ComponentRegistry::default()
+///     .with("reroll", reroll)
+///     .with_fallback(log_unhandled);
+/// registry.dispatch(ctx).await?;
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ComponentRegistry {
+	handlers: HashMap<String, ComponentHandler>,
+	fallback: Option<ComponentHandler>,
+}
+
+impl ComponentRegistry {
+	/// Route custom ids starting with `prefix` (followed by `:` or nothing)
+	/// to `handler`.
+	pub fn with(
+		mut self,
+		prefix: impl Into<String>,
+		handler: ComponentHandler,
+	) -> Self {
+		self.handlers.insert(prefix.into(), handler);
+		self
+	}
+
+	/// Handler for custom ids no prefix matches.
+	pub fn with_fallback(mut self, handler: ComponentHandler) -> Self {
+		self.fallback = Some(handler);
+		self
+	}
+
+	/// The handler `custom_id` routes to, if any.
+	pub fn handler_for(&self, custom_id: &str) -> Option<ComponentHandler> {
+		self.handlers
+			.get(custom_id_prefix(custom_id))
+			.copied()
+			.or(self.fallback)
+	}
+
+	/// Run the handler for `ctx`, logging ids nothing handles.
+	pub async fn dispatch(
+		&self,
+		ctx: ComponentContext,
+	) -> Result<(), HttpError> {
+		match self.handler_for(&ctx.custom_id) {
+			Some(handler) => handler(ctx).await,
+			None => {
+				info!(
+					custom_id = ctx.custom_id,
+					"unhandled component interaction"
+				);
+				Ok(())
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::atomic::AtomicUsize;
+	use std::sync::atomic::Ordering;
+	use twilight_model::http::interaction::InteractionResponseType;
+
+	fn component_interaction(custom_id: &str) -> Interaction {
+		serde_json::from_value(serde_json::json!({
+			"id": "1",
+			"application_id": "2",
+			"type": 3,
+			"token": "tok",
+			"authorizing_integration_owners": {},
+			"entitlements": [],
+			"data": {
+				"custom_id": custom_id,
+				"component_type": 2,
+			},
+		}))
+		.expect("valid interaction JSON")
+	}
+
+	fn context(custom_id: &str) -> ComponentContext {
+		ComponentContext::new(
+			DiscordHttpClient::new("token").dry_run(true),
+			component_interaction(custom_id),
+		)
+		.unwrap()
+	}
+
+	#[test]
+	fn context_splits_prefix_and_args() {
+		let ctx = context("reroll:20");
+		assert_eq!(ctx.prefix(), "reroll");
+		assert_eq!(ctx.args(), "20");
+		let ctx = context("language_select");
+		assert_eq!(ctx.prefix(), "language_select");
+		assert_eq!(ctx.args(), "");
+	}
+
+	static REROLLS: AtomicUsize = AtomicUsize::new(0);
+	static FALLBACKS: AtomicUsize = AtomicUsize::new(0);
+
+	fn count_reroll(ctx: ComponentContext) -> ComponentFuture {
+		Box::pin(async move {
+			assert_eq!(ctx.args(), "6");
+			REROLLS.fetch_add(1, Ordering::SeqCst);
+			Ok(())
+		})
+	}
+
+	fn count_fallback(_ctx: ComponentContext) -> ComponentFuture {
+		Box::pin(async move {
+			FALLBACKS.fetch_add(1, Ordering::SeqCst);
+			Ok(())
+		})
+	}
+
+	#[test]
+	fn registry_routes_by_prefix() {
+		let registry = ComponentRegistry::default()
+			.with("reroll", count_reroll)
+			.with_fallback(count_fallback);
+
+		futures_lite::future::block_on(registry.dispatch(context("reroll:6")))
+			.unwrap();
+		assert_eq!(REROLLS.load(Ordering::SeqCst), 1);
+		assert_eq!(FALLBACKS.load(Ordering::SeqCst), 0);
+
+		futures_lite::future::block_on(registry.dispatch(context("mystery:1")))
+			.unwrap();
+		assert_eq!(REROLLS.load(Ordering::SeqCst), 1);
+		assert_eq!(FALLBACKS.load(Ordering::SeqCst), 1);
+	}
+
+	#[test]
+	fn registry_without_fallback_ignores_unknown() {
+		let registry =
+			ComponentRegistry::default().with("reroll", count_reroll);
+		assert!(registry.handler_for("mystery").is_none());
+		// `rerolled` isn't the `reroll` prefix.
+		assert!(registry.handler_for("rerolled:6").is_none());
+		assert!(
+			futures_lite::future::block_on(registry.dispatch(context("x")))
+				.is_ok()
+		);
+	}
+
+	#[test]
+	fn component_reply_updates_by_default() {
+		let data = InteractionResponseData::default().with_content("hi");
+		let update = ComponentReply::from(data.clone()).into_response();
+		assert_eq!(update.kind, InteractionResponseType::UpdateMessage);
+		assert_eq!(update.data, Some(data.clone()));
+
+		let new = ComponentReply::new(data).as_new_message().into_response();
+		assert_eq!(new.kind, InteractionResponseType::ChannelMessageWithSource);
+	}
+
+	#[test]
+	fn loading_response_disables_all_components() {
+		let mut interaction = component_interaction("reroll:20");
+		interaction.message = Some(
+			serde_json::from_value(serde_json::json!({
+				"id": "5",
+				"channel_id": "6",
+				"author": {
+					"id": "7",
+					"username": "bot",
+					"discriminator": "0",
+					"avatar": null,
+				},
+				"content": "🎲 You rolled 4",
+				"timestamp": "2024-01-01T00:00:00.000000+00:00",
+				"edited_timestamp": null,
+				"tts": false,
+				"mention_everyone": false,
+				"mentions": [],
+				"mention_roles": [],
+				"attachments": [],
+				"embeds": [],
+				"pinned": false,
+				"type": 0,
+				"components": [{
+					"type": 1,
+					"components": [
+						{ "type": 2, "style": 1, "custom_id": "reroll:20" },
+						{ "type": 2, "style": 2, "custom_id": "done" },
+					],
+				}, {
+					"type": 1,
+					"components": [
+						{ "type": 3, "custom_id": "pick", "options": [] },
+					],
+				}],
+			}))
+			.unwrap(),
+		);
+
+		let response = loading_response(&interaction);
+		assert_eq!(response.kind, InteractionResponseType::UpdateMessage);
+		let data = response.data.unwrap();
+		assert_eq!(data.content, None);
+		let rows = data.components.unwrap();
+		let children: Vec<&Component> = rows
+			.iter()
+			.flat_map(|row| match row {
+				Component::ActionRow(row) => row.components.iter(),
+				other => panic!("expected an action row, got {:?}", other),
+			})
+			.collect();
+		assert_eq!(children.len(), 3);
+		for child in &children {
+			match child {
+				Component::Button(button) => assert!(button.disabled),
+				Component::SelectMenu(select) => assert!(select.disabled),
+				other => panic!("unexpected component {:?}", other),
+			}
+		}
+		// Only the clicked button is marked.
+		let marked: Vec<bool> = children
+			.iter()
+			.map(|child| match child {
+				Component::Button(button) => button.emoji.is_some(),
+				_ => false,
+			})
+			.collect();
+		assert_eq!(marked, [true, false, false]);
+	}
+
+	#[test]
+	fn new_rejects_non_component_interactions() {
+		let mut interaction = component_interaction("x");
+		interaction.data = None;
+		assert!(
+			ComponentContext::new(DiscordHttpClient::new("t"), interaction)
+				.is_none()
+		);
+	}
+}
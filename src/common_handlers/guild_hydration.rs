@@ -0,0 +1,249 @@
+//! Detects when the guilds listed in READY have finished loading.
+//!
+//! READY only lists guild ids as unavailable, the full data follows in a
+//! burst of GUILD_CREATEs. [`GuildHydration`] checks each one off and
+//! triggers [`DiscordGuildsReady`] once they have all arrived, or after a
+//! timeout for guilds stuck in an outage.
+use crate::prelude::*;
+use beet::core::time_ext;
+use beet::prelude::*;
+use std::time::Duration;
+use tracing::info;
+use tracing::warn;
+use twilight_model::gateway::payload::incoming::GuildCreate;
+use twilight_model::id::Id;
+use twilight_model::id::marker::GuildMarker;
+
+/// How long to wait for READY's guilds before giving up on the rest.
+pub const DEFAULT_HYDRATION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Tracks which of READY's guilds are still waiting on a GUILD_CREATE.
+#[derive(Debug, Clone, Component)]
+#[component(on_add=on_add)]
+pub struct GuildHydration {
+	timeout: Duration,
+	/// Bumped on every READY so a previous session's timeout is ignored.
+	session: u64,
+	pending: HashSet<Id<GuildMarker>>,
+	loaded: Vec<Id<GuildMarker>>,
+	done: bool,
+}
+
+impl Default for GuildHydration {
+	fn default() -> Self {
+		Self {
+			timeout: DEFAULT_HYDRATION_TIMEOUT,
+			session: 0,
+			pending: HashSet::default(),
+			loaded: Vec::new(),
+			// Nothing to wait for until READY arrives.
+			done: true,
+		}
+	}
+}
+
+/// The outcome of a READY's guild hydration.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HydratedGuilds {
+	/// Guilds whose GUILD_CREATE arrived, in arrival order.
+	pub loaded: Vec<Id<GuildMarker>>,
+	/// Guilds still unavailable when the timeout hit.
+	pub unavailable: Vec<Id<GuildMarker>>,
+}
+
+impl GuildHydration {
+	/// Wait `timeout` for READY's guilds instead of
+	/// [`DEFAULT_HYDRATION_TIMEOUT`].
+	pub fn with_timeout(timeout: Duration) -> Self {
+		Self {
+			timeout,
+			..Default::default()
+		}
+	}
+
+	/// Whether every READY guild has loaded or the timeout has passed.
+	pub fn is_done(&self) -> bool { self.done }
+
+	/// Guilds still waiting on a GUILD_CREATE.
+	pub fn pending(&self) -> &HashSet<Id<GuildMarker>> { &self.pending }
+
+	/// Start tracking the guilds from a new READY, finishing straight away
+	/// if there are none.
+	fn start(
+		&mut self,
+		guild_ids: impl IntoIterator<Item = Id<GuildMarker>>,
+	) -> Option<HydratedGuilds> {
+		self.session += 1;
+		self.pending = guild_ids.into_iter().collect();
+		self.loaded.clear();
+		self.done = false;
+		self.pending.is_empty().then(|| self.finish())
+	}
+
+	/// Check off a GUILD_CREATE, finishing if it was the last one.
+	fn guild_loaded(
+		&mut self,
+		guild_id: Id<GuildMarker>,
+	) -> Option<HydratedGuilds> {
+		if self.done || !self.pending.remove(&guild_id) {
+			return None;
+		}
+		self.loaded.push(guild_id);
+		self.pending.is_empty().then(|| self.finish())
+	}
+
+	/// Give up on the remaining guilds of `session`.
+	fn expire(&mut self, session: u64) -> Option<HydratedGuilds> {
+		if self.done || session != self.session {
+			return None;
+		}
+		Some(self.finish())
+	}
+
+	fn finish(&mut self) -> HydratedGuilds {
+		self.done = true;
+		let mut unavailable: Vec<_> = self.pending.drain().collect();
+		unavailable.sort();
+		HydratedGuilds {
+			loaded: std::mem::take(&mut self.loaded),
+			unavailable,
+		}
+	}
+}
+
+/// Triggered once all guilds listed in READY have loaded, or the
+/// hydration timeout passed with some still unavailable.
+#[derive(Debug, Clone, EntityEvent)]
+pub struct DiscordGuildsReady {
+	pub entity: Entity,
+	pub guilds: HydratedGuilds,
+}
+
+/// Fired when a READY's hydration timeout elapses.
+#[derive(EntityEvent)]
+struct HydrationTimeout {
+	entity: Entity,
+	session: u64,
+}
+
+fn on_add(mut world: DeferredWorld, cx: HookContext) {
+	world
+		.commands()
+		.entity(cx.entity)
+		.observe(start_hydration)
+		.observe(guild_hydrated)
+		.observe(hydration_timeout);
+}
+
+fn start_hydration(
+	ev: On<DiscordReady>,
+	mut commands: Commands,
+	mut query: Query<&mut GuildHydration>,
+) -> Result {
+	let entity = ev.event_target();
+	let mut hydration = query.get_mut(entity)?;
+	if let Some(guilds) = hydration.start(ev.guilds.iter().map(|g| g.id)) {
+		commands.trigger(DiscordGuildsReady { entity, guilds });
+		return Ok(());
+	}
+
+	let session = hydration.session;
+	let timeout = hydration.timeout;
+	commands.entity(entity).queue_async(async move |entity| {
+		time_ext::sleep(timeout).await;
+		entity.trigger(move |entity| HydrationTimeout { entity, session });
+		Ok(())
+	});
+	Ok(())
+}
+
+fn guild_hydrated(
+	ev: On<DiscordGuildCreate>,
+	mut commands: Commands,
+	mut query: Query<&mut GuildHydration>,
+) -> Result {
+	// Guilds in an outage stay pending until they recover or time out.
+	let GuildCreate::Available(guild) = &ev.guild_create else {
+		return Ok(());
+	};
+	let entity = ev.event_target();
+	if let Some(guilds) = query.get_mut(entity)?.guild_loaded(guild.id) {
+		info!(count = guilds.loaded.len(), "all READY guilds loaded");
+		commands.trigger(DiscordGuildsReady { entity, guilds });
+	}
+	Ok(())
+}
+
+fn hydration_timeout(
+	ev: On<HydrationTimeout>,
+	mut commands: Commands,
+	mut query: Query<&mut GuildHydration>,
+) -> Result {
+	let entity = ev.event_target();
+	if let Some(guilds) = query.get_mut(entity)?.expire(ev.session) {
+		warn!(
+			loaded = guilds.loaded.len(),
+			unavailable = guilds.unavailable.len(),
+			"timed out waiting for READY guilds"
+		);
+		commands.trigger(DiscordGuildsReady { entity, guilds });
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn ids(ids: &[u64]) -> Vec<Id<GuildMarker>> {
+		ids.iter().map(|&id| Id::new(id)).collect()
+	}
+
+	#[test]
+	fn fires_once_all_guilds_loaded() {
+		let mut hydration = GuildHydration::default();
+		assert!(hydration.start(ids(&[1, 2, 3])).is_none());
+		assert!(!hydration.is_done());
+
+		assert!(hydration.guild_loaded(Id::new(2)).is_none());
+		// Guilds not in READY, e.g. joined later, are ignored.
+		assert!(hydration.guild_loaded(Id::new(9)).is_none());
+		assert!(hydration.guild_loaded(Id::new(1)).is_none());
+		let done = hydration.guild_loaded(Id::new(3)).unwrap();
+
+		assert_eq!(done.loaded, ids(&[2, 1, 3]));
+		assert!(done.unavailable.is_empty());
+		assert!(hydration.is_done());
+		// Later GUILD_CREATEs don't fire again.
+		assert!(hydration.guild_loaded(Id::new(3)).is_none());
+	}
+
+	#[test]
+	fn fires_immediately_without_guilds() {
+		let mut hydration = GuildHydration::default();
+		assert_eq!(hydration.start(ids(&[])), Some(HydratedGuilds::default()));
+	}
+
+	#[test]
+	fn timeout_reports_unavailable_guilds() {
+		let mut hydration = GuildHydration::default();
+		hydration.start(ids(&[1, 2, 3]));
+		hydration.guild_loaded(Id::new(1));
+
+		let done = hydration.expire(hydration.session).unwrap();
+		assert_eq!(done.loaded, ids(&[1]));
+		assert_eq!(done.unavailable, ids(&[2, 3]));
+		assert!(hydration.pending().is_empty());
+	}
+
+	#[test]
+	fn stale_timeout_is_ignored() {
+		let mut hydration = GuildHydration::default();
+		hydration.start(ids(&[1]));
+		let stale = hydration.session;
+		hydration.start(ids(&[1, 2]));
+
+		assert!(hydration.expire(stale).is_none());
+		assert!(!hydration.is_done());
+	}
+}
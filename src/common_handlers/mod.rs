@@ -1,5 +1,17 @@
 mod bot_channel;
+mod bot_member_cache;
+pub use bot_member_cache::*;
 mod bot_state;
+mod command_context;
+pub use command_context::*;
+mod command_metrics;
+pub use command_metrics::*;
+mod component_context;
+pub use component_context::*;
+mod guild_hydration;
+pub use guild_hydration::*;
+mod reaction_roles;
+pub use reaction_roles::*;
 pub use bot_channel::*;
 mod command_demo;
 pub use bot_state::*;
@@ -0,0 +1,307 @@
+//! Reaction roles: members react to a configured message to get a role, and
+//! remove their reaction to drop it.
+//!
+//! The emoji → role mapping is read from a JSON file, usually the one
+//! [`REACTION_ROLES_ENV`] points to:
+//!
+//! ```json
+//! {
+//!     "channel_id": "100",
+//!     "message_id": "200",
+//!     "roles": { "🦀": "300", "blobcat:400": "500" }
+//! }
+//! ```
+//!
+//! On READY the bot adds any configured reaction still missing from the
+//! message, so members have something to click.
+use crate::prelude::*;
+use beet::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracing::info;
+use tracing::warn;
+use twilight_model::channel::message::EmojiReactionType;
+use twilight_model::channel::message::Reaction;
+use twilight_model::gateway::GatewayReaction;
+use twilight_model::id::Id;
+use twilight_model::id::marker::ChannelMarker;
+use twilight_model::id::marker::GuildMarker;
+use twilight_model::id::marker::MessageMarker;
+use twilight_model::id::marker::RoleMarker;
+use twilight_model::id::marker::UserMarker;
+
+/// Environment variable holding the path of the reaction-role config.
+pub const REACTION_ROLES_ENV: &str = "REACTION_ROLES_CONFIG";
+
+/// Which message hands out roles, and the role each emoji grants.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReactionRolesConfig {
+	pub channel_id: Id<ChannelMarker>,
+	pub message_id: Id<MessageMarker>,
+	/// Unicode emoji, or `name:id` for custom emoji, mapped to their role.
+	pub roles: BTreeMap<String, Id<RoleMarker>>,
+}
+
+impl ReactionRolesConfig {
+	/// Parse a config from JSON.
+	pub fn from_json(bytes: &[u8]) -> Result<Self, JsonError> {
+		parse_json(bytes)
+	}
+
+	/// Read and parse the config at `path`.
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, JsonError> {
+		let path = path.as_ref();
+		let bytes = std::fs::read(path).map_err(|e| {
+			JsonError(format!("failed to read {}: {}", path.display(), e))
+		})?;
+		Self::from_json(&bytes)
+	}
+
+	/// The role granted for reacting with `emoji`. Custom emoji are matched
+	/// by id, since their names can change.
+	pub fn role_for(
+		&self,
+		emoji: &EmojiReactionType,
+	) -> Option<Id<RoleMarker>> {
+		self.roles
+			.iter()
+			.find(|(key, _)| emoji_matches(key, emoji))
+			.map(|(_, role_id)| *role_id)
+	}
+
+	/// Configured emoji the bot hasn't reacted with yet, given the message's
+	/// current `reactions`.
+	pub fn missing_reactions(&self, reactions: &[Reaction]) -> Vec<&str> {
+		self.roles
+			.keys()
+			.filter(|key| {
+				!reactions.iter().any(|reaction| {
+					reaction.me && emoji_matches(key, &reaction.emoji)
+				})
+			})
+			.map(String::as_str)
+			.collect()
+	}
+}
+
+/// Whether a config key, a unicode emoji or `name:id`, names `emoji`.
+fn emoji_matches(key: &str, emoji: &EmojiReactionType) -> bool {
+	match emoji {
+		EmojiReactionType::Custom { id, .. } => key
+			.rsplit_once(':')
+			.is_some_and(|(_, key_id)| key_id == id.to_string()),
+		EmojiReactionType::Unicode { name } => key == name,
+	}
+}
+
+/// Grants and revokes roles for reactions on a configured message.
+///
+/// ```ignore
+/// if let Some(roles) = ReactionRoles::from_env() {
+///     commands.entity(bot).insert(roles);
+/// }
+/// ```
+#[derive(Debug, Clone, Component)]
+#[component(on_add=on_add)]
+pub struct ReactionRoles {
+	pub config: ReactionRolesConfig,
+}
+
+impl ReactionRoles {
+	pub fn new(config: ReactionRolesConfig) -> Self { Self { config } }
+
+	/// Load the config [`REACTION_ROLES_ENV`] points to, `None` if it isn't
+	/// set or the file can't be parsed.
+	pub fn from_env() -> Option<Self> {
+		let path = env_ext::var(REACTION_ROLES_ENV).ok()?;
+		match ReactionRolesConfig::load(&path) {
+			Ok(config) => Some(Self::new(config)),
+			Err(e) => {
+				warn!(error = %e, path, "ignoring reaction-role config");
+				None
+			}
+		}
+	}
+
+	/// The guild and role a reaction by someone other than the bot on the
+	/// configured message maps to.
+	fn target(
+		&self,
+		bot_user_id: Id<UserMarker>,
+		reaction: &GatewayReaction,
+	) -> Option<(Id<GuildMarker>, Id<RoleMarker>)> {
+		if reaction.message_id != self.config.message_id
+			|| reaction.user_id == bot_user_id
+		{
+			return None;
+		}
+		Some((reaction.guild_id?, self.config.role_for(&reaction.emoji)?))
+	}
+}
+
+fn on_add(mut world: DeferredWorld, cx: HookContext) {
+	world
+		.commands()
+		.entity(cx.entity)
+		.observe(add_missing_reactions)
+		.observe(grant_reaction_role)
+		.observe(revoke_reaction_role);
+}
+
+/// Reacts to the configured message with every emoji it is missing.
+fn add_missing_reactions(
+	ev: On<DiscordReady>,
+	mut commands: Commands,
+	query: Query<(&ReactionRoles, &DiscordHttpClient)>,
+) -> Result {
+	let (roles, http) = query.get(ev.event_target())?;
+	let config = roles.config.clone();
+	let http = http.clone();
+	commands.queue_async(async move |_| {
+		let message = http
+			.send(GetMessage::new(config.channel_id, config.message_id))
+			.await?;
+		for emoji in config.missing_reactions(&message.reactions) {
+			info!(emoji, "adding missing reaction-role reaction");
+			http.send(CreateReaction::new(
+				config.channel_id,
+				config.message_id,
+				emoji,
+			))
+			.await?;
+		}
+		Ok(())
+	});
+	Ok(())
+}
+
+fn grant_reaction_role(
+	ev: On<DiscordReactionAdd>,
+	mut commands: Commands,
+	query: Query<(
+		&ReactionRoles,
+		&BotState,
+		&DiscordHttpClient,
+		&HandlerLimit,
+	)>,
+) -> Result {
+	let (roles, bot_state, http, limit) = query.get(ev.event_target())?;
+	let Some((guild_id, role_id)) =
+		roles.target(bot_state.user_id(), &ev.reaction.0)
+	else {
+		return Ok(());
+	};
+	let user_id = ev.user_id;
+	let http = http.clone();
+	let limit = limit.clone();
+	info!(%user_id, %role_id, "granting reaction role");
+	commands.queue_async(async move |_| {
+		let _permit = limit.acquire().await;
+		http.send(AddGuildMemberRole::new(guild_id, user_id, role_id))
+			.await?;
+		Ok(())
+	});
+	Ok(())
+}
+
+fn revoke_reaction_role(
+	ev: On<DiscordReactionRemove>,
+	mut commands: Commands,
+	query: Query<(
+		&ReactionRoles,
+		&BotState,
+		&DiscordHttpClient,
+		&HandlerLimit,
+	)>,
+) -> Result {
+	let (roles, bot_state, http, limit) = query.get(ev.event_target())?;
+	let Some((guild_id, role_id)) =
+		roles.target(bot_state.user_id(), &ev.reaction.0)
+	else {
+		return Ok(());
+	};
+	let user_id = ev.user_id;
+	let http = http.clone();
+	let limit = limit.clone();
+	info!(%user_id, %role_id, "revoking reaction role");
+	commands.queue_async(async move |_| {
+		let _permit = limit.acquire().await;
+		http.send(RemoveGuildMemberRole::new(guild_id, user_id, role_id))
+			.await?;
+		Ok(())
+	});
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn config() -> ReactionRolesConfig {
+		let json = r#"{
+			"channel_id": "100",
+			"message_id": "200",
+			"roles": { "🦀": "300", "blobcat:400": "500" }
+		}"#;
+		ReactionRolesConfig::from_json(json.as_bytes()).unwrap()
+	}
+
+	fn reaction(emoji: serde_json::Value, me: bool) -> Reaction {
+		serde_json::from_value(serde_json::json!({
+			"burst_colors": [],
+			"count": 1,
+			"count_details": { "burst": 0, "normal": 1 },
+			"emoji": emoji,
+			"me": me,
+			"me_burst": false,
+		}))
+		.unwrap()
+	}
+
+	#[test]
+	fn config_parses_ids_and_emoji() {
+		let config = config();
+		assert_eq!(config.channel_id.get(), 100);
+		assert_eq!(config.message_id.get(), 200);
+		assert_eq!(config.roles["🦀"].get(), 300);
+		assert_eq!(config.roles["blobcat:400"].get(), 500);
+		assert!(ReactionRolesConfig::from_json(br#"{"roles": {}}"#).is_err());
+	}
+
+	#[test]
+	fn role_lookup_matches_unicode_and_custom_ids() {
+		let config = config();
+		let crab = EmojiReactionType::Unicode {
+			name: "🦀".into()
+		};
+		assert_eq!(config.role_for(&crab).map(|id| id.get()), Some(300));
+		// Custom emoji match by id even after a rename.
+		let renamed = EmojiReactionType::Custom {
+			animated: false,
+			id: Id::new(400),
+			name: Some("blobcat_v2".into()),
+		};
+		assert_eq!(config.role_for(&renamed).map(|id| id.get()), Some(500));
+		let other = EmojiReactionType::Unicode {
+			name: "👍".into()
+		};
+		assert!(config.role_for(&other).is_none());
+	}
+
+	#[test]
+	fn missing_reactions_skips_ones_the_bot_added() {
+		let config = config();
+		let reactions = [
+			reaction(serde_json::json!({ "id": null, "name": "🦀" }), true),
+			// Someone else's reaction still needs the bot's own.
+			reaction(
+				serde_json::json!({ "id": "400", "name": "blobcat" }),
+				false,
+			),
+		];
+		assert_eq!(config.missing_reactions(&reactions), ["blobcat:400"]);
+		assert_eq!(config.missing_reactions(&[]).len(), 2);
+	}
+}
@@ -5,13 +5,17 @@
 //!   - background heartbeat task
 //!   - sequence number + session_id tracking
 //!   - automatic reconnect + RESUME on disconnect
+//!   - queueing outbound payloads across reconnects
 //!   - gateway send rate limiting (120 events / 60s)
 //!
 //! The rest of the codebase consumes a stream of [`GatewayEvent`] values
 //! without ever touching the underlying WebSocket transport directly — when
 //! we swap transports we only need to touch this file.
 
+use std::collections::VecDeque;
 use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -39,10 +43,15 @@ use tracing::warn;
 use crate::tw_gateway::parse_gateway_event;
 use crate::tw_gateway::CloseAction;
 use crate::tw_gateway::GatewayPayload;
+use crate::tw_gateway::UnknownDispatch;
+use crate::tw_gateway::parse_unknown_dispatch;
 use twilight_model::gateway::event::DispatchEvent;
 use twilight_model::gateway::event::GatewayEvent;
 use twilight_model::gateway::Intents;
 use twilight_model::gateway::OpCode;
+use twilight_model::id::Id;
+use twilight_model::id::marker::ChannelMarker;
+use twilight_model::id::marker::GuildMarker;
 
 // ---------------------------------------------------------------------------
 // Constants
@@ -120,6 +129,58 @@ impl SendRateLimiter {
 	}
 }
 
+// ---------------------------------------------------------------------------
+// Outbound user payloads
+// ---------------------------------------------------------------------------
+
+/// Holds payloads from [`GatewayHandle::sender`] while there's no session to
+/// send them on, i.e. between a disconnect and the next READY/RESUMED.
+///
+/// Only the latest presence update (op 3) is kept, since each one replaces
+/// the last; other payloads are sent in order.
+#[derive(Debug, Default)]
+struct Outbox {
+	/// Whether READY/RESUMED has been seen on the current connection.
+	ready: bool,
+	pending: VecDeque<serde_json::Value>,
+}
+
+impl Outbox {
+	/// Returns `payload` if it can be sent right away, otherwise queues it.
+	fn send_or_queue(
+		&mut self,
+		payload: serde_json::Value,
+	) -> Option<serde_json::Value> {
+		if self.ready {
+			return Some(payload);
+		}
+		self.queue(payload);
+		None
+	}
+
+	/// Queue `payload` until the next READY/RESUMED, replacing any queued
+	/// presence update if it is one.
+	fn queue(&mut self, payload: serde_json::Value) {
+		if is_presence_update(&payload) {
+			self.pending.retain(|queued| !is_presence_update(queued));
+		}
+		self.pending.push_back(payload);
+	}
+
+	/// The connection dropped; hold payloads until the session is back.
+	fn disconnected(&mut self) { self.ready = false; }
+
+	/// READY/RESUMED arrived, returns the queued payloads to flush in order.
+	fn ready(&mut self) -> Vec<serde_json::Value> {
+		self.ready = true;
+		self.pending.drain(..).collect()
+	}
+}
+
+fn is_presence_update(payload: &serde_json::Value) -> bool {
+	payload["op"] == OpCode::PresenceUpdate as u8
+}
+
 // ---------------------------------------------------------------------------
 // Configuration
 // ---------------------------------------------------------------------------
@@ -146,6 +207,65 @@ struct SessionState {
 	resume_gateway_url: Option<String>,
 	/// Monotonically increasing sequence counter.
 	sequence: Option<u64>,
+	/// A RESUME was sent and its outcome hasn't been observed yet.
+	resume_pending: bool,
+}
+
+// ---------------------------------------------------------------------------
+// Metrics
+// ---------------------------------------------------------------------------
+
+/// Counters describing how gateway reconnects are going, shared between the
+/// driver task and [`GatewayHandle::metrics`].
+///
+/// A resume *succeeds* when Discord answers with `RESUMED`, and *fails* when
+/// it answers with a fresh `READY` or a non-resumable op 9 instead.
+#[derive(Debug, Default)]
+pub struct GatewayMetrics {
+	resume_attempts: AtomicU64,
+	resume_successes: AtomicU64,
+	resume_failures: AtomicU64,
+	/// Reconnects after an established connection dropped, never reset.
+	reconnects: AtomicU64,
+	/// When the last `READY` arrived.
+	connected_since: std::sync::Mutex<Option<Instant>>,
+}
+
+impl GatewayMetrics {
+	/// Number of RESUME payloads sent.
+	pub fn resume_attempts(&self) -> u64 {
+		self.resume_attempts.load(Ordering::Relaxed)
+	}
+
+	/// Number of resumes acknowledged with `RESUMED`.
+	pub fn resume_successes(&self) -> u64 {
+		self.resume_successes.load(Ordering::Relaxed)
+	}
+
+	/// Number of resumes that fell through to a fresh session.
+	pub fn resume_failures(&self) -> u64 {
+		self.resume_failures.load(Ordering::Relaxed)
+	}
+
+	/// Total reconnects since the driver started. Unlike the driver's
+	/// backoff counter this is not reset by a successful connection.
+	pub fn reconnects(&self) -> u64 { self.reconnects.load(Ordering::Relaxed) }
+
+	/// When the last `READY` was received, `None` before the first one.
+	/// A successful resume keeps the original session, so doesn't reset this.
+	pub fn connected_since(&self) -> Option<Instant> {
+		*self
+			.connected_since
+			.lock()
+			.unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+
+	fn record_ready(&self) {
+		*self
+			.connected_since
+			.lock()
+			.unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Instant::now());
+	}
 }
 
 // ---------------------------------------------------------------------------
@@ -158,11 +278,45 @@ struct SessionState {
 /// (e.g. request guild members, update presence).  The background tasks will
 /// keep running until the handle is dropped or an unrecoverable error occurs.
 pub struct GatewayHandle {
-	/// Send arbitrary JSON payloads on the gateway (rate-limited).
+	/// Send arbitrary JSON payloads on the gateway (rate-limited). Payloads
+	/// sent while reconnecting are held until the session is back.
 	#[allow(dead_code)]
 	pub sender: Sender<serde_json::Value>,
 	/// Receive typed events.
 	pub events: Receiver<GatewayEvent>,
+	/// Receive dispatches twilight-model doesn't recognise.
+	pub unknown: Receiver<UnknownDispatch>,
+	/// Resume counters, updated live by the driver task.
+	pub metrics: Arc<GatewayMetrics>,
+}
+
+impl GatewayHandle {
+	/// When the current session's `READY` arrived, see
+	/// [`GatewayMetrics::connected_since`].
+	pub fn connected_since(&self) -> Option<Instant> {
+		self.metrics.connected_since()
+	}
+
+	/// Total reconnects since [`GatewayConfig::connect`].
+	pub fn reconnect_count(&self) -> u64 { self.metrics.reconnects() }
+
+	/// Join a voice channel, or leave with `channel_id: None` (op 4).
+	///
+	/// This only makes the bot appear in the channel; no voice connection
+	/// is opened.
+	pub async fn update_voice_state(
+		&self,
+		guild_id: Id<GuildMarker>,
+		channel_id: Option<Id<ChannelMarker>>,
+		self_mute: bool,
+		self_deaf: bool,
+	) -> crate::error::Result<()> {
+		let payload =
+			voice_state_payload(guild_id, channel_id, self_mute, self_deaf);
+		self.sender.send(payload).await.map_err(|_| {
+			crate::error::Error::Gateway("gateway driver has stopped".into())
+		})
+	}
 }
 
 impl GatewayConfig {
@@ -173,15 +327,26 @@ impl GatewayConfig {
 	///   - heartbeating at the interval Discord tells us
 	///   - reconnecting + resuming on disconnects
 	///   - rate-limiting outbound sends
-	pub async fn connect(self) -> Result<GatewayHandle, String> {
+	pub async fn connect(self) -> crate::error::Result<GatewayHandle> {
 		let (event_tx, event_rx) = bounded::<GatewayEvent>(256);
 		let (send_tx, send_rx) = bounded::<serde_json::Value>(64);
-
-		async_ext::spawn(gateway_driver(self, event_tx, send_rx)).detach();
+		let (unknown_tx, unknown_rx) = bounded::<UnknownDispatch>(64);
+		let metrics = Arc::new(GatewayMetrics::default());
+
+		async_ext::spawn(gateway_driver(
+			self,
+			event_tx,
+			unknown_tx,
+			send_rx,
+			Arc::clone(&metrics),
+		))
+		.detach();
 
 		Ok(GatewayHandle {
 			sender: send_tx,
 			events: event_rx,
+			unknown: unknown_rx,
+			metrics,
 		})
 	}
 }
@@ -194,9 +359,12 @@ impl GatewayConfig {
 async fn gateway_driver(
 	config: GatewayConfig,
 	event_tx: Sender<GatewayEvent>,
+	unknown_tx: Sender<UnknownDispatch>,
 	send_rx: Receiver<serde_json::Value>,
+	metrics: Arc<GatewayMetrics>,
 ) {
 	let session = Arc::new(Mutex::new(SessionState::default()));
+	let mut outbox = Outbox::default();
 	let mut reconnect_attempts: u32 = 0;
 
 	loop {
@@ -238,6 +406,7 @@ async fn gateway_driver(
 						s.session_id = None;
 						s.sequence = None;
 						s.resume_gateway_url = None;
+						s.resume_pending = false;
 					}
 
 					reconnect_attempts += 1;
@@ -251,7 +420,7 @@ async fn gateway_driver(
 						attempt = reconnect_attempts,
 						"backing off before reconnect"
 					);
-					time_ext::sleep(backoff).await;
+					sleep_queueing(backoff, &send_rx, &mut outbox).await;
 					continue;
 				}
 			};
@@ -291,7 +460,7 @@ async fn gateway_driver(
 		};
 
 		if should_resume {
-			let s = session.lock().await;
+			let mut s = session.lock().await;
 			let resume = json!({
 				"op": OpCode::Resume,
 				"d": {
@@ -300,7 +469,9 @@ async fn gateway_driver(
 					"seq": s.sequence.unwrap(),
 				}
 			});
+			s.resume_pending = true;
 			drop(s);
+			metrics.resume_attempts.fetch_add(1, Ordering::Relaxed);
 			if let Err(e) =
 				rate_limited_send(&ws_write, &rate_limiter, &resume).await
 			{
@@ -310,7 +481,7 @@ async fn gateway_driver(
 				time_ext::sleep(backoff).await;
 				continue;
 			}
-			info!("sent RESUME");
+			info!(attempts = metrics.resume_attempts(), "sent RESUME");
 		} else {
 			let mut identify = json!({
 				"op": OpCode::Identify,
@@ -414,11 +585,15 @@ async fn gateway_driver(
 			&ws_write,
 			&rate_limiter,
 			&event_tx,
+			&unknown_tx,
 			&session,
 			&config,
 			&send_rx,
+			&mut outbox,
+			&metrics,
 		)
 		.await;
+		outbox.disconnected();
 
 		// ------------------------------------------------------------------
 		// 5.  Cleanup — cancel heartbeat, decide whether to reconnect
@@ -443,6 +618,7 @@ async fn gateway_driver(
 				s.session_id = None;
 				s.sequence = None;
 				s.resume_gateway_url = None;
+				s.resume_pending = false;
 			}
 			DisconnectReason::Fatal => {
 				error!("fatal gateway error, shutting down");
@@ -454,7 +630,7 @@ async fn gateway_driver(
 			}
 		}
 
-		reconnect_attempts += 1;
+		note_reconnect(&mut reconnect_attempts, &metrics);
 		if reconnect_attempts > MAX_RECONNECT_ATTEMPTS {
 			error!("exceeded max reconnect attempts, giving up");
 			return;
@@ -465,7 +641,7 @@ async fn gateway_driver(
 			attempt = reconnect_attempts,
 			"reconnecting after backoff"
 		);
-		time_ext::sleep(backoff).await;
+		sleep_queueing(backoff, &send_rx, &mut outbox).await;
 	}
 }
 
@@ -490,9 +666,12 @@ async fn read_loop(
 	ws_write: &Arc<Mutex<SocketWrite>>,
 	rate_limiter: &Arc<Mutex<SendRateLimiter>>,
 	event_tx: &Sender<GatewayEvent>,
+	unknown_tx: &Sender<UnknownDispatch>,
 	session: &Arc<Mutex<SessionState>>,
 	_config: &GatewayConfig,
 	send_rx: &Receiver<serde_json::Value>,
+	outbox: &mut Outbox,
+	metrics: &GatewayMetrics,
 ) -> DisconnectReason {
 	loop {
 		enum Sel {
@@ -513,12 +692,12 @@ async fn read_loop(
 		.await;
 
 		match sel {
-			// Outbound sends from the bot logic (e.g. update presence).
+			// Outbound sends from the bot logic (e.g. update presence),
+			// held in the outbox until the session is READY/RESUMED.
 			Sel::Send(payload) => {
-				if let Err(e) =
-					rate_limited_send(ws_write, rate_limiter, &payload).await
-				{
-					warn!(error = %e, "failed to send user payload on gateway");
+				if let Some(payload) = outbox.send_or_queue(payload) {
+					send_user_payload(ws_write, rate_limiter, outbox, payload)
+						.await;
 				}
 			}
 
@@ -552,17 +731,37 @@ async fn read_loop(
 						let event = match parse_gateway_event(&text) {
 							Ok(ev) => ev,
 							Err(e) => {
-								warn!(error = %e, "failed to parse gateway event");
+								match parse_unknown_dispatch(&text) {
+									Some(unknown) => {
+										debug!(
+											event = %unknown.event_name,
+											"forwarding unknown dispatch"
+										);
+										// Don't stall typed events if
+										// nobody drains this channel.
+										let _ = unknown_tx.try_send(unknown);
+									}
+									None => {
+										warn!(error = %e, "failed to parse gateway event");
+									}
+								}
 								continue;
 							}
 						};
 
+						track_resume_outcome(
+							&event,
+							&mut *session.lock().await,
+							metrics,
+						);
+
 						// Handle session-relevant events internally.
 						match &event {
 							GatewayEvent::Dispatch(
 								_,
 								DispatchEvent::Ready(ready),
 							) => {
+								metrics.record_ready();
 								let mut sess = session.lock().await;
 								sess.session_id =
 									Some(ready.session_id.clone());
@@ -573,6 +772,17 @@ async fn read_loop(
 									user = %ready.user.name,
 									"gateway READY"
 								);
+								drop(sess);
+								flush_outbox(ws_write, rate_limiter, outbox)
+									.await;
+							}
+
+							GatewayEvent::Dispatch(
+								_,
+								DispatchEvent::Resumed,
+							) => {
+								flush_outbox(ws_write, rate_limiter, outbox)
+									.await;
 							}
 
 							GatewayEvent::Heartbeat => {
@@ -681,6 +891,112 @@ async fn read_loop(
 // Helpers
 // ---------------------------------------------------------------------------
 
+/// Count a reconnect after an established connection dropped, both towards
+/// the backoff `reconnect_attempts` and the cumulative
+/// [`GatewayMetrics::reconnects`].
+fn note_reconnect(reconnect_attempts: &mut u32, metrics: &GatewayMetrics) {
+	*reconnect_attempts += 1;
+	metrics.reconnects.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Send a user payload, queueing it again if the send fails so it goes out
+/// after the next reconnect.
+async fn send_user_payload(
+	ws_write: &Arc<Mutex<SocketWrite>>,
+	rate_limiter: &Arc<Mutex<SendRateLimiter>>,
+	outbox: &mut Outbox,
+	payload: serde_json::Value,
+) {
+	if let Err(e) = rate_limited_send(ws_write, rate_limiter, &payload).await {
+		warn!(error = %e, "failed to send user payload, queueing for reconnect");
+		outbox.disconnected();
+		outbox.queue(payload);
+	}
+}
+
+/// Send everything queued while the session was down.
+async fn flush_outbox(
+	ws_write: &Arc<Mutex<SocketWrite>>,
+	rate_limiter: &Arc<Mutex<SendRateLimiter>>,
+	outbox: &mut Outbox,
+) {
+	let queued = outbox.ready();
+	if !queued.is_empty() {
+		info!(count = queued.len(), "flushing queued gateway payloads");
+	}
+	for payload in queued {
+		send_user_payload(ws_write, rate_limiter, outbox, payload).await;
+	}
+}
+
+/// Sleep for `delay`, moving user payloads into `outbox` meanwhile so
+/// [`GatewayHandle::sender`] doesn't block while reconnecting.
+async fn sleep_queueing(
+	delay: Duration,
+	send_rx: &Receiver<serde_json::Value>,
+	outbox: &mut Outbox,
+) {
+	let deadline = Instant::now() + delay;
+	loop {
+		let remaining = deadline.saturating_duration_since(Instant::now());
+		if remaining.is_zero() {
+			return;
+		}
+		let payload = race(
+			async {
+				time_ext::sleep(remaining).await;
+				None
+			},
+			async {
+				match send_rx.recv().await {
+					Ok(payload) => Some(payload),
+					// Sender dropped, just wait out the delay.
+					Err(_) => futures_lite::future::pending().await,
+				}
+			},
+		)
+		.await;
+		match payload {
+			Some(payload) => outbox.queue(payload),
+			None => return,
+		}
+	}
+}
+
+/// Resolve a pending RESUME from the first event that decides it, updating
+/// `metrics` and logging the outcome. Other events leave it pending.
+fn track_resume_outcome(
+	event: &GatewayEvent,
+	session: &mut SessionState,
+	metrics: &GatewayMetrics,
+) {
+	if !session.resume_pending {
+		return;
+	}
+	let succeeded = match event {
+		GatewayEvent::Dispatch(_, DispatchEvent::Resumed) => true,
+		GatewayEvent::Dispatch(_, DispatchEvent::Ready(_))
+		| GatewayEvent::InvalidateSession(false) => false,
+		_ => return,
+	};
+	session.resume_pending = false;
+	if succeeded {
+		metrics.resume_successes.fetch_add(1, Ordering::Relaxed);
+		info!(
+			successes = metrics.resume_successes(),
+			attempts = metrics.resume_attempts(),
+			"RESUME succeeded"
+		);
+	} else {
+		metrics.resume_failures.fetch_add(1, Ordering::Relaxed);
+		warn!(
+			failures = metrics.resume_failures(),
+			attempts = metrics.resume_attempts(),
+			"RESUME failed, session expired and a fresh one will be used"
+		);
+	}
+}
+
 /// Read the HELLO payload from an already-split stream reference.
 async fn read_hello_from_stream(
 	stream: &mut SocketRead,
@@ -756,9 +1072,247 @@ async fn rate_limited_send(
 		.map_err(|e| format!("WS send error: {}", e))
 }
 
+/// Build an op 4 voice state update; a `None` channel serialises as `null`,
+/// which tells Discord to disconnect.
+fn voice_state_payload(
+	guild_id: Id<GuildMarker>,
+	channel_id: Option<Id<ChannelMarker>>,
+	self_mute: bool,
+	self_deaf: bool,
+) -> serde_json::Value {
+	json!({
+		"op": OpCode::VoiceStateUpdate,
+		"d": {
+			"guild_id": guild_id,
+			"channel_id": channel_id,
+			"self_mute": self_mute,
+			"self_deaf": self_deaf,
+		}
+	})
+}
+
 /// Exponential backoff with jitter, capped at 60 s.
 fn backoff_delay(attempt: u32) -> Duration {
+	backoff_delay_with_jitter(attempt, rand::random::<f64>())
+}
+
+/// [`backoff_delay`] with an explicit jitter sample in `[0, 1)`.
+///
+/// The base delay doubles per attempt from 1 s and is scaled by
+/// `0.75..1.25` depending on `jitter`, so `0.5` yields the exact base.
+fn backoff_delay_with_jitter(attempt: u32, jitter: f64) -> Duration {
 	let base_ms = 1000u64 * 2u64.saturating_pow(attempt.min(6));
-	let jitter = (rand::random::<f64>() * 0.5 + 0.75) * base_ms as f64;
-	Duration::from_millis(jitter.min(60_000.0) as u64)
+	let jittered = (jitter * 0.5 + 0.75) * base_ms as f64;
+	Duration::from_millis(jittered.min(60_000.0) as u64)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn pending_session() -> SessionState {
+		SessionState {
+			session_id: Some("abc".to_string()),
+			sequence: Some(42),
+			resume_pending: true,
+			..Default::default()
+		}
+	}
+
+	// -- track_resume_outcome() --------------------------------------------
+
+	#[test]
+	fn resumed_counts_as_success() {
+		let metrics = GatewayMetrics::default();
+		let mut session = pending_session();
+		metrics.resume_attempts.fetch_add(1, Ordering::Relaxed);
+
+		// A heartbeat ack doesn't decide the outcome.
+		track_resume_outcome(
+			&GatewayEvent::HeartbeatAck,
+			&mut session,
+			&metrics,
+		);
+		assert!(session.resume_pending);
+
+		track_resume_outcome(
+			&GatewayEvent::Dispatch(43, DispatchEvent::Resumed),
+			&mut session,
+			&metrics,
+		);
+		assert!(!session.resume_pending);
+		assert_eq!(metrics.resume_attempts(), 1);
+		assert_eq!(metrics.resume_successes(), 1);
+		assert_eq!(metrics.resume_failures(), 0);
+	}
+
+	#[test]
+	fn non_resumable_invalid_session_counts_as_failure() {
+		let metrics = GatewayMetrics::default();
+		let mut session = pending_session();
+		track_resume_outcome(
+			&GatewayEvent::InvalidateSession(false),
+			&mut session,
+			&metrics,
+		);
+		assert!(!session.resume_pending);
+		assert_eq!(metrics.resume_successes(), 0);
+		assert_eq!(metrics.resume_failures(), 1);
+	}
+
+	#[test]
+	fn resumed_without_pending_resume_is_ignored() {
+		let metrics = GatewayMetrics::default();
+		let mut session = SessionState::default();
+		track_resume_outcome(
+			&GatewayEvent::Dispatch(1, DispatchEvent::Resumed),
+			&mut session,
+			&metrics,
+		);
+		assert_eq!(metrics.resume_successes(), 0);
+	}
+
+	// -- note_reconnect() --------------------------------------------------
+
+	#[test]
+	fn reconnect_count_survives_successful_connect() {
+		let metrics = GatewayMetrics::default();
+		let mut reconnect_attempts = 0;
+
+		note_reconnect(&mut reconnect_attempts, &metrics);
+		// A successful connect resets the backoff counter only.
+		reconnect_attempts = 0;
+		note_reconnect(&mut reconnect_attempts, &metrics);
+
+		assert_eq!(reconnect_attempts, 1);
+		assert_eq!(metrics.reconnects(), 2);
+	}
+
+	#[test]
+	fn connected_since_set_on_ready() {
+		let metrics = GatewayMetrics::default();
+		assert!(metrics.connected_since().is_none());
+		let before = Instant::now();
+		metrics.record_ready();
+		assert!(metrics.connected_since().unwrap() >= before);
+	}
+
+	// -- Outbox ------------------------------------------------------------
+
+	fn presence(status: &str) -> serde_json::Value {
+		json!({
+			"op": OpCode::PresenceUpdate as u8,
+			"d": {
+				"status": status,
+				"activities": [],
+				"afk": false,
+				"since": null,
+			}
+		})
+	}
+
+	#[test]
+	fn payload_queued_during_disconnect_is_sent_after_reconnect() {
+		let mut outbox = Outbox::default();
+		assert!(outbox.ready().is_empty());
+		let members = json!({
+			"op": OpCode::RequestGuildMembers as u8,
+			"d": { "guild_id": "10", "query": "", "limit": 0 }
+		});
+		assert_eq!(
+			outbox.send_or_queue(members.clone()),
+			Some(members.clone())
+		);
+
+		outbox.disconnected();
+		assert!(outbox.send_or_queue(members.clone()).is_none());
+		// Flushed on the next READY/RESUMED, then sent directly again.
+		assert_eq!(outbox.ready(), [members.clone()]);
+		assert!(outbox.ready().is_empty());
+		assert_eq!(outbox.send_or_queue(members.clone()), Some(members));
+	}
+
+	#[test]
+	fn queued_presence_updates_coalesce_to_latest() {
+		let mut outbox = Outbox::default();
+		let voice = voice_state_payload(Id::new(10), None, false, false);
+		outbox.queue(presence("idle"));
+		outbox.queue(voice.clone());
+		outbox.queue(presence("dnd"));
+		assert_eq!(outbox.ready(), [voice, presence("dnd")]);
+	}
+
+	// -- voice_state_payload() --------------------------------------------
+
+	#[test]
+	fn voice_state_payload_join() {
+		let payload =
+			voice_state_payload(Id::new(10), Some(Id::new(20)), false, true);
+		assert_eq!(
+			payload,
+			json!({
+				"op": OpCode::VoiceStateUpdate as u8,
+				"d": {
+					"guild_id": "10",
+					"channel_id": "20",
+					"self_mute": false,
+					"self_deaf": true,
+				}
+			})
+		);
+	}
+
+	#[test]
+	fn voice_state_payload_leave_sends_null_channel() {
+		let payload = voice_state_payload(Id::new(10), None, false, false);
+		assert_eq!(payload["op"], OpCode::VoiceStateUpdate as u8);
+		assert!(payload["d"]["channel_id"].is_null());
+		assert!(payload["d"].get("channel_id").is_some());
+	}
+
+	// -- backoff_delay_with_jitter() ---------------------------------------
+
+	#[test]
+	fn backoff_doubles_per_attempt() {
+		let delays: Vec<u64> = (0..6)
+			.map(|a| backoff_delay_with_jitter(a, 0.5).as_millis() as u64)
+			.collect();
+		assert_eq!(delays, vec![1000, 2000, 4000, 8000, 16000, 32000]);
+	}
+
+	#[test]
+	fn backoff_caps_at_sixty_seconds() {
+		for attempt in [6, 7, 20, u32::MAX] {
+			assert_eq!(
+				backoff_delay_with_jitter(attempt, 0.99),
+				Duration::from_secs(60)
+			);
+		}
+	}
+
+	#[test]
+	fn backoff_jitter_bounds() {
+		assert_eq!(
+			backoff_delay_with_jitter(1, 0.0),
+			Duration::from_millis(1500)
+		);
+		assert_eq!(
+			backoff_delay_with_jitter(1, 1.0),
+			Duration::from_millis(2500)
+		);
+	}
+
+	#[test]
+	fn backoff_default_stays_within_jitter_range() {
+		for attempt in 0..8 {
+			let delay = backoff_delay(attempt);
+			let low = backoff_delay_with_jitter(attempt, 0.0);
+			let high = backoff_delay_with_jitter(attempt, 1.0);
+			assert!(delay >= low && delay <= high);
+		}
+	}
 }
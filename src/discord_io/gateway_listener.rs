@@ -6,7 +6,10 @@
 //! mutexes are needed in the bot layer.
 
 use crate::prelude::*;
+use async_lock::Semaphore;
+use async_lock::SemaphoreGuard;
 use beet::prelude::*;
+use std::sync::Arc;
 use twilight_model::gateway::event::DispatchEvent;
 use twilight_model::gateway::event::GatewayEvent;
 use twilight_model::gateway::Intents;
@@ -16,13 +19,95 @@ use twilight_model::gateway::Intents;
 // Gateway intents
 // ---------------------------------------------------------------------------
 
-/// Build the gateway intents using strongly-typed [`Intents`] bitflags.
-fn gateway_intents() -> Intents {
+/// Build the gateway intents using strongly-typed [`Intents`] bitflags, the
+/// default [`GatewayIntents`].
+pub fn gateway_intents() -> Intents {
 	Intents::GUILDS
 		| Intents::GUILD_MEMBERS
 		| Intents::GUILD_PRESENCES
 		| Intents::GUILD_MESSAGES
+		| Intents::GUILD_MESSAGE_TYPING
+		| Intents::GUILD_MESSAGE_REACTIONS
 		| Intents::MESSAGE_CONTENT
+		| Intents::AUTO_MODERATION_EXECUTION
+		| Intents::GUILD_MODERATION
+}
+
+/// The intents `entity` is configured with, see [`GatewayIntents`].
+///
+/// Warns when the bot has [`PrefixCommands`] but not the `MESSAGE_CONTENT`
+/// intent, or fails if those prefix commands are `strict`.
+pub async fn configured_intents(entity: &AsyncEntity) -> Result<Intents> {
+	let intents = entity
+		.get::<GatewayIntents, _>(|intents| intents.0)
+		.await
+		.unwrap_or_else(|_| gateway_intents());
+	// `Ok(strict)` when this bot handles `!` prefix commands.
+	let prefix_strict = entity.get::<PrefixCommands, _>(|p| p.strict).await;
+	if let Ok(strict) = prefix_strict {
+		if missing_message_content_intent(intents, true) {
+			if strict {
+				error!(
+					"prefix commands are enabled but the MESSAGE_CONTENT \
+					 intent is missing, refusing to start"
+				);
+				return Err(
+					"MESSAGE_CONTENT intent required for prefix commands"
+						.to_string()
+						.into(),
+				);
+			}
+			warn!(
+				"prefix commands are enabled but the MESSAGE_CONTENT intent \
+				 is missing, `!` commands will not receive message text"
+			);
+		}
+	}
+	Ok(intents)
+}
+
+/// Whether `intents` would silently break prefix commands, which need
+/// `MESSAGE_CONTENT` to see message text.
+pub fn missing_message_content_intent(
+	intents: Intents,
+	uses_prefix_commands: bool,
+) -> bool {
+	uses_prefix_commands && !intents.contains(Intents::MESSAGE_CONTENT)
+}
+
+// ---------------------------------------------------------------------------
+// Handler concurrency
+// ---------------------------------------------------------------------------
+
+/// Handler tasks a bot runs at once unless given another [`HandlerLimit`].
+pub const DEFAULT_MAX_CONCURRENT_HANDLERS: usize = 32;
+
+/// Caps how many event handler tasks a bot runs at once.
+///
+/// Handlers hold an [`acquire`](Self::acquire) permit for the async work
+/// they queue, and the event loop waits for a free slot before triggering
+/// the next event, so a burst of events can't start an unbounded number of
+/// tasks. Clones share the same slots.
+#[derive(Debug, Clone, Component)]
+pub struct HandlerLimit(Arc<Semaphore>);
+
+impl HandlerLimit {
+	/// Allow at most `max` handler tasks at once, at least one.
+	pub fn new(max: usize) -> Self {
+		Self(Arc::new(Semaphore::new(max.max(1))))
+	}
+
+	/// Wait for a slot, held until the guard is dropped.
+	pub async fn acquire(&self) -> SemaphoreGuard<'_> {
+		self.0.acquire().await
+	}
+
+	/// Wait until a slot is free without taking it.
+	pub async fn ready(&self) { drop(self.acquire().await); }
+}
+
+impl Default for HandlerLimit {
+	fn default() -> Self { Self::new(DEFAULT_MAX_CONCURRENT_HANDLERS) }
 }
 
 // ---------------------------------------------------------------------------
@@ -40,16 +125,41 @@ pub async fn start_gateway_listener(entity: AsyncEntity) -> Result {
 		.get::<DiscordBot, _>(|bot| bot.token().to_string())
 		.await?;
 
+	if let Err(err) = validate_token_format(&token) {
+		error!("invalid bot token: {}", err);
+		return Err(err.to_string().into());
+	}
+	let token = token.trim().to_string();
+
 	// Create the HTTP client (cheap to clone — Arc internals).
 	let http = DiscordHttpClient::new(&token);
 	entity.insert_then(http.clone()).await;
 
+	// Preflight the token over REST, where a rejected token is a clear 401
+	// rather than a 4004 gateway close.
+	match http.send(GetCurrentUser).await {
+		Ok(user) => info!(user = %user.name, "authenticated"),
+		Err(HttpError::Api { status, .. })
+			if status == StatusCode::UNAUTHORIZED =>
+		{
+			error!(
+				"invalid token: Discord rejected DISCORD_TOKEN, reset it in \
+				 the Developer Portal"
+			);
+			return Err("invalid token".to_string().into());
+		}
+		// The gateway reports anything else, e.g. a network outage.
+		Err(err) => warn!(error = %err, "token preflight failed"),
+	}
+
 	// Insert state into the Bevy world as Resources.
 
+	let intents = configured_intents(&entity).await?;
+
 	// Connect to the Discord gateway.
 	let gw = GatewayConfig {
 		token,
-		intents: gateway_intents(),
+		intents,
 		shard: None, // single-shard
 	}
 	.connect()
@@ -60,10 +170,45 @@ pub async fn start_gateway_listener(entity: AsyncEntity) -> Result {
 	})?;
 
 	info!("gateway connected, entering event loop");
+	let limit = entity
+		.get::<HandlerLimit, _>(|limit| limit.clone())
+		.await
+		.unwrap_or_default();
+	let raw_events = entity.get::<RawGatewayEvents, _>(|_| ()).await.is_ok();
 
 	// ----- Main event loop -----
-	while let Ok(event) = gw.events.recv().await {
+	//
+	// `trigger` only runs the observers' synchronous part; any slow work
+	// (HTTP calls, pagination) is handed to `queue_async` by the observer,
+	// so one long-running handler doesn't hold up the next event. Events are
+	// still triggered in gateway order, which keeps READY ahead of the
+	// dispatches that depend on `BotState`. On bots with `RawGatewayEvents`
+	// every event is first triggered as a `DiscordGatewayEvent`, for
+	// handlers needing more than the typed events below.
+	//
+	// Handlers run their queued work under a `HandlerLimit` permit. While
+	// every permit is taken the loop stops reading events, so the number
+	// of tasks stays bounded and the gateway's channel absorbs the burst.
+	loop {
+		limit.ready().await;
+		let incoming = futures_lite::future::race(
+			async { gw.events.recv().await.map(Incoming::Event) },
+			async { gw.unknown.recv().await.map(Incoming::Unknown) },
+		)
+		.await;
+		let event = match incoming {
+			Ok(Incoming::Event(event)) => event,
+			Ok(Incoming::Unknown(unknown)) => {
+				trace!("Unknown Dispatch Received: {unknown:#?}");
+				entity.trigger(DiscordUnknownDispatch::create(unknown));
+				continue;
+			}
+			Err(_) => break,
+		};
 		trace!("Event Received: {event:#?}");
+		if raw_events {
+			entity.trigger(DiscordGatewayEvent::create(event.clone()));
+		}
 
 		match event {
 			GatewayEvent::Dispatch(_, dispatch) => match dispatch {
@@ -82,9 +227,42 @@ pub async fn start_gateway_listener(entity: AsyncEntity) -> Result {
 				DispatchEvent::InteractionCreate(interaction) => {
 					entity.trigger(DiscordInteraction::create(interaction.0));
 				}
+				DispatchEvent::MemberUpdate(member) => {
+					entity.trigger(DiscordMemberUpdate::create(*member));
+				}
+				DispatchEvent::RoleCreate(role_create) => {
+					entity.trigger(DiscordRoleCreate::create(role_create));
+				}
+				DispatchEvent::RoleUpdate(role_update) => {
+					entity.trigger(DiscordRoleUpdate::create(role_update));
+				}
+				DispatchEvent::RoleDelete(role_delete) => {
+					entity.trigger(DiscordRoleDelete::create(role_delete));
+				}
+				DispatchEvent::TypingStart(typing_start) => {
+					entity.trigger(DiscordTypingStart::create(*typing_start));
+				}
+				DispatchEvent::ReactionAdd(reaction) => {
+					entity.trigger(DiscordReactionAdd::create(*reaction));
+				}
+				DispatchEvent::ReactionRemove(reaction) => {
+					entity.trigger(DiscordReactionRemove::create(*reaction));
+				}
+				DispatchEvent::BanAdd(ban) => {
+					entity.trigger(DiscordBanAdd::create(ban));
+				}
+				DispatchEvent::BanRemove(ban) => {
+					entity.trigger(DiscordBanRemove::create(ban));
+				}
+				DispatchEvent::AutoModerationActionExecution(execution) => {
+					entity.trigger(
+						DiscordAutoModerationActionExecution::create(execution),
+					);
+				}
 				DispatchEvent::Resumed => {
 					// known event, no-op
 				}
+				// Still reaches `DiscordGatewayEvent` observers.
 				other => {
 					tracing::warn!(event = ?other, "unhandled dispatch event");
 				}
@@ -108,6 +286,12 @@ pub async fn start_gateway_listener(entity: AsyncEntity) -> Result {
 	Ok(())
 }
 
+/// Next item from either gateway event channel.
+enum Incoming {
+	Event(GatewayEvent),
+	Unknown(UnknownDispatch),
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -115,6 +299,7 @@ pub async fn start_gateway_listener(entity: AsyncEntity) -> Result {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::test_fixtures::*;
 
 	// -- gateway_intents() -------------------------------------------------
 
@@ -134,9 +319,132 @@ mod tests {
 			intents.contains(Intents::GUILD_MESSAGES),
 			"missing GUILD_MESSAGES"
 		);
+		assert!(
+			intents.contains(Intents::GUILD_MESSAGE_TYPING),
+			"missing GUILD_MESSAGE_TYPING"
+		);
+		assert!(
+			intents.contains(Intents::GUILD_MESSAGE_REACTIONS),
+			"missing GUILD_MESSAGE_REACTIONS"
+		);
 		assert!(
 			intents.contains(Intents::MESSAGE_CONTENT),
 			"missing MESSAGE_CONTENT"
 		);
+		assert!(
+			intents.contains(Intents::AUTO_MODERATION_EXECUTION),
+			"missing AUTO_MODERATION_EXECUTION"
+		);
+		assert!(
+			intents.contains(Intents::GUILD_MODERATION),
+			"missing GUILD_MODERATION"
+		);
+	}
+
+	// -- DiscordGatewayEvent ------------------------------------------------
+
+	#[derive(Default, Component)]
+	struct MessageCount(usize);
+
+	fn count_messages(
+		ev: On<DiscordGatewayEvent>,
+		mut counts: Query<&mut MessageCount>,
+	) -> Result {
+		if let GatewayEvent::Dispatch(_, DispatchEvent::MessageCreate(_)) =
+			&ev.event
+		{
+			counts.get_mut(ev.event_target())?.0 += 1;
+		}
+		Ok(())
+	}
+
+	fn message_create(id: &str) -> GatewayEvent {
+		let json = serde_json::json!({
+			"op": 0,
+			"s": 1,
+			"t": "MESSAGE_CREATE",
+			"d": message_json(id, "hi"),
+		});
+		crate::tw_gateway::parse_gateway_event(&json.to_string()).unwrap()
+	}
+
+	#[test]
+	fn gateway_event_observer_counts_messages() {
+		let mut world = World::new();
+		let mut bot = world.spawn(MessageCount::default());
+		bot.observe(count_messages);
+		bot.trigger(DiscordGatewayEvent::create(message_create("1")));
+		bot.trigger(DiscordGatewayEvent::create(GatewayEvent::HeartbeatAck));
+		bot.trigger(DiscordGatewayEvent::create(message_create("2")));
+		assert_eq!(bot.get::<MessageCount>().unwrap().0, 2);
+	}
+
+	// -- configured_intents() ---------------------------------------------
+
+	#[test]
+	fn listener_reads_intents_from_the_bot_entity() {
+		let mut app = App::new();
+		app.add_plugins((MinimalPlugins, AsyncPlugin::default()));
+		let without_content =
+			GatewayIntents(gateway_intents() - Intents::MESSAGE_CONTENT);
+		let prefix = PrefixCommands::default();
+		let strict = PrefixCommands::strict();
+		let world = app.world_mut();
+		let bots = [
+			world.spawn_empty().id(),
+			world.spawn(without_content).id(),
+			world.spawn((without_content, prefix)).id(),
+			world.spawn((without_content, strict)).id(),
+		];
+		let results = Arc::new(std::sync::Mutex::new(Vec::new()));
+		let sink = results.clone();
+		world.commands().queue_async(async move |world| {
+			for bot in bots {
+				let intents = configured_intents(&world.entity(bot))
+					.await
+					.map_err(|e| e.to_string());
+				sink.lock().unwrap().push(intents);
+			}
+		});
+		for _ in 0..100 {
+			if results.lock().unwrap().len() == bots.len() {
+				break;
+			}
+			app.update();
+			std::thread::sleep(std::time::Duration::from_millis(1));
+		}
+
+		let results = results.lock().unwrap();
+		assert_eq!(results.len(), bots.len());
+		assert_eq!(results[0], Ok(gateway_intents()));
+		assert_eq!(results[1], Ok(without_content.0));
+		// Non-strict prefix commands only warn.
+		assert_eq!(results[2], Ok(without_content.0));
+		assert!(
+			results[3]
+				.as_ref()
+				.is_err_and(|e| e.contains("MESSAGE_CONTENT")),
+			"{:?}",
+			results[3]
+		);
+	}
+
+	// -- missing_message_content_intent() ----------------------------------
+
+	#[test]
+	fn message_content_not_required_without_prefix_commands() {
+		assert!(!missing_message_content_intent(Intents::GUILDS, false));
+		assert!(!missing_message_content_intent(gateway_intents(), false));
+	}
+
+	#[test]
+	fn message_content_present_with_prefix_commands() {
+		assert!(!missing_message_content_intent(gateway_intents(), true));
+	}
+
+	#[test]
+	fn message_content_missing_with_prefix_commands() {
+		let intents = gateway_intents() - Intents::MESSAGE_CONTENT;
+		assert!(missing_message_content_intent(intents, true));
 	}
 }
@@ -18,17 +18,35 @@
 
 use crate::prelude::*;
 use async_lock::Mutex;
+use async_lock::Semaphore;
 use beet::core::time_ext;
 use beet::prelude::*;
 use std::collections::HashMap;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::Poll;
 use std::time::Duration;
 use std::time::Instant;
 use tracing::debug;
+use tracing::info;
 use tracing::warn;
+use twilight_model::channel::Channel;
+use twilight_model::channel::message::AllowedMentions;
 use twilight_model::channel::message::Message;
+use twilight_model::channel::message::embed::Embed;
+use twilight_model::guild::Emoji;
+use twilight_model::guild::Guild;
+use twilight_model::guild::GuildIntegration;
+use twilight_model::guild::Member;
+use twilight_model::guild::auto_moderation::AutoModerationRule;
+use twilight_model::guild::widget::GuildWidget;
 use twilight_model::id::Id;
 use twilight_model::id::marker::ChannelMarker;
+use twilight_model::id::marker::GuildMarker;
+use twilight_model::id::marker::MessageMarker;
+use twilight_model::id::marker::RoleMarker;
+use twilight_model::id::marker::UserMarker;
+use twilight_model::user::CurrentUserGuild;
 
 // ---------------------------------------------------------------------------
 // Constants
@@ -37,6 +55,33 @@ use twilight_model::id::marker::ChannelMarker;
 const BASE_URL: &str = "https://discord.com/api/v10";
 const USER_AGENT: &str =
 	"BeetFramework (https://github.com/mrchantey/beet, 0.1)";
+const DEFAULT_MAX_RETRIES: u32 = 5;
+/// Pages of 100 messages [`DiscordHttpClient::count_messages`] scans.
+pub const COUNT_DEFAULT_MAX_PAGES: usize = 100;
+/// During a scan past [`COUNT_DEFAULT_MAX_PAGES`], refresh the typing
+/// indicator after this many pages. It lasts about 10 seconds.
+const COUNT_TYPING_INTERVAL_PAGES: usize = 10;
+/// Channels [`DiscordHttpClient::count_messages_in_channels`] scans at once
+/// by default.
+pub const COUNT_DEFAULT_CONCURRENCY: usize = 4;
+/// Pages of 100 recent messages
+/// [`DiscordHttpClient::delete_recent_messages_by`] looks through.
+pub const CLEANUP_SCAN_PAGES: usize = 5;
+/// Oldest message bulk delete accepts: 14 days, less a minute so messages
+/// don't age out between the check and the request.
+const BULK_DELETE_MAX_AGE: Duration =
+	Duration::from_secs(14 * 24 * 60 * 60 - 60);
+/// Most messages one bulk delete takes.
+const BULK_DELETE_MAX_MESSAGES: usize = 100;
+/// Most options [`DiscordHttpClient::post_reaction_vote`] takes, the number
+/// of distinct reactions Discord allows on a message.
+pub const REACTION_VOTE_MAX_OPTIONS: usize = 20;
+/// Largest file [`DiscordHttpClient::send_message_with_file_from_url`]
+/// re-uploads, Discord's default upload limit.
+pub const URL_FILE_MAX_BYTES: usize = 10 * 1024 * 1024;
+/// Upper bounds in milliseconds of the [`RouteMetrics`] latency histogram
+/// buckets. A final bucket counts anything slower.
+pub const LATENCY_BUCKETS_MS: [u64; 7] = [50, 100, 250, 500, 1000, 2500, 5000];
 
 // ---------------------------------------------------------------------------
 // Rate-limit tracker (per-bucket)
@@ -48,11 +93,23 @@ struct BucketState {
 	resets_at: Instant,
 }
 
+/// Per-bucket rate-limit tracker.
+///
+/// Discord reuses the same `X-RateLimit-Bucket` hash for every value of a
+/// route's major parameter, so bucket state is keyed by hash *and*
+/// [`major_parameter`]; otherwise two different interactions or channels
+/// would queue behind each other.
+///
+/// Interaction callbacks are exempt from the global rate limit and only wait
+/// on their own bucket.
+///
+/// Per-interaction and per-token route keys are never reused, so state is
+/// dropped once its bucket resets rather than kept for the process lifetime.
 #[derive(Debug, Clone)]
 struct RateLimiter {
-	/// Route-key → bucket id mapping.
+	/// Route-key → bucket key mapping.
 	route_buckets: HashMap<String, String>,
-	/// Bucket id → state.
+	/// Bucket key (`hash:major`) → state.
 	buckets: HashMap<String, BucketState>,
 	/// Global rate-limit: if set, no requests may be sent until this instant.
 	global_until: Option<Instant>,
@@ -73,7 +130,7 @@ impl RateLimiter {
 		// Global rate limit takes priority.
 		if let Some(until) = self.global_until {
 			let now = Instant::now();
-			if until > now {
+			if until > now && !is_global_exempt(route_key) {
 				return Some(until - now);
 			}
 		}
@@ -101,6 +158,8 @@ impl RateLimiter {
 		}
 
 		if let Some(ref bucket) = info.bucket {
+			self.evict_expired();
+			let bucket = format!("{}:{}", bucket, major_parameter(route_key));
 			self.route_buckets
 				.insert(route_key.to_string(), bucket.clone());
 
@@ -110,12 +169,91 @@ impl RateLimiter {
 				Instant::now() + Duration::from_secs(1)
 			};
 
-			self.buckets.insert(bucket.clone(), BucketState {
+			self.buckets.insert(bucket, BucketState {
 				remaining: info.remaining.unwrap_or(1),
 				resets_at: reset_instant,
 			});
 		}
 	}
+
+	/// Forget buckets that have reset, which can no longer delay a request,
+	/// and the routes pointing at them.
+	fn evict_expired(&mut self) {
+		let now = Instant::now();
+		self.buckets.retain(|_, state| state.resets_at > now);
+		let buckets = &self.buckets;
+		self.route_buckets
+			.retain(|_, bucket| buckets.contains_key(bucket));
+	}
+}
+
+/// A rate limiter that several [`DiscordHttpClient`]s can share.
+///
+/// Discord's global and per-bucket limits apply to the bot token, not to a
+/// client, so when running sharded every shard should send through one
+/// limiter. Clones of a client already share theirs; use
+/// [`DiscordHttpClient::from_shared`] when clients are constructed
+/// separately.
+///
+/// ```ignore
+/// let limiter = SharedRateLimiter::new();
+/// let shard_0 = DiscordHttpClient::from_shared(&token, limiter.clone());
+/// let shard_1 = DiscordHttpClient::from_shared(&token, limiter);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedRateLimiter(Arc<Mutex<RateLimiter>>);
+
+impl SharedRateLimiter {
+	pub fn new() -> Self { Self(Arc::new(Mutex::new(RateLimiter::new()))) }
+
+	/// Whether both handles point at the same limiter.
+	pub fn ptr_eq(&self, other: &Self) -> bool { Arc::ptr_eq(&self.0, &other.0) }
+}
+
+impl Default for SharedRateLimiter {
+	fn default() -> Self { Self::new() }
+}
+
+/// The major-parameter prefix of a route key, e.g. `channels/123` for
+/// `"POST /channels/123/messages"`. Webhook routes include the token id.
+fn major_parameter(route_key: &str) -> &str {
+	let path = route_key
+		.split_once(' ')
+		.map_or(route_key, |(_, path)| path)
+		.trim_start_matches('/');
+	let segments = if path.starts_with("webhooks/") { 3 } else { 2 };
+	match path.match_indices('/').nth(segments - 1) {
+		Some((idx, _)) => &path[..idx],
+		None => path,
+	}
+}
+
+/// `route_key` with the ids of its major parameter replaced by placeholders,
+/// e.g. `POST /interactions/{id}/callback`, so [`HttpMetrics`] keeps one
+/// entry per route rather than one per channel or interaction.
+fn route_template(route_key: &str) -> String {
+	let Some((method, path)) = route_key.split_once(' ') else {
+		return route_key.to_string();
+	};
+	let path = path.trim_start_matches('/');
+	let major = major_parameter(route_key);
+	let segments: Vec<&str> = major
+		.split('/')
+		.enumerate()
+		.map(|(index, segment)| match index {
+			0 => segment,
+			// The hashed token of a webhook route.
+			2 => "{token}",
+			_ if segment.bytes().all(|b| b.is_ascii_digit()) => "{id}",
+			_ => segment,
+		})
+		.collect();
+	format!("{} /{}{}", method, segments.join("/"), &path[major.len()..])
+}
+
+/// Routes that are not counted against the global rate limit.
+fn is_global_exempt(route_key: &str) -> bool {
+	route_key.starts_with("POST /interactions/")
 }
 
 // ---------------------------------------------------------------------------
@@ -158,6 +296,133 @@ fn parse_rate_limit_headers(parts: &ResponseParts) -> RateLimitInfo {
 	}
 }
 
+// ---------------------------------------------------------------------------
+// Auth headers
+// ---------------------------------------------------------------------------
+
+/// The kind of credential a [`DiscordHttpClient`] authenticates with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AuthKind {
+	/// A bot token, sent as `Authorization: Bot <token>`.
+	Bot,
+	/// A user OAuth2 access token, sent as `Authorization: Bearer <token>`.
+	Bearer,
+}
+
+impl AuthKind {
+	fn prefix(self) -> &'static str {
+		match self {
+			AuthKind::Bot => "Bot",
+			AuthKind::Bearer => "Bearer",
+		}
+	}
+}
+
+/// Attach the `authorization` and `user-agent` headers Discord requires on
+/// every authenticated REST call.
+fn apply_auth_headers(
+	mut req: Request,
+	auth: AuthKind,
+	token: &str,
+) -> Request {
+	req.headers
+		.set_raw("authorization", format!("{} {}", auth.prefix(), token));
+	apply_user_agent(req)
+}
+
+/// Attach only the `user-agent` header, for public endpoints.
+fn apply_user_agent(mut req: Request) -> Request {
+	req.headers.set_raw("user-agent", USER_AGENT);
+	req
+}
+
+// ---------------------------------------------------------------------------
+// Request metrics
+// ---------------------------------------------------------------------------
+
+/// Counters for a single route key, see [`HttpMetrics`].
+#[derive(Debug, Clone, Default)]
+pub struct RouteMetrics {
+	/// Attempts sent on this route, including retries after a 429.
+	pub count: u64,
+	/// Attempts that failed before any response arrived.
+	pub transport_errors: u64,
+	/// Number of responses per status code.
+	pub statuses: Vec<(StatusCode, u64)>,
+	pub total_latency: Duration,
+	pub max_latency: Duration,
+	/// Attempts per [`LATENCY_BUCKETS_MS`] bucket, the last entry counting
+	/// anything slower than the largest bound.
+	pub latency_buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+}
+
+impl RouteMetrics {
+	/// Average latency across all attempts.
+	pub fn mean_latency(&self) -> Duration {
+		match self.count {
+			0 => Duration::ZERO,
+			count => self.total_latency / count as u32,
+		}
+	}
+
+	/// How many responses had `status`.
+	pub fn status_count(&self, status: StatusCode) -> u64 {
+		self.statuses
+			.iter()
+			.find(|(s, _)| *s == status)
+			.map_or(0, |(_, count)| *count)
+	}
+
+	fn record(&mut self, status: Option<StatusCode>, latency: Duration) {
+		self.count += 1;
+		self.total_latency += latency;
+		self.max_latency = self.max_latency.max(latency);
+		let bucket = LATENCY_BUCKETS_MS
+			.iter()
+			.position(|&bound| latency.as_millis() <= bound as u128)
+			.unwrap_or(LATENCY_BUCKETS_MS.len());
+		self.latency_buckets[bucket] += 1;
+		match status {
+			Some(status) => {
+				match self.statuses.iter_mut().find(|(s, _)| *s == status) {
+					Some((_, count)) => *count += 1,
+					None => self.statuses.push((status, 1)),
+				}
+			}
+			None => self.transport_errors += 1,
+		}
+	}
+}
+
+/// Per-route request counts and latencies, collected when the client is
+/// built with [`DiscordHttpClient::with_metrics`].
+#[derive(Debug, Clone, Default)]
+pub struct HttpMetrics {
+	/// Metrics keyed by route template, the [`DiscordRequest::route_key`]
+	/// without its ids, e.g. `POST /channels/{id}/messages`.
+	pub routes: HashMap<String, RouteMetrics>,
+}
+
+impl HttpMetrics {
+	/// Metrics for the route of `route_key`, if it has been hit. Takes
+	/// either a route key or its template.
+	pub fn route(&self, route_key: &str) -> Option<&RouteMetrics> {
+		self.routes.get(&route_template(route_key))
+	}
+
+	fn record(
+		&mut self,
+		route_key: &str,
+		status: Option<StatusCode>,
+		latency: Duration,
+	) {
+		self.routes
+			.entry(route_template(route_key))
+			.or_default()
+			.record(status, latency);
+	}
+}
+
 // ---------------------------------------------------------------------------
 // Errors
 // ---------------------------------------------------------------------------
@@ -168,12 +433,28 @@ pub enum HttpError {
 	Api {
 		status: StatusCode,
 		body: String,
+		/// The rate-limit route key, e.g. `POST /channels/1/messages`.
 		route: String,
+		/// Our guess at the cause, shown after Discord's error.
+		hint: Option<&'static str>,
 	},
 	/// Transport / network error.
 	Transport(String),
 	/// Serialisation error.
 	Serde(String),
+	/// The request breaks a Discord limit checked before sending, e.g. too
+	/// many messages for one bulk delete.
+	Validation(String),
+	/// Still rate-limited after exhausting every retry.
+	RateLimitedExhausted {
+		route: String,
+		/// The `retry_after` (seconds) from the final 429.
+		last_retry_after: f64,
+	},
+	/// A file downloaded to re-upload was rejected, e.g. too large.
+	Download(String),
+	/// A dry-run client skipped a request whose response it can't fake.
+	DryRun { route: String },
 }
 
 impl std::fmt::Display for HttpError {
@@ -183,17 +464,136 @@ impl std::fmt::Display for HttpError {
 				status,
 				body,
 				route,
+				hint,
 			} => {
-				write!(f, "Discord API error {} on {}: {}", status, route, body)
+				write!(f, "Discord API error {} on {}: ", status, route)?;
+				let errors = validation_errors(body);
+				if errors.is_empty() {
+					f.write_str(body)?;
+				} else {
+					let errors: Vec<_> = errors
+						.iter()
+						.map(|(path, message)| format!("{}: {}", path, message))
+						.collect();
+					write!(f, "invalid form body: {}", errors.join("; "))?;
+				}
+				match hint {
+					Some(hint) => write!(f, " ({})", hint),
+					None => Ok(()),
+				}
 			}
 			HttpError::Transport(e) => write!(f, "HTTP transport error: {}", e),
 			HttpError::Serde(e) => write!(f, "Serialisation error: {}", e),
+			HttpError::Validation(e) => write!(f, "invalid request: {}", e),
+			HttpError::RateLimitedExhausted {
+				route,
+				last_retry_after,
+			} => write!(
+				f,
+				"rate-limited on {} after max retries (retry after {}s)",
+				route, last_retry_after
+			),
+			HttpError::Download(e) => write!(f, "download rejected: {}", e),
+			HttpError::DryRun { route } => {
+				write!(f, "dry run, no fake response for {}", route)
+			}
 		}
 	}
 }
 
 impl std::error::Error for HttpError {}
 
+/// JSON error code Discord returns for a channel that doesn't exist, e.g.
+/// one deleted since its id was stored.
+pub const UNKNOWN_CHANNEL: u64 = 10003;
+
+/// JSON error code Discord returns when pinning in a channel that already
+/// has the maximum of 50 pins.
+pub const MAX_PINS_REACHED: u64 = 30003;
+
+impl HttpError {
+	/// The JSON error `code` of an API error, e.g. [`UNKNOWN_CHANNEL`].
+	pub fn code(&self) -> Option<u64> {
+		let HttpError::Api { body, .. } = self else {
+			return None;
+		};
+		serde_json::from_str::<serde_json::Value>(body)
+			.ok()?
+			.get("code")?
+			.as_u64()
+	}
+
+	/// Whether the request may succeed if sent again unchanged: transport
+	/// failures, 5xx responses and rate limits. Other API errors, e.g. a
+	/// 400 or 403, fail the same way every time.
+	pub fn is_transient(&self) -> bool {
+		match self {
+			HttpError::Transport(_)
+			| HttpError::RateLimitedExhausted { .. } => true,
+			HttpError::Api { status, .. } => {
+				status.is_server_error()
+					|| *status == StatusCode::TOO_MANY_REQUESTS
+			}
+			_ => false,
+		}
+	}
+
+	/// The `(path, message)` pairs of a 50035 "Invalid Form Body" response,
+	/// empty for any other error.
+	pub fn validation_errors(&self) -> Vec<(String, String)> {
+		match self {
+			HttpError::Api { body, .. } => validation_errors(body),
+			_ => Vec::new(),
+		}
+	}
+}
+
+/// Flatten the nested `errors` tree of a 50035 response body into
+/// `(path, message)` pairs, e.g.
+/// `("embeds.0.description", "Must be 4096 or fewer in length.")`.
+fn validation_errors(body: &str) -> Vec<(String, String)> {
+	let Ok(value) = serde_json::from_str::<serde_json::Value>(body) else {
+		return Vec::new();
+	};
+	if value.get("code").and_then(|c| c.as_u64()) != Some(50035) {
+		return Vec::new();
+	}
+	let mut errors = Vec::new();
+	if let Some(tree) = value.get("errors") {
+		collect_validation_errors(tree, &mut String::new(), &mut errors);
+	}
+	errors
+}
+
+fn collect_validation_errors(
+	node: &serde_json::Value,
+	path: &mut String,
+	out: &mut Vec<(String, String)>,
+) {
+	let Some(map) = node.as_object() else {
+		return;
+	};
+	for (key, child) in map {
+		if key == "_errors" {
+			for error in child.as_array().into_iter().flatten() {
+				let message = error
+					.get("message")
+					.and_then(|m| m.as_str())
+					.unwrap_or("invalid value");
+				out.push((path.clone(), message.to_string()));
+			}
+			continue;
+		}
+		let len = path.len();
+		if !path.is_empty() {
+			path.push('.');
+		}
+		path.push_str(key);
+		collect_validation_errors(child, path, out);
+		path.truncate(len);
+	}
+}
+
 impl From<JsonError> for HttpError {
 	fn from(e: JsonError) -> Self { HttpError::Serde(e.0) }
 }
@@ -205,7 +605,8 @@ impl From<JsonError> for HttpError {
 
 /// A thin, rate-limit–aware HTTP client for the Discord REST API.
 ///
-/// Cheap to clone (internals are behind `Arc`).
+/// Cheap to clone (internals are behind `Arc`). Clones share one
+/// [`SharedRateLimiter`], as should every client using the same token.
 ///
 /// ## Usage
 ///
@@ -218,15 +619,148 @@ impl From<JsonError> for HttpError {
 #[derive(Clone, Component)]
 pub struct DiscordHttpClient {
 	token: String,
-	limiter: Arc<Mutex<RateLimiter>>,
+	auth: AuthKind,
+	/// Number of retries after a 429 before giving up.
+	max_retries: u32,
+	/// Log mutating requests instead of sending them.
+	dry_run: bool,
+	/// Requests dry-run mode skipped, `None` unless enabled with
+	/// [`with_dry_run_log`](Self::with_dry_run_log).
+	dry_run_log: Option<Arc<std::sync::Mutex<Vec<DiscordRequest>>>>,
+	limiter: SharedRateLimiter,
+	/// Per-route metrics, `None` unless enabled with
+	/// [`with_metrics`](Self::with_metrics).
+	metrics: Option<Arc<std::sync::Mutex<HttpMetrics>>>,
+	/// Mentions a created message may ping unless it sets its own.
+	allowed_mentions: AllowedMentions,
+	/// DM channel ids opened by [`create_dm`](Self::create_dm), by
+	/// recipient. Clones share the cache.
+	dm_channels:
+		Arc<std::sync::Mutex<HashMap<Id<UserMarker>, Id<ChannelMarker>>>>,
 }
 
 impl DiscordHttpClient {
 	/// Create a new client with the given bot token.
 	pub fn new(token: impl Into<String>) -> Self {
+		Self::from_shared(token, SharedRateLimiter::new())
+	}
+
+	/// Create a bot-token client that waits on `limiter`, shared with any
+	/// other client built from it, e.g. one per shard.
+	pub fn from_shared(
+		token: impl Into<String>,
+		limiter: SharedRateLimiter,
+	) -> Self {
+		Self {
+			token: token.into(),
+			auth: AuthKind::Bot,
+			max_retries: DEFAULT_MAX_RETRIES,
+			dry_run: false,
+			dry_run_log: None,
+			limiter,
+			metrics: None,
+			allowed_mentions: default_allowed_mentions(),
+			dm_channels: Default::default(),
+		}
+	}
+
+	/// The rate limiter this client waits on, to build further clients
+	/// with [`from_shared`](Self::from_shared).
+	pub fn shared_limiter(&self) -> SharedRateLimiter { self.limiter.clone() }
+
+	/// Create a new client authenticated with a user OAuth2 access token.
+	///
+	/// Only endpoints that accept user credentials will succeed, for example
+	/// [`get_current_user_guilds`](Self::get_current_user_guilds).
+	pub fn with_bearer(token: impl Into<String>) -> Self {
 		Self {
 			token: token.into(),
-			limiter: Arc::new(Mutex::new(RateLimiter::new())),
+			auth: AuthKind::Bearer,
+			max_retries: DEFAULT_MAX_RETRIES,
+			dry_run: false,
+			dry_run_log: None,
+			limiter: SharedRateLimiter::new(),
+			metrics: None,
+			allowed_mentions: default_allowed_mentions(),
+			dm_channels: Default::default(),
+		}
+	}
+
+	/// Set how many times a request is retried after a 429 before
+	/// [`HttpError::RateLimitedExhausted`] is returned.
+	pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+		self.max_retries = max_retries;
+		self
+	}
+
+	/// Set the mentions a [`CreateMessage`] may ping when it doesn't set
+	/// its own, replacing [`default_allowed_mentions`].
+	pub fn with_allowed_mentions(mut self, mentions: AllowedMentions) -> Self {
+		self.allowed_mentions = mentions;
+		self
+	}
+
+	/// The mentions messages sent by this client may ping by default.
+	pub fn allowed_mentions(&self) -> &AllowedMentions {
+		&self.allowed_mentions
+	}
+
+	/// Enable or disable dry-run mode.
+	///
+	/// In dry-run mode every non-GET request is logged and answered with a
+	/// synthesized success instead of being sent: message create/edit
+	/// routes return a fake [`Message`] echoing the request body, opening a
+	/// DM a fake DM channel, and other routes an empty object. Requests
+	/// whose output can't be built from that fail with [`HttpError::DryRun`].
+	/// GETs still hit the API. Skipped requests aren't counted in metrics.
+	pub fn dry_run(mut self, enabled: bool) -> Self {
+		self.dry_run = enabled;
+		self
+	}
+
+	/// Keep the requests dry-run mode skips, so tests can check what a
+	/// handler would have sent. Read them back with
+	/// [`take_dry_run_log`](Self::take_dry_run_log). Clones share the log.
+	pub fn with_dry_run_log(mut self, enabled: bool) -> Self {
+		self.dry_run_log = enabled.then(Default::default);
+		self
+	}
+
+	/// Drain the requests logged since the last call, empty if the log is
+	/// disabled.
+	pub fn take_dry_run_log(&self) -> Vec<DiscordRequest> {
+		self.dry_run_log
+			.as_ref()
+			.map(|log| std::mem::take(&mut *log.lock().unwrap()))
+			.unwrap_or_default()
+	}
+
+	/// Enable or disable per-route request metrics.
+	///
+	/// Every attempt then records its route key, status and latency, read
+	/// back with [`metrics_snapshot`](Self::metrics_snapshot). Disabled by
+	/// default so unused metrics cost nothing. Clones share the metrics.
+	pub fn with_metrics(mut self, enabled: bool) -> Self {
+		self.metrics = enabled.then(Default::default);
+		self
+	}
+
+	/// A copy of the metrics collected so far, `None` if metrics are
+	/// disabled.
+	pub fn metrics_snapshot(&self) -> Option<HttpMetrics> {
+		self.metrics
+			.as_ref()
+			.map(|metrics| metrics.lock().unwrap().clone())
+	}
+
+	fn record_metrics(
+		&self,
+		route_key: &str,
+		status: Option<StatusCode>,
+		latency: Duration,
+	) {
+		if let Some(metrics) = &self.metrics {
+			metrics.lock().unwrap().record(route_key, status, latency);
 		}
 	}
 
@@ -255,192 +789,2004 @@ impl DiscordHttpClient {
 		&self,
 		request: R,
 	) -> Result<R::Output, HttpError> {
-		let req = request.into_discord_request()?;
-		let bytes = self.raw_request(&req).await?;
+		let mut req = request.into_discord_request()?;
+		self.apply_allowed_mentions(&mut req);
+		if self.dry_run && !matches!(req.method, HttpMethod::Get) {
+			info!(
+				method = ?req.method,
+				path = req.path.as_str(),
+				body = ?req.body,
+				"dry run, not sending request"
+			);
+			let response = dry_run_response(&req);
+			let route = req.route_key.clone();
+			if let Some(log) = &self.dry_run_log {
+				log.lock().unwrap().push(req);
+			}
+			return R::parse_response(&response)
+				.map_err(|_| HttpError::DryRun { route });
+		}
+		let bytes = self.raw_request(&req, R::AUTHENTICATED).await?;
 		R::parse_response(&bytes).map_err(Into::into)
 	}
 
+	/// Give a message create without its own `allowed_mentions` this
+	/// client's default.
+	fn apply_allowed_mentions(&self, req: &mut DiscordRequest) {
+		let is_create_message = matches!(req.method, HttpMethod::Post)
+			&& req.route_key.starts_with("POST /channels/")
+			&& req.route_key.ends_with("/messages");
+		if !is_create_message {
+			return;
+		}
+		if let RequestBody::Json(serde_json::Value::Object(body)) =
+			&mut req.body
+		{
+			body.entry("allowed_mentions").or_insert_with(|| {
+				serde_json::to_value(&self.allowed_mentions).unwrap_or_default()
+			});
+		}
+	}
+
 	// ------------------------------------------------------------------
-	// Higher-level helpers (compose multiple requests)
+	// Convenience wrappers
 	// ------------------------------------------------------------------
 
-	/// Count messages in a channel by paginating backwards. Caps at 10 000.
-	pub async fn count_messages(
+	/// Show "bot is typing…" in a channel, e.g. before a slow command.
+	///
+	/// The indicator lasts about 10 seconds or until the bot sends a
+	/// message. Callers with longer work should trigger it again rather
+	/// than rely on it being refreshed for them.
+	pub async fn trigger_typing(
 		&self,
 		channel_id: Id<ChannelMarker>,
-	) -> Result<usize, HttpError> {
-		let mut count = 0usize;
-		let mut before: Option<Id<twilight_model::id::marker::MessageMarker>> =
-			None;
-		let max_pages = 100;
-
-		for _ in 0..max_pages {
-			let mut req = GetChannelMessages::new(channel_id).limit(100);
-			if let Some(b) = before {
-				req = req.before(b);
-			}
-
-			let messages: Vec<Message> = self.send(req).await?;
-
-			if messages.is_empty() {
-				break;
-			}
+	) -> Result<(), HttpError> {
+		self.send(CreateTypingTrigger::new(channel_id)).await
+	}
 
-			count += messages.len();
-			before = messages.last().map(|m| m.id);
+	/// Post a message containing only `embed`.
+	pub async fn send_embed(
+		&self,
+		channel_id: Id<ChannelMarker>,
+		embed: Embed,
+	) -> Result<Message, HttpError> {
+		self.send(CreateMessage::new(channel_id).embed(embed)).await
+	}
 
-			if messages.len() < 100 {
-				break;
-			}
-		}
+	/// Open a DM channel with `recipient_id`, or return the existing one.
+	/// The channel id is cached for [`send_dm`](Self::send_dm).
+	pub async fn create_dm(
+		&self,
+		recipient_id: Id<UserMarker>,
+	) -> Result<Channel, HttpError> {
+		let channel =
+			self.send(CreatePrivateChannel::new(recipient_id)).await?;
+		self.dm_channels
+			.lock()
+			.unwrap()
+			.insert(recipient_id, channel.id);
+		Ok(channel)
+	}
 
-		Ok(count)
+	/// Send `content` to `user_id` in a DM, opening the channel only the
+	/// first time. Fails with a 403 [`HttpError::Api`] when the user
+	/// doesn't accept DMs from the bot.
+	pub async fn send_dm(
+		&self,
+		user_id: Id<UserMarker>,
+		content: impl Into<String>,
+	) -> Result<Message, HttpError> {
+		let cached = self.dm_channels.lock().unwrap().get(&user_id).copied();
+		let channel_id = match cached {
+			Some(channel_id) => channel_id,
+			None => self.create_dm(user_id).await?.id,
+		};
+		self.send(CreateMessage::new(channel_id).content(content))
+			.await
 	}
 
-	/// Get the very first message ever sent in a channel.
-	pub async fn get_first_message(
+	/// Reply to `message_id` with a message containing only `embed`.
+	pub async fn reply_embed(
 		&self,
 		channel_id: Id<ChannelMarker>,
+		message_id: Id<MessageMarker>,
+		embed: Embed,
 	) -> Result<Message, HttpError> {
-		let messages: Vec<Message> = self
-			.send(
-				GetChannelMessages::new(channel_id)
-					.after(Id::new(1))
-					.limit(1),
-			)
-			.await?;
+		self.send(
+			CreateMessage::new(channel_id)
+				.embed(embed)
+				.reply_to(message_id),
+		)
+		.await
+	}
 
-		messages.into_iter().next().ok_or_else(|| HttpError::Api {
-			status: StatusCode::NOT_FOUND,
-			body: "No messages found in this channel.".to_string(),
-			route: format!("GET /channels/{}/messages", channel_id),
-		})
+	/// Replace a member's roles with `roles` in a single PATCH, instead of
+	/// one add or remove call per role.
+	///
+	/// Fails if the list adds or drops a role the bot can't manage, e.g. a
+	/// managed role or one above the bot's highest role.
+	pub async fn set_member_roles(
+		&self,
+		guild_id: Id<GuildMarker>,
+		user_id: Id<UserMarker>,
+		roles: impl IntoIterator<Item = Id<RoleMarker>>,
+	) -> Result<Member, HttpError> {
+		self.send(
+			UpdateGuildMember::new(guild_id, user_id)
+				.roles(Some(roles.into_iter().collect())),
+		)
+		.await
 	}
 
-	// ------------------------------------------------------------------
-	// Internal: build a beet Request with auth + user-agent
-	// ------------------------------------------------------------------
+	/// Edit a message the bot sent, e.g. to live-update a poll or
+	/// scoreboard. Only the fields set on `edit` change.
+	pub async fn edit_message(
+		&self,
+		channel_id: Id<ChannelMarker>,
+		message_id: Id<MessageMarker>,
+		edit: &CreateMessage,
+	) -> Result<Message, HttpError> {
+		self.send(EditMessage::from_create(channel_id, message_id, edit))
+			.await
+	}
 
-	fn build_base_request(&self, method: HttpMethod, url: &str) -> Request {
-		let mut req = Request::new(method, url);
-		req.headers
-			.set_raw("authorization", format!("Bot {}", self.token));
-		req.headers.set_raw("user-agent", USER_AGENT);
-		req
+	/// Delete a message. Discord answers with an empty 204; a message that
+	/// is already gone fails with a 404 [`HttpError::Api`].
+	pub async fn delete_message(
+		&self,
+		channel_id: Id<ChannelMarker>,
+		message_id: Id<MessageMarker>,
+	) -> Result<(), HttpError> {
+		self.send(DeleteMessage::new(channel_id, message_id)).await
 	}
 
-	// ------------------------------------------------------------------
-	// Internal: low-level dispatch with rate-limit handling
-	// ------------------------------------------------------------------
+	/// Delete several messages at once, e.g. for a purge command. Requires
+	/// `MANAGE_MESSAGES`.
+	///
+	/// Bulk delete takes 2–100 messages under 14 days old, so other input
+	/// fails with [`HttpError::Validation`] before any request is made. A
+	/// single message is deleted with [`delete_message`](Self::delete_message)
+	/// instead.
+	pub async fn bulk_delete_messages(
+		&self,
+		channel_id: Id<ChannelMarker>,
+		message_ids: &[Id<MessageMarker>],
+	) -> Result<(), HttpError> {
+		if let [message_id] = message_ids {
+			return self.delete_message(channel_id, *message_id).await;
+		}
+		let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+		check_bulk_delete(message_ids, now_ms)?;
+		self.send(BulkDeleteMessages::new(channel_id, message_ids.to_vec()))
+			.await
+	}
 
-	/// Execute a [`DiscordRequest`] with rate-limit back-off and retry.
+	/// Pin a message. Requires `MANAGE_MESSAGES` (or `PIN_MESSAGES`).
 	///
-	/// Returns the raw response bytes on success.
-	async fn raw_request(
+	/// Channels hold at most 50 pins; past that Discord's error body,
+	/// with code [`MAX_PINS_REACHED`], is returned as-is in an
+	/// [`HttpError::Api`] to show the user.
+	pub async fn pin_message(
 		&self,
-		req: &DiscordRequest,
-	) -> Result<Vec<u8>, HttpError> {
-		let max_retries = 5;
-		let route_key = &req.route_key;
+		channel_id: Id<ChannelMarker>,
+		message_id: Id<MessageMarker>,
+	) -> Result<(), HttpError> {
+		self.send(CreatePin::new(channel_id, message_id)).await
+	}
 
-		for attempt in 0..=max_retries {
-			// Pre-request: wait if the rate limiter says so.
-			{
-				let limiter = self.limiter.lock().await;
-				if let Some(delay) = limiter.delay_for(route_key) {
-					let delay = delay.min(Duration::from_secs(60));
-					drop(limiter);
-					debug!(
-						route = route_key.as_str(),
-						delay_ms = delay.as_millis() as u64,
-						"rate-limit pre-emptive backoff"
-					);
-					time_ext::sleep(delay).await;
-				}
-			}
+	/// Unpin a message, with the same permissions as
+	/// [`pin_message`](Self::pin_message).
+	pub async fn unpin_message(
+		&self,
+		channel_id: Id<ChannelMarker>,
+		message_id: Id<MessageMarker>,
+	) -> Result<(), HttpError> {
+		self.send(DeletePin::new(channel_id, message_id)).await
+	}
 
-			let url =
-				format!("{}/{}", BASE_URL, req.path.trim_start_matches('/'));
+	/// The pinned messages of a channel, at most 50.
+	pub async fn get_pinned_messages(
+		&self,
+		channel_id: Id<ChannelMarker>,
+	) -> Result<Vec<Message>, HttpError> {
+		self.send(GetPins::new(channel_id)).await
+	}
 
-			let http_req = match &req.body {
-				RequestBody::None => self.build_base_request(req.method, &url),
-				RequestBody::Json(value) => {
-					let base = self.build_base_request(req.method, &url);
-					base.with_json_body(value)
-						.map_err(|e| HttpError::Serde(e.to_string()))?
-				}
-				RequestBody::Raw { content_type, data } => {
-					let mut base = self.build_base_request(req.method, &url);
-					base.headers.set_raw("content-type", content_type);
-					base.with_body(data.clone())
-				}
-			};
+	/// React to a message as the bot, e.g. a ✅ confirmation. `emoji` is a
+	/// unicode emoji like `"✅"`, percent-encoded for the path, or `name:id`
+	/// for a custom one like `"blobcat:123456789"`.
+	pub async fn add_reaction(
+		&self,
+		channel_id: Id<ChannelMarker>,
+		message_id: Id<MessageMarker>,
+		emoji: &str,
+	) -> Result<(), HttpError> {
+		self.send(CreateReaction::new(channel_id, message_id, emoji))
+			.await
+	}
 
-			let resp = http_req
-				.send()
-				.await
-				.map_err(|e| HttpError::Transport(e.to_string()))?;
+	/// Remove the bot's own reaction added with
+	/// [`add_reaction`](Self::add_reaction), taking the same `emoji` forms.
+	pub async fn delete_own_reaction(
+		&self,
+		channel_id: Id<ChannelMarker>,
+		message_id: Id<MessageMarker>,
+		emoji: &str,
+	) -> Result<(), HttpError> {
+		self.send(DeleteOwnReaction::new(channel_id, message_id, emoji))
+			.await
+	}
 
-			let status = resp.status();
-			let rl_info = parse_rate_limit_headers(resp.response_parts());
+	/// Post a quick vote: an embed listing `options` as `(emoji, label)`
+	/// pairs, with each emoji added as a reaction in order for people to
+	/// click. Options repeating an earlier emoji are dropped.
+	///
+	/// More than [`REACTION_VOTE_MAX_OPTIONS`] options fail with
+	/// [`HttpError::Validation`] before anything is posted.
+	///
+	/// ```ignore
+	/// http.post_reaction_vote(channel_id, "Pizza tonight?", &[
+	///     ("👍", "Yes"),
+	///     ("👎", "No"),
+	/// ]).await?;
+	/// ```
+	pub async fn post_reaction_vote(
+		&self,
+		channel_id: Id<ChannelMarker>,
+		question: &str,
+		options: &[(&str, &str)],
+	) -> Result<Message, HttpError> {
+		let (embed, emojis) = reaction_vote(question, options)?;
+		let message = self.send_embed(channel_id, embed).await?;
+		for emoji in emojis {
+			self.add_reaction(channel_id, message.id, emoji).await?;
+		}
+		Ok(message)
+	}
 
-			// Update the limiter regardless of status.
-			{
-				let mut limiter = self.limiter.lock().await;
-				limiter.update(route_key, &rl_info);
-			}
+	/// Remove every reaction from a message, e.g. to reset a reaction-role
+	/// message. Requires `MANAGE_MESSAGES`.
+	pub async fn delete_all_reactions(
+		&self,
+		channel_id: Id<ChannelMarker>,
+		message_id: Id<MessageMarker>,
+	) -> Result<(), HttpError> {
+		self.send(DeleteAllReactions::new(channel_id, message_id))
+			.await
+	}
 
-			if status == StatusCode::TOO_MANY_REQUESTS {
-				let retry_after = rl_info.reset_after.unwrap_or(1.0);
-				let delay = Duration::from_secs_f64(retry_after.min(60.0));
-				warn!(
-					route = route_key.as_str(),
-					attempt,
-					retry_after_s = retry_after,
-					global = rl_info.is_global,
-					"rate-limited by Discord, backing off"
-				);
+	/// Remove every reaction of one emoji from a message. `emoji` is a
+	/// unicode emoji or `name:id` for custom ones. Requires
+	/// `MANAGE_MESSAGES`.
+	pub async fn delete_all_reactions_for_emoji(
+		&self,
+		channel_id: Id<ChannelMarker>,
+		message_id: Id<MessageMarker>,
+		emoji: impl Into<String>,
+	) -> Result<(), HttpError> {
+		self.send(DeleteAllReactionsForEmoji::new(
+			channel_id, message_id, emoji,
+		))
+		.await
+	}
 
-				if rl_info.is_global {
-					let mut limiter = self.limiter.lock().await;
-					limiter.global_until = Some(Instant::now() + delay);
-				}
+	/// List a guild's auto-moderation rules. Requires `MANAGE_GUILD`.
+	pub async fn get_auto_moderation_rules(
+		&self,
+		guild_id: Id<GuildMarker>,
+	) -> Result<Vec<AutoModerationRule>, HttpError> {
+		self.send(GetAutoModerationRules::new(guild_id)).await
+	}
 
-				if attempt < max_retries {
-					time_ext::sleep(delay).await;
-					continue;
-				}
-			}
+	/// List a guild's integrations, such as Twitch or YouTube subscriber
+	/// roles. Requires `MANAGE_GUILD`.
+	pub async fn get_guild_integrations(
+		&self,
+		guild_id: Id<GuildMarker>,
+	) -> Result<Vec<GuildIntegration>, HttpError> {
+		self.send(GetGuildIntegrations::new(guild_id)).await
+	}
 
-			let resp_bytes = resp
-				.bytes()
-				.await
-				.map_err(|e: BevyError| HttpError::Transport(e.to_string()))?;
+	/// Fetch a guild's public widget, e.g. for a status page showing online
+	/// members and voice channels. Fails with 403 unless the widget is
+	/// enabled in the guild's settings, no auth header is sent.
+	pub async fn get_guild_widget(
+		&self,
+		guild_id: Id<GuildMarker>,
+	) -> Result<GuildWidget, HttpError> {
+		self.send(GetGuildWidget::new(guild_id)).await
+	}
 
-			if status.is_ok() {
-				return Ok(resp_bytes.to_vec());
-			}
+	/// Fetch a guild with its approximate member and presence counts.
+	pub async fn get_guild(
+		&self,
+		guild_id: Id<GuildMarker>,
+	) -> Result<Guild, HttpError> {
+		self.get_guild_opts(guild_id, true).await
+	}
 
-			let body_str = String::from_utf8_lossy(&resp_bytes).to_string();
-			return Err(HttpError::Api {
-				status,
-				body: body_str,
-				route: route_key.to_string(),
-			});
+	/// Fetch a guild, skipping the heavier approximate counts when
+	/// `with_counts` is false, e.g. when only the name or owner is needed.
+	pub async fn get_guild_opts(
+		&self,
+		guild_id: Id<GuildMarker>,
+		with_counts: bool,
+	) -> Result<Guild, HttpError> {
+		self.send(GetGuild::new(guild_id).with_counts(with_counts))
+			.await
+	}
+
+	/// List a guild's custom emojis.
+	pub async fn list_guild_emojis(
+		&self,
+		guild_id: Id<GuildMarker>,
+	) -> Result<Vec<Emoji>, HttpError> {
+		self.send(GetGuildEmojis::new(guild_id)).await
+	}
+
+	/// Execute a webhook. When Discord rejects a request carrying
+	/// components with a 400, the error's route notes that only
+	/// application-owned webhooks may send them.
+	pub async fn execute_webhook(
+		&self,
+		req: ExecuteWebhook,
+	) -> Result<serde_json::Value, HttpError> {
+		let has_components = req.has_components();
+		self.send(req)
+			.await
+			.map_err(|e| explain_webhook_error(e, has_components))
+	}
+
+	// ------------------------------------------------------------------
+	// Higher-level helpers (compose multiple requests)
+	// ------------------------------------------------------------------
+
+	/// Download the image at `url` and post it to `channel_id` as an
+	/// attachment, so it stays hosted in the channel.
+	///
+	/// Rejects anything that isn't an `image/*` content type or is larger
+	/// than [`URL_FILE_MAX_BYTES`]. The filename comes from the URL path,
+	/// with an extension from the content type if it has none.
+	pub async fn send_message_with_file_from_url(
+		&self,
+		channel_id: Id<ChannelMarker>,
+		content: impl Into<String>,
+		url: &str,
+	) -> Result<Message, HttpError> {
+		let resp = Request::new(HttpMethod::Get, url)
+			.send()
+			.await
+			.map_err(|e| HttpError::Transport(e.to_string()))?;
+		let status = resp.status();
+		if !status.is_ok() {
+			return Err(HttpError::Download(format!(
+				"GET {} returned {}",
+				url, status
+			)));
+		}
+		let headers = &resp.response_parts().headers;
+		let content_type = headers
+			.first_raw("content-type")
+			.unwrap_or_default()
+			.to_string();
+		let extension = image_extension(&content_type)?;
+		let length = headers
+			.first_raw("content-length")
+			.and_then(|s: &str| s.parse::<usize>().ok());
+		if let Some(length) = length {
+			check_download_size(length)?;
+		}
+
+		// Read chunk by chunk, so a missing or wrong `content-length` can't
+		// make us buffer more than the limit.
+		let mut body = resp.body;
+		let mut bytes = Vec::new();
+		while let Some(chunk) = body
+			.next()
+			.await
+			.map_err(|e: BevyError| HttpError::Transport(e.to_string()))?
+		{
+			push_download_chunk(&mut bytes, &chunk)?;
+		}
+		let filename = filename_from_url(url, extension);
+		self.send(
+			CreateMessageWithFile::new(channel_id, filename, bytes)
+				.content(content),
+		)
+		.await
+	}
+
+	/// Count messages in a channel by paginating backwards. Caps at 10 000.
+	pub async fn count_messages(
+		&self,
+		channel_id: Id<ChannelMarker>,
+	) -> Result<usize, HttpError> {
+		self.count_messages_with_limit(channel_id, COUNT_DEFAULT_MAX_PAGES)
+			.await
+	}
+
+	/// Count messages in a channel, scanning at most `max_pages` pages of
+	/// 100 messages.
+	///
+	/// Each page is a request, so a large cap can take minutes and eat into
+	/// the channel's rate limit. Scans past [`COUNT_DEFAULT_MAX_PAGES`] keep
+	/// a typing indicator up in the channel while they run.
+	pub async fn count_messages_with_limit(
+		&self,
+		channel_id: Id<ChannelMarker>,
+		max_pages: usize,
+	) -> Result<usize, HttpError> {
+		let long_scan = max_pages > COUNT_DEFAULT_MAX_PAGES;
+		count_message_pages(max_pages, move |page, before| async move {
+			if long_scan && page % COUNT_TYPING_INTERVAL_PAGES == 0 {
+				if let Err(e) = self.trigger_typing(channel_id).await {
+					debug!(error = %e, "failed to trigger typing during count");
+				}
+			}
+			let mut req = GetChannelMessages::new(channel_id).limit(100);
+			if let Some(b) = before {
+				req = req.before(b);
+			}
+			let messages: Vec<Message> = self.send(req).await?;
+			Ok(messages.into_iter().map(|m| m.id).collect())
+		})
+		.await
+	}
+
+	/// Count messages in each of `channel_ids` like
+	/// [`count_messages`](Self::count_messages), scanning up to
+	/// `concurrency` channels at once.
+	///
+	/// Pages within a channel are still fetched one after another, since
+	/// each depends on the last. Results are in `channel_ids` order.
+	pub async fn count_messages_in_channels(
+		&self,
+		channel_ids: &[Id<ChannelMarker>],
+		concurrency: usize,
+	) -> Vec<(Id<ChannelMarker>, Result<usize, HttpError>)> {
+		let counts = map_concurrent(channel_ids, concurrency, |channel_id| {
+			self.count_messages(*channel_id)
+		})
+		.await;
+		channel_ids.iter().copied().zip(counts).collect()
+	}
+
+	/// Delete the last `count` messages `author_id` sent in a channel, e.g.
+	/// the bot's own test messages after a deploy, returning how many were
+	/// deleted.
+	///
+	/// Only the newest [`CLEANUP_SCAN_PAGES`] pages of messages are
+	/// searched. Messages under 14 days old are removed with bulk deletes
+	/// of up to 100, which need `MANAGE_MESSAGES`, older ones one at a
+	/// time.
+	pub async fn delete_recent_messages_by(
+		&self,
+		channel_id: Id<ChannelMarker>,
+		author_id: Id<UserMarker>,
+		count: usize,
+	) -> Result<usize, HttpError> {
+		let mut messages: Vec<Message> = Vec::new();
+		let mut before = None;
+
+		for _ in 0..CLEANUP_SCAN_PAGES {
+			let mut req = GetChannelMessages::new(channel_id).limit(100);
+			if let Some(b) = before {
+				req = req.before(b);
+			}
+
+			let page: Vec<Message> = self.send(req).await?;
+			let page_len = page.len();
+			before = page.last().map(|m| m.id);
+			messages.extend(page);
+
+			let found =
+				messages.iter().filter(|m| m.author.id == author_id).count();
+			if page_len < 100 || found >= count {
+				break;
+			}
+		}
+
+		let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+		let targets = cleanup_targets(&messages, author_id, count, now_ms);
+		let deleted = targets.bulk.iter().map(Vec::len).sum::<usize>()
+			+ targets.single.len();
+		for batch in targets.bulk {
+			self.send(BulkDeleteMessages::new(channel_id, batch))
+				.await?;
+		}
+		for message_id in targets.single {
+			self.send(DeleteMessage::new(channel_id, message_id))
+				.await?;
+		}
+		Ok(deleted)
+	}
+
+	/// List every guild the current user is a member of, paginating through
+	/// `/users/@me/guilds` 200 at a time.
+	///
+	/// Works with both bot and bearer clients.
+	pub async fn get_current_user_guilds(
+		&self,
+	) -> Result<Vec<CurrentUserGuild>, HttpError> {
+		let mut guilds = Vec::new();
+		let mut after = None;
+
+		loop {
+			let mut req = GetCurrentUserGuilds::new().limit(200);
+			if let Some(a) = after {
+				req = req.after(a);
+			}
+
+			let page: Vec<CurrentUserGuild> = self.send(req).await?;
+			let page_len = page.len();
+			after = page.last().map(|g| g.id);
+			guilds.extend(page);
+
+			if page_len < 200 {
+				break;
+			}
+		}
+
+		Ok(guilds)
+	}
+
+	/// Fetch one message, e.g. to quote it. A deleted or unknown message
+	/// fails with a 404 [`HttpError::Api`], which isn't retried.
+	pub async fn get_message(
+		&self,
+		channel_id: Id<ChannelMarker>,
+		message_id: Id<MessageMarker>,
+	) -> Result<Message, HttpError> {
+		self.send(GetMessage::new(channel_id, message_id)).await
+	}
+
+	/// Get the very first message ever sent in a channel.
+	pub async fn get_first_message(
+		&self,
+		channel_id: Id<ChannelMarker>,
+	) -> Result<Message, HttpError> {
+		let messages: Vec<Message> = self
+			.send(
+				GetChannelMessages::new(channel_id)
+					.after(Id::new(1))
+					.limit(1),
+			)
+			.await?;
+
+		messages.into_iter().next().ok_or_else(|| HttpError::Api {
+			status: StatusCode::NOT_FOUND,
+			body: "No messages found in this channel.".to_string(),
+			route: format!("GET /channels/{}/messages", channel_id),
+			hint: None,
+		})
+	}
+
+	// ------------------------------------------------------------------
+	// Internal: build a beet Request with auth + user-agent
+	// ------------------------------------------------------------------
+
+	fn build_base_request(
+		&self,
+		method: HttpMethod,
+		url: &str,
+		authenticated: bool,
+	) -> Request {
+		let req = Request::new(method, url);
+		if authenticated {
+			apply_auth_headers(req, self.auth, &self.token)
+		} else {
+			apply_user_agent(req)
+		}
+	}
+
+	// ------------------------------------------------------------------
+	// Internal: low-level dispatch with rate-limit handling
+	// ------------------------------------------------------------------
+
+	/// Execute a [`DiscordRequest`] with rate-limit back-off and retry.
+	///
+	/// Returns the raw response bytes on success.
+	async fn raw_request(
+		&self,
+		req: &DiscordRequest,
+		authenticated: bool,
+	) -> Result<Vec<u8>, HttpError> {
+		retry_rate_limited(&req.route_key, self.max_retries, |_| {
+			self.send_once(req, authenticated)
+		})
+		.await
+	}
+
+	/// Perform a single attempt of `req`, honouring any pre-emptive delay
+	/// from the rate limiter and recording the response headers.
+	async fn send_once(
+		&self,
+		req: &DiscordRequest,
+		authenticated: bool,
+	) -> Result<SendOutcome, HttpError> {
+		let route_key = &req.route_key;
+
+		// Pre-request: wait if the rate limiter says so.
+		{
+			let limiter = self.limiter.0.lock().await;
+			if let Some(delay) = limiter.delay_for(route_key) {
+				let delay = delay.min(Duration::from_secs(60));
+				drop(limiter);
+				debug!(
+					route = route_key.as_str(),
+					delay_ms = delay.as_millis() as u64,
+					"rate-limit pre-emptive backoff"
+				);
+				time_ext::sleep(delay).await;
+			}
+		}
+
+		let url = format!("{}/{}", BASE_URL, req.path.trim_start_matches('/'));
+
+		let mut base = self.build_base_request(req.method, &url, authenticated);
+		let http_req = match &req.body {
+			RequestBody::None => base,
+			RequestBody::Json(value) => base
+				.with_json_body(value)
+				.map_err(|e| HttpError::Serde(e.to_string()))?,
+			RequestBody::Raw { content_type, data } => {
+				base.headers.set_raw("content-type", content_type);
+				base.with_body(data.clone())
+			}
+		};
+
+		let started = Instant::now();
+		let resp = match http_req.send().await {
+			Ok(resp) => resp,
+			Err(e) => {
+				self.record_metrics(route_key, None, started.elapsed());
+				return Err(HttpError::Transport(e.to_string()));
+			}
+		};
+
+		let status = resp.status();
+		self.record_metrics(route_key, Some(status), started.elapsed());
+		let rl_info = parse_rate_limit_headers(resp.response_parts());
+
+		// Update the limiter regardless of status.
+		{
+			let mut limiter = self.limiter.0.lock().await;
+			limiter.update(route_key, &rl_info);
+		}
+
+		if status == StatusCode::TOO_MANY_REQUESTS {
+			let retry_after = rl_info.reset_after.unwrap_or(1.0);
+			if rl_info.is_global {
+				let delay = Duration::from_secs_f64(retry_after.min(60.0));
+				let mut limiter = self.limiter.0.lock().await;
+				limiter.global_until = Some(Instant::now() + delay);
+			}
+			debug!(
+				route = route_key.as_str(),
+				global = rl_info.is_global,
+				"received 429"
+			);
+			return Ok(SendOutcome::RateLimited { retry_after });
 		}
 
+		let resp_bytes = resp
+			.bytes()
+			.await
+			.map_err(|e: BevyError| HttpError::Transport(e.to_string()))?;
+
+		if status.is_ok() {
+			return Ok(SendOutcome::Success(resp_bytes.to_vec()));
+		}
+
+		let body_str = String::from_utf8_lossy(&resp_bytes).to_string();
 		Err(HttpError::Api {
-			status: StatusCode::TOO_MANY_REQUESTS,
-			body: "rate-limited after max retries".to_string(),
+			status,
+			body: body_str,
 			route: route_key.to_string(),
+			hint: None,
+		})
+	}
+}
+
+/// The file extension for an `image/*` content type, e.g. `png` for
+/// `image/png; charset=binary`. Errors for anything else.
+fn image_extension(content_type: &str) -> Result<&str, HttpError> {
+	let mime = content_type.split(';').next().unwrap_or_default().trim();
+	match mime.strip_prefix("image/") {
+		Some("jpeg") => Ok("jpg"),
+		Some("svg+xml") => Ok("svg"),
+		Some(subtype) if !subtype.is_empty() => Ok(subtype),
+		_ => Err(HttpError::Download(format!(
+			"expected an image, got content type `{}`",
+			content_type
+		))),
+	}
+}
+
+fn check_download_size(len: usize) -> Result<(), HttpError> {
+	if len > URL_FILE_MAX_BYTES {
+		return Err(HttpError::Download(format!(
+			"file is {} bytes, the limit is {}",
+			len, URL_FILE_MAX_BYTES
+		)));
+	}
+	Ok(())
+}
+
+/// Append `chunk` to a download, failing as soon as it would pass
+/// [`URL_FILE_MAX_BYTES`] rather than after reading the whole body.
+fn push_download_chunk(
+	bytes: &mut Vec<u8>,
+	chunk: &[u8],
+) -> Result<(), HttpError> {
+	check_download_size(bytes.len() + chunk.len())?;
+	bytes.extend_from_slice(chunk);
+	Ok(())
+}
+
+/// The last path segment of `url`, without query or fragment and limited
+/// to characters safe in an attachment name. Falls back to `image`, and
+/// appends `extension` when the name has none.
+fn filename_from_url(url: &str, extension: &str) -> String {
+	let path = url.split(['?', '#']).next().unwrap_or_default();
+	let path = path.split_once("://").map_or(path, |(_, rest)| rest);
+	let segment = path
+		.split_once('/')
+		.map_or("", |(_, path)| path.rsplit('/').next().unwrap_or_default());
+	let name: String = segment
+		.chars()
+		.filter(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+		.collect();
+	let name = name.trim_matches('.');
+	match name {
+		"" => format!("image.{}", extension),
+		name if name.contains('.') => name.to_string(),
+		name => format!("{}.{}", name, extension),
+	}
+}
+
+/// Count messages by paging backwards through up to `max_pages` pages of
+/// 100. `fetch_page(page, before)` returns the ids of one page, newest
+/// first.
+async fn count_message_pages<F, Fut>(
+	max_pages: usize,
+	mut fetch_page: F,
+) -> Result<usize, HttpError>
+where
+	F: FnMut(usize, Option<Id<MessageMarker>>) -> Fut,
+	Fut: Future<Output = Result<Vec<Id<MessageMarker>>, HttpError>>,
+{
+	let mut count = 0usize;
+	let mut before: Option<Id<MessageMarker>> = None;
+
+	for page in 0..max_pages {
+		let ids = fetch_page(page, before).await?;
+		count += ids.len();
+		before = ids.last().copied();
+
+		if ids.len() < 100 {
+			break;
+		}
+	}
+
+	Ok(count)
+}
+
+/// Run `f` on every item with at most `concurrency` futures in flight,
+/// returning the outputs in item order.
+async fn map_concurrent<'a, T, F, Fut>(
+	items: &'a [T],
+	concurrency: usize,
+	f: F,
+) -> Vec<Fut::Output>
+where
+	F: Fn(&'a T) -> Fut,
+	Fut: Future,
+{
+	let semaphore = Semaphore::new(concurrency.max(1));
+	let mut pending: Vec<Option<Pin<Box<_>>>> = items
+		.iter()
+		.map(|item| {
+			let semaphore = &semaphore;
+			let fut = f(item);
+			Some(Box::pin(async move {
+				let _permit = semaphore.acquire().await;
+				fut.await
+			}))
 		})
+		.collect();
+	let mut outputs: Vec<Option<Fut::Output>> =
+		pending.iter().map(|_| None).collect();
+
+	futures_lite::future::poll_fn(|cx| {
+		let mut done = true;
+		for (slot, output) in pending.iter_mut().zip(outputs.iter_mut()) {
+			let Some(fut) = slot else { continue };
+			match fut.as_mut().poll(cx) {
+				Poll::Ready(value) => {
+					*output = Some(value);
+					*slot = None;
+				}
+				Poll::Pending => done = false,
+			}
+		}
+		if done { Poll::Ready(()) } else { Poll::Pending }
+	})
+	.await;
+	outputs.into_iter().flatten().collect()
+}
+
+/// Messages picked for deletion by [`cleanup_targets`].
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CleanupTargets {
+	/// Young enough for bulk delete, in batches of 2 to 100.
+	bulk: Vec<Vec<Id<MessageMarker>>>,
+	/// To delete one at a time.
+	single: Vec<Id<MessageMarker>>,
+}
+
+/// Pick the first `count` of `messages`, newest first, sent by `author_id`
+/// and split them by whether bulk delete still accepts them at `now_ms`.
+fn cleanup_targets(
+	messages: &[Message],
+	author_id: Id<UserMarker>,
+	count: usize,
+	now_ms: u64,
+) -> CleanupTargets {
+	let max_age_ms = BULK_DELETE_MAX_AGE.as_millis() as u64;
+	let (mut bulk, mut single): (Vec<&Message>, Vec<&Message>) = messages
+		.iter()
+		.filter(|m| m.author.id == author_id)
+		.take(count)
+		.partition(|m| {
+			m.snowflake_timestamp_ms()
+				.is_some_and(|sent| now_ms.saturating_sub(sent) < max_age_ms)
+		});
+	// Bulk delete takes at most 100 messages and needs at least two, so a
+	// lone leftover is deleted singly.
+	if bulk.len() % BULK_DELETE_MAX_MESSAGES == 1 {
+		single.extend(bulk.pop());
+	}
+	CleanupTargets {
+		bulk: bulk
+			.chunks(BULK_DELETE_MAX_MESSAGES)
+			.map(|batch| batch.iter().map(|m| m.id).collect())
+			.collect(),
+		single: single.iter().map(|m| m.id).collect(),
+	}
+}
+
+/// The embed and reactions of a [`DiscordHttpClient::post_reaction_vote`],
+/// each emoji once.
+fn reaction_vote<'a>(
+	question: &str,
+	options: &[(&'a str, &str)],
+) -> Result<(Embed, Vec<&'a str>), HttpError> {
+	if options.len() > REACTION_VOTE_MAX_OPTIONS {
+		return Err(HttpError::Validation(format!(
+			"a reaction vote takes at most {} options, got {}",
+			REACTION_VOTE_MAX_OPTIONS,
+			options.len()
+		)));
+	}
+	let mut emojis: Vec<&str> = Vec::new();
+	let mut lines = Vec::new();
+	for (emoji, label) in options {
+		if emojis.contains(emoji) {
+			continue;
+		}
+		emojis.push(emoji);
+		lines.push(format!("{} {}", emoji, label));
+	}
+	let embed = Embed::new()
+		.with_title(question)
+		.with_description(lines.join("\n"))
+		.with_color(colors::DISCORD_BLURPLE);
+	Ok((embed, emojis))
+}
+
+/// Check `message_ids` against bulk delete's limits at `now_ms` before
+/// sending.
+fn check_bulk_delete(
+	message_ids: &[Id<MessageMarker>],
+	now_ms: u64,
+) -> Result<(), HttpError> {
+	let max_age_ms = BULK_DELETE_MAX_AGE.as_millis() as u64;
+	let problem = if !(2..=BULK_DELETE_MAX_MESSAGES)
+		.contains(&message_ids.len())
+	{
+		Some(format!(
+			"bulk delete takes 2 to {} messages, got {}",
+			BULK_DELETE_MAX_MESSAGES,
+			message_ids.len()
+		))
+	} else {
+		message_ids
+			.iter()
+			.find(|id| {
+				now_ms.saturating_sub(snowflake_timestamp_ms(**id))
+					>= max_age_ms
+			})
+			.map(|id| {
+				format!("message {} is too old to bulk delete (14 days)", id)
+			})
+	};
+	match problem {
+		Some(problem) => Err(HttpError::Validation(problem)),
+		None => Ok(()),
 	}
 }
 
+/// Name the likely cause of a 400 from a webhook execution that sent
+/// components: the webhook isn't owned by an application.
+fn explain_webhook_error(err: HttpError, has_components: bool) -> HttpError {
+	match err {
+		HttpError::Api {
+			status,
+			body,
+			route,
+			..
+		} if has_components && status == StatusCode::BAD_REQUEST => HttpError::Api {
+			status,
+			body,
+			route,
+			hint: Some("components require an application-owned webhook"),
+		},
+		other => other,
+	}
+}
+
+// ---------------------------------------------------------------------------
+// Dry run
+// ---------------------------------------------------------------------------
+
+/// Synthesize a success body for a request that was not sent.
+///
+/// Creating or editing a message yields a minimal [`Message`] carrying the
+/// request's content, embeds, components, and flags, and opening a DM a DM
+/// channel with the recipient's id. Anything else gets `{}`, which
+/// satisfies the `()` outputs of most mutating endpoints.
+fn dry_run_response(req: &DiscordRequest) -> Vec<u8> {
+	if req.path == "users/@me/channels" {
+		let recipient = match &req.body {
+			RequestBody::Json(body) => body["recipient_id"].clone(),
+			_ => "1".into(),
+		};
+		let channel = serde_json::json!({
+			"id": recipient,
+			"type": 1,
+			"recipients": [{
+				"id": recipient,
+				"username": "dry-run",
+				"discriminator": "0",
+				"avatar": null,
+			}],
+		});
+		return serde_json::to_vec(&channel).unwrap_or_else(|_| b"{}".to_vec());
+	}
+
+	let is_message_route = req.path.split('/').any(|seg| seg == "messages")
+		&& matches!(req.method, HttpMethod::Post | HttpMethod::Patch);
+	if !is_message_route {
+		return b"{}".to_vec();
+	}
+
+	let channel_id = req
+		.path
+		.strip_prefix("channels/")
+		.and_then(|rest| rest.split('/').next())
+		.unwrap_or("1");
+
+	let mut message = serde_json::json!({
+		"id": "1",
+		"channel_id": channel_id,
+		"author": {
+			"id": "1",
+			"username": "dry-run",
+			"discriminator": "0",
+			"avatar": null,
+			"bot": true,
+		},
+		"content": "",
+		"timestamp": chrono::Utc::now()
+			.format("%Y-%m-%dT%H:%M:%S%.6f+00:00")
+			.to_string(),
+		"edited_timestamp": null,
+		"embeds": [],
+		"attachments": [],
+		"mention_everyone": false,
+		"mention_roles": [],
+		"mentions": [],
+		"pinned": false,
+		"tts": false,
+		"type": 0,
+	});
+
+	if let RequestBody::Json(serde_json::Value::Object(body)) = &req.body {
+		for key in ["content", "embeds", "components", "flags"] {
+			if let Some(value) = body.get(key) {
+				message[key] = value.clone();
+			}
+		}
+	}
+
+	serde_json::to_vec(&message).unwrap_or_else(|_| b"{}".to_vec())
+}
+
+// ---------------------------------------------------------------------------
+// Retry loop
+// ---------------------------------------------------------------------------
+
+/// Result of a single send attempt that didn't fail outright.
+#[derive(Debug)]
+enum SendOutcome {
+	/// A success status with the raw response body.
+	Success(Vec<u8>),
+	/// Discord answered 429; wait `retry_after` seconds before retrying.
+	RateLimited { retry_after: f64 },
+}
+
+/// Drive `send_once` until it succeeds, fails, or has been rate-limited
+/// `max_retries + 1` times, in which case
+/// [`HttpError::RateLimitedExhausted`] is returned.
+async fn retry_rate_limited<F, Fut>(
+	route_key: &str,
+	max_retries: u32,
+	mut send_once: F,
+) -> Result<Vec<u8>, HttpError>
+where
+	F: FnMut(u32) -> Fut,
+	Fut: std::future::Future<Output = Result<SendOutcome, HttpError>>,
+{
+	let mut last_retry_after = 0.0;
+
+	for attempt in 0..=max_retries {
+		match send_once(attempt).await? {
+			SendOutcome::Success(bytes) => return Ok(bytes),
+			SendOutcome::RateLimited { retry_after } => {
+				last_retry_after = retry_after;
+				warn!(
+					route = route_key,
+					attempt,
+					retry_after_s = retry_after,
+					"rate-limited by Discord, backing off"
+				);
+				let delay = Duration::from_secs_f64(retry_after.min(60.0));
+				if attempt < max_retries && !delay.is_zero() {
+					time_ext::sleep(delay).await;
+				}
+			}
+		}
+	}
+
+	Err(HttpError::RateLimitedExhausted {
+		route: route_key.to_string(),
+		last_retry_after,
+	})
+}
+
 impl std::fmt::Debug for DiscordHttpClient {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		f.debug_struct("DiscordHttpClient")
 			.field("token", &"<redacted>")
+			.field("auth", &self.auth)
+			.field("dry_run", &self.dry_run)
+			.field("metrics", &self.metrics.is_some())
 			.finish()
 	}
 }
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_fixtures::*;
+	use twilight_model::channel::message::MentionType;
+
+	// -- RateLimiter -------------------------------------------------------
+
+	fn exhausted(bucket: &str) -> RateLimitInfo {
+		RateLimitInfo {
+			remaining: Some(0),
+			reset_at: None,
+			reset_after: Some(30.0),
+			bucket: Some(bucket.into()),
+			is_global: false,
+		}
+	}
+
+	fn callback_route(interaction_id: u64) -> String {
+		CreateInteractionResponse::new(
+			twilight_model::id::Id::new(interaction_id),
+			"tok",
+			twilight_model::http::interaction::InteractionResponse::pong(),
+		)
+		.into_discord_request()
+		.unwrap()
+		.route_key
+	}
+
+	#[test]
+	fn interaction_callbacks_do_not_block_each_other() {
+		let mut limiter = RateLimiter::new();
+		let first = callback_route(1);
+		let second = callback_route(2);
+		// Discord hands out the same bucket hash for every callback.
+		limiter.update(&first, &exhausted("callback"));
+		assert!(limiter.delay_for(&first).is_some());
+		assert!(limiter.delay_for(&second).is_none());
+		limiter.update(&second, &exhausted("callback"));
+		assert!(limiter.delay_for(&second).is_some());
+	}
+
+	#[test]
+	fn shared_bucket_applies_within_major_parameter() {
+		let mut limiter = RateLimiter::new();
+		limiter.update("POST /channels/1/messages", &exhausted("msgs"));
+		limiter.update("PATCH /channels/1/messages", &exhausted("msgs"));
+		assert!(limiter.delay_for("PATCH /channels/1/messages").is_some());
+		limiter.update("POST /channels/2/messages", &exhausted("msgs"));
+		assert!(limiter.delay_for("POST /channels/1/messages").is_some());
+	}
+
+	#[test]
+	fn interaction_callbacks_skip_global_limit() {
+		let mut limiter = RateLimiter::new();
+		limiter.update("GET /users/@me", &RateLimitInfo {
+			is_global: true,
+			..exhausted("global")
+		});
+		assert!(limiter.delay_for("POST /channels/1/messages").is_some());
+		assert!(limiter.delay_for(&callback_route(1)).is_none());
+	}
+
+	#[test]
+	fn clients_from_one_shared_limiter_share_global_limit() {
+		let limiter = SharedRateLimiter::new();
+		let shard_0 = DiscordHttpClient::from_shared("token", limiter.clone());
+		let shard_1 = DiscordHttpClient::from_shared("token", limiter);
+		assert!(shard_0.shared_limiter().ptr_eq(&shard_1.shared_limiter()));
+
+		futures_lite::future::block_on(async {
+			let global = RateLimitInfo {
+				is_global: true,
+				..exhausted("global")
+			};
+			let mut shared = shard_0.limiter.0.lock().await;
+			shared.update("GET /gateway", &global);
+			drop(shared);
+			let route = "POST /channels/1/messages";
+			assert!(shard_1.limiter.0.lock().await.delay_for(route).is_some());
+			// Separately built clients don't see each other's limits.
+			let other = DiscordHttpClient::new("token");
+			assert!(other.limiter.0.lock().await.delay_for(route).is_none());
+		});
+	}
+
+	#[test]
+	fn major_parameter_prefixes() {
+		assert_eq!(
+			major_parameter("POST /channels/123/messages"),
+			"channels/123"
+		);
+		assert_eq!(
+			major_parameter("POST /interactions/9/callback"),
+			"interactions/9"
+		);
+		assert_eq!(
+			major_parameter("PATCH /webhooks/1/abcd/messages"),
+			"webhooks/1/abcd"
+		);
+		assert_eq!(major_parameter("GET /invites"), "invites");
+	}
+
+	#[test]
+	fn expired_buckets_are_evicted() {
+		let mut limiter = RateLimiter::new();
+		let expired = RateLimitInfo {
+			reset_after: Some(0.0),
+			..exhausted("callback")
+		};
+		for id in 1..=100 {
+			limiter.update(&callback_route(id), &expired);
+		}
+		limiter.update(&callback_route(101), &exhausted("callback"));
+		assert_eq!(limiter.buckets.len(), 1);
+		assert_eq!(limiter.route_buckets.len(), 1);
+		assert!(limiter.delay_for(&callback_route(101)).is_some());
+	}
+
+	#[test]
+	fn route_templates_drop_ids() {
+		assert_eq!(
+			route_template("POST /interactions/9/callback"),
+			"POST /interactions/{id}/callback"
+		);
+		assert_eq!(
+			route_template("PATCH /webhooks/1/abcd/messages/@original"),
+			"PATCH /webhooks/{id}/{token}/messages/@original"
+		);
+		assert_eq!(
+			route_template("POST /channels/123/messages"),
+			"POST /channels/{id}/messages"
+		);
+		assert_eq!(route_template("GET /users/@me"), "GET /users/@me");
+		let template = "POST /channels/{id}/messages";
+		assert_eq!(route_template(template), template);
+	}
+
+	// -- apply_auth_headers() ----------------------------------------------
+
+	#[test]
+	fn auth_headers_use_bot_prefix() {
+		let req = apply_auth_headers(
+			Request::new(
+				HttpMethod::Get,
+				"https://discord.com/api/v10/users/@me",
+			),
+			AuthKind::Bot,
+			"abc.def.ghi",
+		);
+		assert_eq!(
+			req.headers.first_raw("authorization"),
+			Some("Bot abc.def.ghi")
+		);
+	}
+
+	#[test]
+	fn auth_headers_set_user_agent() {
+		let req = apply_auth_headers(
+			Request::new(
+				HttpMethod::Get,
+				"https://discord.com/api/v10/users/@me",
+			),
+			AuthKind::Bot,
+			"token",
+		);
+		assert_eq!(req.headers.first_raw("user-agent"), Some(USER_AGENT));
+	}
+
+	#[test]
+	fn bearer_client_uses_bearer_prefix() {
+		let client = DiscordHttpClient::with_bearer("oauth-token");
+		let req = client.build_base_request(
+			HttpMethod::Get,
+			"https://discord.com/api/v10/users/@me/guilds",
+			true,
+		);
+		assert_eq!(
+			req.headers.first_raw("authorization"),
+			Some("Bearer oauth-token")
+		);
+	}
+
+	#[test]
+	fn unauthenticated_request_omits_authorization() {
+		let client = DiscordHttpClient::new("bot-token");
+		let req = client.build_base_request(
+			HttpMethod::Get,
+			"https://discord.com/api/v10/guilds/1/widget.json",
+			GetGuildWidget::AUTHENTICATED,
+		);
+		assert_eq!(req.headers.first_raw("authorization"), None);
+		assert_eq!(req.headers.first_raw("user-agent"), Some(USER_AGENT));
+	}
+
+	#[test]
+	fn bot_client_uses_bot_prefix() {
+		let client = DiscordHttpClient::new("bot-token");
+		let req = client.build_base_request(
+			HttpMethod::Get,
+			"https://discord.com/api/v10/users/@me",
+			true,
+		);
+		assert_eq!(
+			req.headers.first_raw("authorization"),
+			Some("Bot bot-token")
+		);
+	}
+
+	// -- retry_rate_limited() ---------------------------------------------
+
+	#[test]
+	fn retry_exhaustion_returns_distinct_error() {
+		let mut calls = 0;
+		let result = futures_lite::future::block_on(retry_rate_limited(
+			"GET /channels/1/messages",
+			3,
+			|_| {
+				calls += 1;
+				async { Ok(SendOutcome::RateLimited { retry_after: 0.0 }) }
+			},
+		));
+
+		assert_eq!(calls, 4, "initial attempt plus three retries");
+		match result {
+			Err(HttpError::RateLimitedExhausted {
+				route,
+				last_retry_after,
+			}) => {
+				assert_eq!(route, "GET /channels/1/messages");
+				assert_eq!(last_retry_after, 0.0);
+			}
+			other => panic!("expected RateLimitedExhausted, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn retry_returns_success_after_rate_limit() {
+		let result = futures_lite::future::block_on(retry_rate_limited(
+			"GET /users/@me",
+			3,
+			|attempt| async move {
+				if attempt == 0 {
+					Ok(SendOutcome::RateLimited { retry_after: 0.0 })
+				} else {
+					Ok(SendOutcome::Success(b"ok".to_vec()))
+				}
+			},
+		));
+		assert_eq!(result.unwrap(), b"ok".to_vec());
+	}
+
+	#[test]
+	fn retry_returns_api_errors_without_retrying() {
+		let mut calls = 0;
+		let result = futures_lite::future::block_on(retry_rate_limited(
+			"GET /channels/1/messages",
+			3,
+			|_| {
+				calls += 1;
+				async {
+					Err(HttpError::Api {
+						status: StatusCode::NOT_FOUND,
+						body: r#"{"message":"Unknown Message","code":10008}"#
+							.to_string(),
+						route: "GET /channels/1/messages".to_string(),
+						hint: None,
+					})
+				}
+			},
+		));
+		assert_eq!(calls, 1);
+		let err = result.unwrap_err();
+		assert!(matches!(
+			err,
+			HttpError::Api { status, .. } if status == StatusCode::NOT_FOUND
+		));
+		assert_eq!(err.code(), Some(10008));
+	}
+
+	// -- validation errors ------------------------------------------------
+
+	const INVALID_FORM_BODY: &str = r#"{
+		"code": 50035,
+		"errors": {
+			"content": {
+				"_errors": [{
+					"code": "BASE_TYPE_MAX_LENGTH",
+					"message": "Must be 2000 or fewer in length."
+				}]
+			},
+			"embeds": {
+				"0": {
+					"description": {
+						"_errors": [{
+							"code": "BASE_TYPE_MAX_LENGTH",
+							"message": "Must be 4096 or fewer in length."
+						}]
+					},
+					"fields": {
+						"1": {
+							"name": {
+								"_errors": [{
+									"code": "BASE_TYPE_REQUIRED",
+									"message": "This field is required"
+								}]
+							}
+						}
+					}
+				}
+			}
+		},
+		"message": "Invalid Form Body"
+	}"#;
+
+	fn api_error(body: &str) -> HttpError {
+		HttpError::Api {
+			status: StatusCode::BAD_REQUEST,
+			body: body.to_string(),
+			route: "POST /channels/1/messages".to_string(),
+			hint: None,
+		}
+	}
+
+	#[test]
+	fn validation_errors_flatten_nested_paths() {
+		let errors = api_error(INVALID_FORM_BODY).validation_errors();
+		let errors: Vec<_> = errors
+			.iter()
+			.map(|(path, message)| (path.as_str(), message.as_str()))
+			.collect();
+		assert_eq!(errors, [
+			("content", "Must be 2000 or fewer in length."),
+			("embeds.0.description", "Must be 4096 or fewer in length."),
+			("embeds.0.fields.1.name", "This field is required"),
+		]);
+	}
+
+	#[test]
+	fn validation_errors_in_display() {
+		let text = api_error(INVALID_FORM_BODY).to_string();
+		assert!(
+			text.contains(
+				"embeds.0.description: Must be 4096 or fewer in length."
+			),
+			"{}",
+			text
+		);
+		assert!(!text.contains("_errors"));
+	}
+
+	#[test]
+	fn other_api_errors_display_raw_body() {
+		let body = r#"{"message": "Unknown Channel", "code": 10003}"#;
+		let err = api_error(body);
+		assert!(err.validation_errors().is_empty());
+		assert!(err.to_string().ends_with(body));
+		assert_eq!(err.code(), Some(UNKNOWN_CHANNEL));
+		assert_eq!(HttpError::Transport("reset".into()).code(), None);
+	}
+
+	#[test]
+	fn transient_errors() {
+		let with_status = |status| HttpError::Api {
+			status,
+			..api_error("{}")
+		};
+		assert!(HttpError::Transport("reset".into()).is_transient());
+		assert!(with_status(StatusCode::SERVICE_UNAVAILABLE).is_transient());
+		assert!(with_status(StatusCode::TOO_MANY_REQUESTS).is_transient());
+		assert!(!with_status(StatusCode::FORBIDDEN).is_transient());
+		assert!(!api_error("{}").is_transient());
+		assert!(!HttpError::Serde("eof".into()).is_transient());
+	}
+
+	#[test]
+	fn pin_limit_error_keeps_discord_message() {
+		let body = r#"{"message": "Maximum number of pins reached (50)", "code": 30003}"#;
+		let err = api_error(body);
+		assert!(err.to_string().contains("Maximum number of pins reached"));
+		assert_eq!(err.code(), Some(MAX_PINS_REACHED));
+	}
+
+	// -- send_message_with_file_from_url ----------------------------------
+
+	#[test]
+	fn filename_inferred_from_url() {
+		let name = |url| filename_from_url(url, "png");
+		assert_eq!(name("https://example.com/cats/tabby.jpg"), "tabby.jpg");
+		assert_eq!(name("https://example.com/a/b.gif?size=2#top"), "b.gif");
+		assert_eq!(name("https://example.com/render"), "render.png");
+		assert_eq!(name("https://example.com/"), "image.png");
+		assert_eq!(name("https://example.com"), "image.png");
+		assert_eq!(name("https://example.com/my%20cat.webp"), "my20cat.webp");
+	}
+
+	#[test]
+	fn only_image_content_types_are_accepted() {
+		assert_eq!(image_extension("image/png").unwrap(), "png");
+		let jpeg = image_extension("image/jpeg; charset=binary");
+		assert_eq!(jpeg.unwrap(), "jpg");
+		assert!(matches!(
+			image_extension("text/html; charset=utf-8"),
+			Err(HttpError::Download(_))
+		));
+		assert!(image_extension("").is_err());
+		assert!(image_extension("image/").is_err());
+	}
+
+	#[test]
+	fn oversized_downloads_are_rejected() {
+		assert!(check_download_size(URL_FILE_MAX_BYTES).is_ok());
+		assert!(check_download_size(URL_FILE_MAX_BYTES + 1).is_err());
+	}
+
+	#[test]
+	fn download_stops_at_the_first_chunk_past_the_limit() {
+		let chunk = vec![0; 1024 * 1024];
+		let mut bytes = Vec::new();
+		let mut pushed = 0;
+		let result = std::iter::repeat(&chunk).take(100).try_for_each(|c| {
+			pushed += 1;
+			push_download_chunk(&mut bytes, c)
+		});
+		assert!(matches!(result, Err(HttpError::Download(_))));
+		assert_eq!(pushed, URL_FILE_MAX_BYTES / chunk.len() + 1);
+		assert_eq!(bytes.len(), URL_FILE_MAX_BYTES);
+	}
+
+	// -- execute_webhook --------------------------------------------------
+
+	#[test]
+	fn webhook_component_rejection_is_explained() {
+		let err = explain_webhook_error(api_error(INVALID_FORM_BODY), true);
+		assert!(
+			err.to_string().contains("application-owned webhook"),
+			"{}",
+			err
+		);
+		// Validation details are still reported, and the route key is
+		// left alone.
+		assert!(!err.validation_errors().is_empty());
+		let HttpError::Api { route, .. } = &err else {
+			panic!("expected an API error, got {:?}", err);
+		};
+		assert_eq!(route, "POST /channels/1/messages");
+
+		let err = explain_webhook_error(api_error(INVALID_FORM_BODY), false);
+		assert!(!err.to_string().contains("application-owned webhook"));
+	}
+
+	// -- count_message_pages ---------------------------------------------
+
+	/// A page of `len` ids counting down from `before`.
+	fn message_page(
+		before: Option<Id<MessageMarker>>,
+		len: u64,
+	) -> Vec<Id<MessageMarker>> {
+		let top = before.map_or(1_000_000, |id| id.get());
+		(1..=len).map(|i| Id::new(top - i)).collect()
+	}
+
+	#[test]
+	fn map_concurrent_bounds_in_flight_futures() {
+		use std::cell::Cell;
+
+		let in_flight = Cell::new(0);
+		let max_in_flight = Cell::new(0);
+		let items: Vec<usize> = (0..10).collect();
+		let doubled = map_concurrent(&items, 3, |item| {
+			let (in_flight, max_in_flight) = (&in_flight, &max_in_flight);
+			async move {
+				in_flight.set(in_flight.get() + 1);
+				max_in_flight.set(max_in_flight.get().max(in_flight.get()));
+				// Let the other futures run before finishing.
+				for _ in 0..3 {
+					futures_lite::future::yield_now().await;
+				}
+				in_flight.set(in_flight.get() - 1);
+				item * 2
+			}
+		});
+		let outputs = futures_lite::future::block_on(doubled);
+
+		assert_eq!(max_in_flight.get(), 3);
+		assert_eq!(outputs, (0..10).map(|i| i * 2).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn count_message_pages_respects_page_cap() {
+		let mut pages = Vec::new();
+		let count = futures_lite::future::block_on(count_message_pages(
+			3,
+			|page, before| {
+				pages.push(page);
+				async move { Ok(message_page(before, 100)) }
+			},
+		))
+		.unwrap();
+		assert_eq!(count, 300);
+		assert_eq!(pages, [0, 1, 2]);
+	}
+
+	#[test]
+	fn count_message_pages_stops_at_short_page() {
+		let mut befores = Vec::new();
+		let count = futures_lite::future::block_on(count_message_pages(
+			10,
+			|page, before| {
+				befores.push(before.map(|id| id.get()));
+				let len = if page == 1 { 42 } else { 100 };
+				async move { Ok(message_page(before, len)) }
+			},
+		))
+		.unwrap();
+		assert_eq!(count, 142);
+		// Each page continues before the oldest id of the last.
+		assert_eq!(befores, [None, Some(999_900)]);
+	}
+
+	// -- cleanup_targets -------------------------------------------------
+
+	const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+	const NOW_MS: u64 = 1_700_000_000_000;
+
+	/// A message from `author` sent `age_days` before [`NOW_MS`].
+	fn message_at(age_days: u64, author: u64) -> Message {
+		let sent_ms = NOW_MS - age_days * DAY_MS;
+		let id = (sent_ms - 1_420_070_400_000) << 22;
+		let mut json = message_json(&id.to_string(), "");
+		json["author"]["id"] = author.to_string().into();
+		parse_message(json)
+	}
+
+	#[test]
+	fn cleanup_targets_filters_by_author_and_age() {
+		let bot = Id::new(7);
+		let messages = [
+			message_at(0, 7),
+			message_at(1, 8),
+			message_at(2, 7),
+			message_at(20, 7),
+			message_at(30, 7),
+		];
+		let targets = cleanup_targets(&messages, bot, 10, NOW_MS);
+		assert_eq!(targets.bulk, [vec![messages[0].id, messages[2].id]]);
+		assert_eq!(targets.single, [messages[3].id, messages[4].id]);
+	}
+
+	#[test]
+	fn cleanup_targets_takes_newest_count() {
+		let messages = [message_at(0, 7), message_at(1, 7), message_at(2, 7)];
+		let targets = cleanup_targets(&messages, Id::new(7), 2, NOW_MS);
+		assert_eq!(targets.bulk, [vec![messages[0].id, messages[1].id]]);
+		assert!(targets.single.is_empty());
+	}
+
+	#[test]
+	fn cleanup_targets_deletes_lone_recent_message_singly() {
+		let messages = [message_at(0, 7), message_at(20, 7)];
+		let targets = cleanup_targets(&messages, Id::new(7), 5, NOW_MS);
+		assert!(targets.bulk.is_empty());
+		assert_eq!(targets.single.len(), 2);
+		assert!(targets.single.contains(&messages[0].id));
+	}
+
+	#[test]
+	fn cleanup_targets_batches_bulk_deletes_by_100() {
+		let messages: Vec<Message> =
+			(0..201).map(|_| message_at(0, 7)).collect();
+		let targets = cleanup_targets(&messages, Id::new(7), 300, NOW_MS);
+		let batches: Vec<usize> = targets.bulk.iter().map(Vec::len).collect();
+		assert_eq!(batches, [100, 100]);
+		// The 201st can't be bulk deleted alone.
+		assert_eq!(targets.single.len(), 1);
+
+		let targets = cleanup_targets(&messages, Id::new(7), 150, NOW_MS);
+		let batches: Vec<usize> = targets.bulk.iter().map(Vec::len).collect();
+		assert_eq!(batches, [100, 50]);
+		assert!(targets.single.is_empty());
+	}
+
+	#[test]
+	fn bulk_delete_checks_count_and_age() {
+		let ids = |ages: &[u64]| -> Vec<Id<MessageMarker>> {
+			ages.iter().map(|age| message_at(*age, 7).id).collect()
+		};
+		assert!(check_bulk_delete(&ids(&[0, 13]), NOW_MS).is_ok());
+
+		let too_old = check_bulk_delete(&ids(&[0, 15]), NOW_MS);
+		let Err(HttpError::Validation(problem)) = too_old else {
+			panic!("expected a validation error, got {:?}", too_old);
+		};
+		assert!(problem.contains("too old"), "{}", problem);
+
+		for ages in [vec![], vec![0], vec![0; 101]] {
+			let err = check_bulk_delete(&ids(&ages), NOW_MS)
+				.unwrap_err()
+				.to_string();
+			assert!(err.contains("invalid request: bulk delete"), "{}", err);
+		}
+	}
+
+	#[test]
+	fn reaction_vote_caps_options_and_reacts_once_per_emoji() {
+		let (embed, emojis) = reaction_vote(
+			"Lunch?",
+			&[("🍕", "Pizza"), ("🌮", "Tacos"), ("🍕", "More pizza")],
+		)
+		.unwrap();
+		assert_eq!(emojis, ["🍕", "🌮"]);
+		assert_eq!(embed.title.as_deref(), Some("Lunch?"));
+		assert_eq!(embed.description.as_deref(), Some("🍕 Pizza\n🌮 Tacos"));
+
+		let options = vec![("👍", "Yes"); REACTION_VOTE_MAX_OPTIONS];
+		assert!(reaction_vote("?", &options).is_ok());
+		let options = vec![("👍", "Yes"); REACTION_VOTE_MAX_OPTIONS + 1];
+		let err = reaction_vote("?", &options);
+		let Err(HttpError::Validation(problem)) = err else {
+			panic!("expected a validation error, got {:?}", err);
+		};
+		assert!(problem.contains("at most 20 options"), "{}", problem);
+	}
+
+	#[test]
+	fn bulk_delete_of_one_message_deletes_it_singly() {
+		let client = DiscordHttpClient::new("token").dry_run(true);
+		// An old message would be rejected by bulk delete.
+		let old = message_at(30, 7).id;
+		let result = futures_lite::future::block_on(
+			client.bulk_delete_messages(Id::new(42), &[old]),
+		);
+		assert!(result.is_ok());
+	}
+
+	// -- dry_run ----------------------------------------------------------
+
+	#[test]
+	fn dry_run_create_message_returns_fake_message() {
+		let client = DiscordHttpClient::new("token").dry_run(true);
+		let msg = futures_lite::future::block_on(
+			client.send(CreateMessage::new(Id::new(42)).content("hi")),
+		)
+		.unwrap();
+		assert_eq!(msg.content, "hi");
+		assert_eq!(msg.channel_id.get(), 42);
+	}
+
+	#[test]
+	fn dry_run_delete_returns_unit() {
+		let client = DiscordHttpClient::new("token").dry_run(true);
+		let result = futures_lite::future::block_on(
+			client.send(DeleteMessage::new(Id::new(42), Id::new(7))),
+		);
+		assert!(result.is_ok());
+	}
+
+	#[test]
+	fn send_dm_reuses_cached_channel() {
+		let client = DiscordHttpClient::new("token").dry_run(true);
+		// The cache is shared between clones.
+		client
+			.clone()
+			.dm_channels
+			.lock()
+			.unwrap()
+			.insert(Id::new(7), Id::new(70));
+		let msg =
+			futures_lite::future::block_on(client.send_dm(Id::new(7), "hi"))
+				.unwrap();
+		assert_eq!(msg.channel_id, Id::new(70));
+		assert_eq!(msg.content, "hi");
+		// An uncached user gets a fake DM channel with their id.
+		let msg =
+			futures_lite::future::block_on(client.send_dm(Id::new(8), "hi"))
+				.unwrap();
+		assert_eq!(msg.channel_id, Id::new(8));
+	}
+
+	#[test]
+	fn dry_run_log_keeps_skipped_requests() {
+		let client = DiscordHttpClient::new("token")
+			.dry_run(true)
+			.with_dry_run_log(true);
+		// Clones share the log.
+		let sender = client.clone();
+		futures_lite::future::block_on(
+			sender.send(DeleteMessage::new(Id::new(42), Id::new(7))),
+		)
+		.unwrap();
+		let log = client.take_dry_run_log();
+		assert_eq!(log.len(), 1);
+		assert_eq!(log[0].path, "channels/42/messages/7");
+		assert!(client.take_dry_run_log().is_empty());
+		assert!(
+			DiscordHttpClient::new("token")
+				.dry_run(true)
+				.take_dry_run_log()
+				.is_empty()
+		);
+	}
+
+	#[test]
+	fn create_message_does_not_ping_everyone_by_default() {
+		let client = DiscordHttpClient::new("token")
+			.dry_run(true)
+			.with_dry_run_log(true);
+		futures_lite::future::block_on(client.send(
+			CreateMessage::new(Id::new(42)).content("@everyone look at this"),
+		))
+		.unwrap();
+		let sent = client.take_dry_run_log();
+		let RequestBody::Json(body) = &sent[0].body else {
+			panic!("expected a JSON body, got {:?}", sent[0].body);
+		};
+		let mentions = &body["allowed_mentions"];
+		assert_eq!(mentions["parse"], serde_json::json!(["users", "roles"]));
+		assert_eq!(mentions["replied_user"], true);
+	}
+
+	#[test]
+	fn default_allowed_mentions_can_be_overridden() {
+		let client = DiscordHttpClient::new("token")
+			.dry_run(true)
+			.with_dry_run_log(true)
+			.with_allowed_mentions(AllowedMentions {
+				parse: vec![MentionType::Users],
+				..Default::default()
+			});
+		let everyone = AllowedMentions {
+			parse: vec![MentionType::Everyone],
+			..Default::default()
+		};
+		futures_lite::future::block_on(async {
+			client
+				.send(CreateMessage::new(Id::new(42)).content("<@1> hi"))
+				.await
+				.unwrap();
+			client
+				.send(
+					CreateMessage::new(Id::new(42))
+						.content("@everyone hi")
+						.allowed_mentions(everyone),
+				)
+				.await
+				.unwrap();
+		});
+		let parses: Vec<_> = client
+			.take_dry_run_log()
+			.into_iter()
+			.map(|req| match req.body {
+				RequestBody::Json(body) => {
+					body["allowed_mentions"]["parse"].clone()
+				}
+				other => panic!("expected a JSON body, got {:?}", other),
+			})
+			.collect();
+		// The client default, then the message's own mentions.
+		assert_eq!(parses, [
+			serde_json::json!(["users"]),
+			serde_json::json!(["everyone"])
+		]);
+		// Other clients keep the built-in default.
+		assert_eq!(
+			DiscordHttpClient::new("token").allowed_mentions(),
+			&default_allowed_mentions()
+		);
+	}
+
+	#[test]
+	fn dry_run_reports_unfakeable_responses() {
+		let client = DiscordHttpClient::new("token").dry_run(true);
+		let result = futures_lite::future::block_on(
+			client.send(CreateInvite::new(Id::new(42))),
+		);
+		assert!(matches!(
+			result,
+			Err(HttpError::DryRun { route }) if route == "POST /channels/42/invites"
+		));
+	}
+
+	#[test]
+	fn send_embed_sends_single_embed_without_content() {
+		let client = DiscordHttpClient::new("token").dry_run(true);
+		let msg = futures_lite::future::block_on(
+			client.send_embed(Id::new(42), Embed::new().with_title("T")),
+		)
+		.unwrap();
+		assert_eq!(msg.channel_id.get(), 42);
+		assert_eq!(msg.embeds.len(), 1);
+		assert_eq!(msg.embeds[0].title.as_deref(), Some("T"));
+		assert!(msg.content.is_empty());
+	}
+
+	#[test]
+	fn reply_embed_sends_single_embed() {
+		let client = DiscordHttpClient::new("token").dry_run(true);
+		let msg = futures_lite::future::block_on(client.reply_embed(
+			Id::new(42),
+			Id::new(7),
+			Embed::new().with_title("T"),
+		))
+		.unwrap();
+		assert_eq!(msg.embeds.len(), 1);
+		assert!(msg.content.is_empty());
+	}
+
+	// -- metrics ----------------------------------------------------------
+
+	#[test]
+	fn metrics_disabled_by_default() {
+		let client = DiscordHttpClient::new("token").dry_run(true);
+		futures_lite::future::block_on(
+			client.send(DeleteMessage::new(Id::new(42), Id::new(7))),
+		)
+		.unwrap();
+		assert!(client.metrics_snapshot().is_none());
+	}
+
+	#[test]
+	fn metrics_count_requests_per_route() {
+		let client = DiscordHttpClient::new("token").with_metrics(true);
+		// Clones share the same metrics.
+		let other = client.clone();
+		client.record_metrics(
+			"POST /channels/42/messages",
+			Some(StatusCode::OK),
+			Duration::from_millis(20),
+		);
+		other.record_metrics(
+			"DELETE /channels/42/messages",
+			Some(StatusCode::NO_CONTENT),
+			Duration::from_millis(20),
+		);
+
+		let snapshot = client.metrics_snapshot().unwrap();
+		assert_eq!(snapshot.routes.len(), 2);
+		let create = snapshot.route("POST /channels/42/messages").unwrap();
+		assert_eq!(create.count, 1);
+		assert_eq!(create.status_count(StatusCode::OK), 1);
+		assert_eq!(create.latency_buckets[0], 1);
+		let delete = snapshot.route("DELETE /channels/42/messages").unwrap();
+		assert_eq!(delete.count, 1);
+	}
+
+	#[test]
+	fn dry_run_requests_skip_metrics() {
+		let client = DiscordHttpClient::new("token")
+			.dry_run(true)
+			.with_metrics(true);
+		futures_lite::future::block_on(
+			client.send(CreateMessage::new(Id::new(42)).content("a")),
+		)
+		.unwrap();
+		assert!(client.metrics_snapshot().unwrap().routes.is_empty());
+	}
+
+	#[test]
+	fn metrics_group_interaction_callbacks_by_template() {
+		let mut metrics = HttpMetrics::default();
+		let latency = Duration::from_millis(20);
+		metrics.record(&callback_route(1), Some(StatusCode::OK), latency);
+		metrics.record(&callback_route(2), Some(StatusCode::OK), latency);
+		assert_eq!(metrics.routes.len(), 1);
+		let callbacks = &metrics.routes["POST /interactions/{id}/callback"];
+		assert_eq!(callbacks.count, 2);
+		assert_eq!(metrics.route(&callback_route(3)).unwrap().count, 2);
+	}
+
+	#[test]
+	fn route_metrics_histogram_and_statuses() {
+		let mut metrics = RouteMetrics::default();
+		metrics.record(Some(StatusCode::OK), Duration::from_millis(20));
+		metrics.record(
+			Some(StatusCode::TOO_MANY_REQUESTS),
+			Duration::from_millis(300),
+		);
+		metrics.record(None, Duration::from_secs(10));
+
+		assert_eq!(metrics.count, 3);
+		assert_eq!(metrics.transport_errors, 1);
+		assert_eq!(metrics.status_count(StatusCode::OK), 1);
+		assert_eq!(metrics.status_count(StatusCode::TOO_MANY_REQUESTS), 1);
+		assert_eq!(metrics.status_count(StatusCode::NOT_FOUND), 0);
+		assert_eq!(metrics.latency_buckets, [1, 0, 0, 1, 0, 0, 0, 1]);
+		assert_eq!(metrics.max_latency, Duration::from_secs(10));
+		assert_eq!(metrics.mean_latency(), Duration::from_millis(3440));
+	}
+
+	#[test]
+	fn dry_run_response_copies_embeds() {
+		let req = CreateMessage::new(Id::new(42))
+			.embed(Embed::new().with_title("T"))
+			.into_discord_request()
+			.unwrap();
+		let message: Message = parse_json(&dry_run_response(&req)).unwrap();
+		assert_eq!(message.embeds.len(), 1);
+		assert_eq!(message.embeds[0].title.as_deref(), Some("T"));
+	}
+
+	// -- Debug redaction ---------------------------------------------------
+
+	#[test]
+	fn debug_redacts_token() {
+		let client = DiscordHttpClient::new("super-secret-token");
+		let debug = format!("{:?}", client);
+		assert!(debug.contains("<redacted>"));
+		assert!(!debug.contains("super-secret-token"));
+	}
+}
@@ -0,0 +1,50 @@
+//! Named embed colors.
+//!
+//! Discord embeds take a 24-bit RGB integer; use these instead of bare hex
+//! literals so the palette stays consistent across handlers.
+//!
+//! ```ignore
+//! let embed = Embed::new().with_color(colors::SUCCESS_GREEN);
+//! ```
+
+/// Discord's brand blurple.
+pub const DISCORD_BLURPLE: u32 = 0x5865F2;
+/// Confirmations and successful operations.
+pub const SUCCESS_GREEN: u32 = 0x57F287;
+/// Failures and destructive actions.
+pub const ERROR_RED: u32 = 0xED4245;
+/// Warnings and user reports.
+pub const WARNING_ORANGE: u32 = 0xFF6600;
+/// Informational notices.
+pub const INFO_BLUE: u32 = 0x3498DB;
+/// Neutral, low-emphasis embeds.
+pub const NEUTRAL_GREY: u32 = 0x99AAB5;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn palette_values() {
+		assert_eq!(DISCORD_BLURPLE, 0x5865F2);
+		assert_eq!(SUCCESS_GREEN, 0x57F287);
+		assert_eq!(ERROR_RED, 0xED4245);
+		assert_eq!(WARNING_ORANGE, 0xFF6600);
+		assert_eq!(INFO_BLUE, 0x3498DB);
+		assert_eq!(NEUTRAL_GREY, 0x99AAB5);
+	}
+
+	#[test]
+	fn palette_fits_in_24_bits() {
+		for color in [
+			DISCORD_BLURPLE,
+			SUCCESS_GREEN,
+			ERROR_RED,
+			WARNING_ORANGE,
+			INFO_BLUE,
+			NEUTRAL_GREY,
+		] {
+			assert!(color <= 0xFFFFFF);
+		}
+	}
+}
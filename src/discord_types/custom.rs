@@ -10,10 +10,23 @@
 use beet::prelude::*;
 use serde::Deserialize;
 use serde::Serialize;
+use twilight_model::application::interaction::Interaction;
+use twilight_model::application::interaction::InteractionData;
+use twilight_model::application::interaction::modal::ModalInteractionComponent;
+use twilight_model::application::interaction::modal::ModalInteractionData;
+use twilight_model::channel::message::component::Component;
+use twilight_model::http::interaction::InteractionResponse;
+use twilight_model::http::interaction::InteractionResponseData;
 use twilight_model::id::Id;
 use twilight_model::id::marker::ChannelMarker;
 use twilight_model::id::marker::GuildMarker;
 use twilight_model::id::marker::MessageMarker;
+use twilight_model::poll::PollMedia;
+
+use super::InteractionResponseDataExt;
+use super::InteractionResponseExt;
+use super::action_row;
+use super::text_input;
 
 // ---------------------------------------------------------------------------
 // DiscordRequest / RequestBody
@@ -28,10 +41,29 @@ pub struct DiscordRequest {
 	pub path: String,
 	/// Route template used for per-route rate-limit bucketing,
 	/// e.g. `"POST /channels/123/messages"`.
+	///
+	/// Discord buckets per *major parameter* (channel, guild, webhook id and
+	/// token, interaction id), so those must appear in the key; tokens are
+	/// passed through [`token_route_id`] to keep them out of logs.
+	/// Interaction callbacks (`POST /interactions/{id}/callback`) are exempt
+	/// from the global rate limit.
 	pub route_key: String,
 	pub body: RequestBody,
 }
 
+/// Stand-in for a webhook or interaction token in a
+/// [`DiscordRequest::route_key`].
+///
+/// Each token is its own rate-limit bucket, but route keys are logged, so
+/// the token is hashed rather than embedded verbatim.
+pub fn token_route_id(token: &str) -> String {
+	use std::hash::Hash;
+	use std::hash::Hasher;
+	let mut hasher = std::collections::hash_map::DefaultHasher::new();
+	token.hash(&mut hasher);
+	format!("{:016x}", hasher.finish())
+}
+
 /// The body payload of a [`DiscordRequest`].
 #[derive(Debug)]
 pub enum RequestBody {
@@ -70,7 +102,8 @@ impl std::error::Error for JsonError {}
 ///
 /// Auth and user-agent headers are appended automatically by
 /// [`DiscordHttpClient::send`](crate::discord_io::DiscordHttpClient::send)
-/// before the request is dispatched.
+/// before the request is dispatched, public endpoints opt out of the auth
+/// header through [`Self::AUTHENTICATED`].
 ///
 /// # Examples
 ///
@@ -86,6 +119,10 @@ pub trait IntoDiscordRequest {
 	/// The type returned in the response body (e.g. `Message`, `()`, …).
 	type Output;
 
+	/// Whether to send the `authorization` header, `false` for public
+	/// endpoints like the guild widget.
+	const AUTHENTICATED: bool = true;
+
 	/// Build the [`DiscordRequest`] from this value.
 	fn into_discord_request(self) -> Result<DiscordRequest, JsonError>;
 
@@ -179,8 +216,9 @@ pub fn build_multipart(
 	buf
 }
 
-/// Percent-encode a unicode emoji for use in reaction URLs.
-/// Custom emoji in `name:id` format are returned as-is.
+/// Percent-encode a unicode emoji for use in reaction URLs, e.g. `✅`
+/// becomes `%E2%9C%85`. Custom emoji in `name:id` format, e.g.
+/// `blobcat:123456789`, are returned as-is.
 pub fn url_encode_emoji(emoji: &str) -> String {
 	if emoji.contains(':') {
 		// Custom emoji — no encoding needed.
@@ -214,6 +252,261 @@ pub struct CreateMessageReference {
 	pub fail_if_not_exists: bool,
 }
 
+// ---------------------------------------------------------------------------
+// Outbound poll (used by CreateMessage body)
+// ---------------------------------------------------------------------------
+
+/// Maximum number of answers Discord accepts on a poll.
+pub const POLL_MAX_ANSWERS: usize = 10;
+
+/// A native poll attached to an outbound message.
+///
+/// twilight-model's `Poll` describes a poll *received* on a message and
+/// carries results and expiry; this is the smaller creation payload.
+///
+/// ```ignore
+/// let poll = CreatePoll::new("Lunch?").answer("Pizza").answer("Tacos");
+/// http.send(CreateMessage::new(channel_id).poll(poll)).await?;
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatePoll {
+	pub question: PollMedia,
+	pub answers: Vec<CreatePollAnswer>,
+	/// Hours the poll stays open, Discord defaults to 24.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub duration: Option<u32>,
+	pub allow_multiselect: bool,
+}
+
+/// A single answer of a [`CreatePoll`].
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatePollAnswer {
+	pub poll_media: PollMedia,
+}
+
+impl CreatePoll {
+	pub fn new(question: impl Into<String>) -> Self {
+		Self {
+			question: text_media(question),
+			answers: Vec::new(),
+			duration: None,
+			allow_multiselect: false,
+		}
+	}
+
+	/// Append a text answer.
+	pub fn answer(mut self, text: impl Into<String>) -> Self {
+		self.answers.push(CreatePollAnswer {
+			poll_media: text_media(text),
+		});
+		self
+	}
+
+	/// Number of hours the poll stays open.
+	pub fn duration(mut self, hours: u32) -> Self {
+		self.duration = Some(hours);
+		self
+	}
+
+	/// Allow voters to pick more than one answer.
+	pub fn allow_multiselect(mut self, allow: bool) -> Self {
+		self.allow_multiselect = allow;
+		self
+	}
+
+	/// Check the answer count is within Discord's 1–10 range.
+	pub fn validate(&self) -> Result<(), JsonError> {
+		if (1..=POLL_MAX_ANSWERS).contains(&self.answers.len()) {
+			Ok(())
+		} else {
+			Err(JsonError(format!(
+				"poll requires 1-{} answers, got {}",
+				POLL_MAX_ANSWERS,
+				self.answers.len()
+			)))
+		}
+	}
+}
+
+fn text_media(text: impl Into<String>) -> PollMedia {
+	PollMedia {
+		emoji: None,
+		text: Some(text.into()),
+	}
+}
+
+// ---------------------------------------------------------------------------
+// Modal builder
+// ---------------------------------------------------------------------------
+
+/// Maximum number of text inputs (one per action row) Discord accepts in a
+/// modal.
+pub const MODAL_MAX_INPUTS: usize = 5;
+
+/// Builds a modal response, wrapping each text input in its own action row
+/// as Discord requires.
+///
+/// ```ignore
+/// let modal = ModalBuilder::new("report_modal", "Submit a Report")
+///     .text_input("subject", "Subject", 1, true)
+///     .text_input("body", "Description", 2, true)
+///     .build()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct ModalBuilder {
+	custom_id: String,
+	title: String,
+	inputs: Vec<Component>,
+}
+
+impl ModalBuilder {
+	pub fn new(custom_id: impl Into<String>, title: impl Into<String>) -> Self {
+		Self {
+			custom_id: custom_id.into(),
+			title: title.into(),
+			inputs: Vec::new(),
+		}
+	}
+
+	/// Append a text input, see [`text_input`] for the arguments.
+	pub fn text_input(
+		mut self,
+		custom_id: impl Into<String>,
+		label: impl Into<String>,
+		style: u8,
+		required: bool,
+	) -> Self {
+		self.inputs
+			.push(text_input(custom_id, label, style, required));
+		self
+	}
+
+	/// Check the modal has between 1 and [`MODAL_MAX_INPUTS`] inputs.
+	pub fn validate(&self) -> Result<(), JsonError> {
+		if (1..=MODAL_MAX_INPUTS).contains(&self.inputs.len()) {
+			Ok(())
+		} else {
+			Err(JsonError(format!(
+				"modal requires 1-{} text inputs, got {}",
+				MODAL_MAX_INPUTS,
+				self.inputs.len()
+			)))
+		}
+	}
+
+	/// The top-level components, one action row per input.
+	pub fn components(&self) -> Vec<Component> {
+		self.inputs
+			.iter()
+			.map(|input| action_row(vec![input.clone()]))
+			.collect()
+	}
+
+	/// Validate and build the modal [`InteractionResponse`].
+	pub fn build(self) -> Result<InteractionResponse, JsonError> {
+		self.validate()?;
+		let components = self.components();
+		Ok(InteractionResponse::modal(
+			InteractionResponseData::default()
+				.with_title(self.title)
+				.with_custom_id(self.custom_id)
+				.with_components(components),
+		))
+	}
+}
+
+// ---------------------------------------------------------------------------
+// Modal submit values
+// ---------------------------------------------------------------------------
+
+/// The values submitted with a modal, keyed by component custom id.
+///
+/// ```ignore
+/// let values = ModalValues::from_interaction(&interaction)?;
+/// let subject = values.text("report_subject").unwrap_or_default();
+/// let severity = values.select("report_severity");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModalValues {
+	/// The custom id of the submitted modal.
+	pub custom_id: String,
+	pub text_inputs: HashMap<String, String>,
+	/// Chosen values of each select menu. User, role, mentionable and
+	/// channel selects hold the chosen ids.
+	pub selects: HashMap<String, Vec<String>>,
+}
+
+impl ModalValues {
+	/// Read the values of a modal submit, `None` for other interactions.
+	pub fn from_interaction(interaction: &Interaction) -> Option<Self> {
+		match interaction.data.as_ref()? {
+			InteractionData::ModalSubmit(data) => Some(Self::from_data(data)),
+			_ => None,
+		}
+	}
+
+	pub fn from_data(data: &ModalInteractionData) -> Self {
+		let mut values = Self {
+			custom_id: data.custom_id.clone(),
+			..Default::default()
+		};
+		for component in &data.components {
+			values.collect(component);
+		}
+		values
+	}
+
+	/// The value of the text input `custom_id`.
+	pub fn text(&self, custom_id: &str) -> Option<&str> {
+		self.text_inputs.get(custom_id).map(String::as_str)
+	}
+
+	/// The chosen values of the select `custom_id`, empty if it is missing.
+	pub fn select(&self, custom_id: &str) -> &[String] {
+		self.selects.get(custom_id).map_or(&[], Vec::as_slice)
+	}
+
+	fn collect(&mut self, component: &ModalInteractionComponent) {
+		fn ids<T: ToString>(values: &[T]) -> Vec<String> {
+			values.iter().map(ToString::to_string).collect()
+		}
+		let (custom_id, values) = match component {
+			ModalInteractionComponent::ActionRow(row) => {
+				for component in &row.components {
+					self.collect(component);
+				}
+				return;
+			}
+			ModalInteractionComponent::Label(label) => {
+				self.collect(&label.component);
+				return;
+			}
+			ModalInteractionComponent::TextInput(input) => {
+				self.text_inputs
+					.insert(input.custom_id.clone(), input.value.clone());
+				return;
+			}
+			ModalInteractionComponent::StringSelect(select) => {
+				(&select.custom_id, select.values.clone())
+			}
+			ModalInteractionComponent::UserSelect(select) => {
+				(&select.custom_id, ids(&select.values))
+			}
+			ModalInteractionComponent::RoleSelect(select) => {
+				(&select.custom_id, ids(&select.values))
+			}
+			ModalInteractionComponent::MentionableSelect(select) => {
+				(&select.custom_id, ids(&select.values))
+			}
+			ModalInteractionComponent::ChannelSelect(select) => {
+				(&select.custom_id, ids(&select.values))
+			}
+			_ => return,
+		};
+		self.selects.insert(custom_id.clone(), values);
+	}
+}
+
 // ---------------------------------------------------------------------------
 // Rate-limit info parsed from response headers
 // ---------------------------------------------------------------------------
@@ -237,6 +530,14 @@ pub struct RateLimitInfo {
 mod tests {
 	use super::*;
 
+	#[test]
+	fn token_route_id_hides_token() {
+		let id = token_route_id("secret-token");
+		assert_eq!(id, token_route_id("secret-token"));
+		assert_ne!(id, token_route_id("other-token"));
+		assert!(!id.contains("secret"));
+	}
+
 	#[test]
 	fn json_body_serialises_value() {
 		let body = json_body(&serde_json::json!({"content": "hi"})).unwrap();
@@ -323,4 +624,118 @@ mod tests {
 		assert!(!json.contains("channel_id"));
 		assert!(!json.contains("guild_id"));
 	}
+
+	#[test]
+	fn create_poll_serialises_two_answers() {
+		let poll = CreatePoll::new("Lunch?")
+			.answer("Pizza")
+			.answer("Tacos")
+			.duration(4);
+		let json = serde_json::to_value(&poll).unwrap();
+		assert_eq!(json["question"]["text"], "Lunch?");
+		assert_eq!(json["answers"].as_array().unwrap().len(), 2);
+		assert_eq!(json["answers"][0]["poll_media"]["text"], "Pizza");
+		assert_eq!(json["answers"][1]["poll_media"]["text"], "Tacos");
+		assert_eq!(json["duration"], 4);
+		assert_eq!(json["allow_multiselect"], false);
+		assert!(json["question"].get("emoji").is_none());
+	}
+
+	#[test]
+	fn create_poll_requires_one_to_ten_answers() {
+		assert!(CreatePoll::new("Q").validate().is_err());
+		assert!(CreatePoll::new("Q").answer("A").validate().is_ok());
+		let ten = (0..10)
+			.fold(CreatePoll::new("Q"), |poll, i| poll.answer(i.to_string()));
+		assert!(ten.validate().is_ok());
+		assert!(ten.answer("eleven").validate().is_err());
+	}
+
+	#[test]
+	fn modal_builder_wraps_each_input_in_a_row() {
+		let response = ModalBuilder::new("m", "Title")
+			.text_input("a", "A", 1, true)
+			.text_input("b", "B", 2, false)
+			.build()
+			.unwrap();
+		let data = response.data.unwrap();
+		assert_eq!(data.custom_id.as_deref(), Some("m"));
+		assert_eq!(data.title.as_deref(), Some("Title"));
+		let rows = data.components.unwrap();
+		assert_eq!(rows.len(), 2);
+		for (row, id) in rows.iter().zip(["a", "b"]) {
+			match row {
+				Component::ActionRow(row) => {
+					assert_eq!(row.components.len(), 1);
+					match &row.components[0] {
+						Component::TextInput(input) => {
+							assert_eq!(input.custom_id, id)
+						}
+						other => panic!("expected TextInput, got {:?}", other),
+					}
+				}
+				other => panic!("expected ActionRow, got {:?}", other),
+			}
+		}
+	}
+
+	#[test]
+	fn modal_builder_rejects_six_inputs() {
+		let modal = (0..6).fold(ModalBuilder::new("m", "Title"), |m, i| {
+			m.text_input(i.to_string(), "Label", 1, true)
+		});
+		let err = modal.build().unwrap_err();
+		assert!(err.0.contains("got 6"));
+	}
+
+	#[test]
+	fn modal_builder_rejects_no_inputs() {
+		assert!(ModalBuilder::new("m", "Title").build().is_err());
+	}
+
+	#[test]
+	fn modal_values_reads_text_inputs_and_selects() {
+		let interaction: Interaction =
+			serde_json::from_value(serde_json::json!({
+				"id": "1",
+				"application_id": "2",
+				"type": 5,
+				"token": "tok",
+				"authorizing_integration_owners": {},
+				"entitlements": [],
+				"data": {
+					"custom_id": "report_modal",
+					"components": [
+						{
+							"type": 1,
+							"id": 1,
+							"components": [{
+								"type": 4,
+								"id": 2,
+								"custom_id": "report_subject",
+								"value": "Broken link",
+							}],
+						},
+						{
+							"type": 18,
+							"id": 3,
+							"component": {
+								"type": 3,
+								"id": 4,
+								"custom_id": "report_severity",
+								"values": ["high"],
+							},
+						},
+					],
+				},
+			}))
+			.expect("valid modal submit JSON");
+
+		let values = ModalValues::from_interaction(&interaction).unwrap();
+		assert_eq!(values.custom_id, "report_modal");
+		assert_eq!(values.text("report_subject"), Some("Broken link"));
+		assert_eq!(values.select("report_severity"), ["high"]);
+		assert!(values.select("missing").is_empty());
+		assert_eq!(values.text("report_severity"), None);
+	}
 }
@@ -1,3 +1,11 @@
+//! One-pass reads of the bot entity's state for observers.
+//!
+//! Bot state lives in components on the bot entity rather than resources,
+//! so an observer reads everything it needs, e.g. [`BotState`] and
+//! [`BotChannels`], through a single query while the event is handled.
+//! There are no separate awaits between the reads for another event to
+//! interleave with; slow work is handed to `queue_async` afterwards.
+//! Async code reads the same state in one pass with `read_states`.
 use crate::prelude::*;
 use beet::prelude::*;
 
@@ -47,3 +55,61 @@ impl MessageInfo {
 	}
 	pub fn is_bot(&self) -> bool { self.is_bot }
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_fixtures::*;
+	use twilight_model::channel::Message;
+	use twilight_model::id::Id;
+
+	/// Whether each observed message counted as direct, in order.
+	#[derive(Default, Component)]
+	struct DirectMessages(Vec<bool>);
+
+	fn record_direct(
+		ev: On<DiscordMessage>,
+		discord: DiscordQuery,
+		mut seen: Query<&mut DirectMessages>,
+	) -> Result {
+		let info = discord.message_info(&ev)?;
+		seen.get_mut(ev.event_target())?
+			.0
+			.push(info.is_direct_message());
+		Ok(())
+	}
+
+	
+	fn message(author_id: &str, channel_id: &str) -> Message {
+		let mut json = message_json("500", "hi");
+		json["guild_id"] = "1".into();
+		json["channel_id"] = channel_id.into();
+		json["author"]["id"] = author_id.into();
+		parse_message(json)
+	}
+
+	#[test]
+	fn message_info_reads_current_bot_state_and_channels() {
+		let mut world = World::new();
+		let mut channels = BotChannels::default();
+		channels.insert(Id::new(1), Id::new(10));
+		let mut bot = world.spawn((channels, DirectMessages::default()));
+		bot.observe(common_handlers::init_bot_state);
+		bot.trigger(DiscordReady::create(ready("42")));
+		bot.observe(record_direct);
+
+		bot.trigger(DiscordMessage::create(message("789", "10")));
+		bot.trigger(DiscordMessage::create(message("789", "11")));
+		// The bot's own messages in its channel aren't direct.
+		bot.trigger(DiscordMessage::create(message("42", "10")));
+		// Moving the bot channel is seen by the very next message.
+		bot.get_mut::<BotChannels>()
+			.unwrap()
+			.insert(Id::new(1), Id::new(11));
+		bot.trigger(DiscordMessage::create(message("789", "11")));
+
+		assert_eq!(bot.get::<DirectMessages>().unwrap().0, [
+			true, false, false, true
+		]);
+	}
+}
@@ -1,9 +1,21 @@
+use crate::tw_gateway::UnknownDispatch;
 use beet::prelude::*;
 use twilight_model::application::interaction::Interaction;
 use twilight_model::channel::message::Message;
+use twilight_model::gateway::event::GatewayEvent;
+use twilight_model::gateway::payload::incoming::AutoModerationActionExecution;
+use twilight_model::gateway::payload::incoming::BanAdd;
+use twilight_model::gateway::payload::incoming::BanRemove;
 use twilight_model::gateway::payload::incoming::GuildCreate;
+use twilight_model::gateway::payload::incoming::MemberUpdate;
 use twilight_model::gateway::payload::incoming::PresenceUpdate;
+use twilight_model::gateway::payload::incoming::ReactionAdd;
+use twilight_model::gateway::payload::incoming::ReactionRemove;
 use twilight_model::gateway::payload::incoming::Ready;
+use twilight_model::gateway::payload::incoming::RoleCreate;
+use twilight_model::gateway::payload::incoming::RoleDelete;
+use twilight_model::gateway::payload::incoming::RoleUpdate;
+use twilight_model::gateway::payload::incoming::TypingStart;
 
 
 /// The first dispatch message sent, often used to get the
@@ -104,3 +116,274 @@ impl std::ops::Deref for DiscordInteraction {
 	type Target = Interaction;
 	fn deref(&self) -> &Self::Target { &self.interaction }
 }
+
+
+/// Sent when a role is created in a guild.
+#[derive(Debug, Clone, EntityEvent)]
+pub struct DiscordRoleCreate {
+	entity: Entity,
+	pub role_create: RoleCreate,
+}
+
+impl DiscordRoleCreate {
+	pub fn create(role_create: RoleCreate) -> impl FnOnce(Entity) -> Self {
+		move |entity| Self {
+			entity,
+			role_create,
+		}
+	}
+}
+
+impl std::ops::Deref for DiscordRoleCreate {
+	type Target = RoleCreate;
+	fn deref(&self) -> &Self::Target { &self.role_create }
+}
+
+/// Sent when a role's name, color, permissions or position changes.
+/// Carries the full updated role.
+#[derive(Debug, Clone, EntityEvent)]
+pub struct DiscordRoleUpdate {
+	entity: Entity,
+	pub role_update: RoleUpdate,
+}
+
+impl DiscordRoleUpdate {
+	pub fn create(role_update: RoleUpdate) -> impl FnOnce(Entity) -> Self {
+		move |entity| Self {
+			entity,
+			role_update,
+		}
+	}
+}
+
+impl std::ops::Deref for DiscordRoleUpdate {
+	type Target = RoleUpdate;
+	fn deref(&self) -> &Self::Target { &self.role_update }
+}
+
+/// Sent when a role is deleted. Only the ids are included, so handlers
+/// needing the role's name must have seen it earlier.
+#[derive(Debug, Clone, EntityEvent)]
+pub struct DiscordRoleDelete {
+	entity: Entity,
+	pub role_delete: RoleDelete,
+}
+
+impl DiscordRoleDelete {
+	pub fn create(role_delete: RoleDelete) -> impl FnOnce(Entity) -> Self {
+		move |entity| Self {
+			entity,
+			role_delete,
+		}
+	}
+}
+
+impl std::ops::Deref for DiscordRoleDelete {
+	type Target = RoleDelete;
+	fn deref(&self) -> &Self::Target { &self.role_delete }
+}
+
+/// Sent when a user starts typing in a channel. `guild_id` and `member`
+/// are only set for guild channels.
+#[derive(Debug, Clone, EntityEvent)]
+pub struct DiscordTypingStart {
+	entity: Entity,
+	pub typing_start: TypingStart,
+}
+
+impl DiscordTypingStart {
+	pub fn create(typing_start: TypingStart) -> impl FnOnce(Entity) -> Self {
+		move |entity| Self {
+			entity,
+			typing_start,
+		}
+	}
+}
+
+impl std::ops::Deref for DiscordTypingStart {
+	type Target = TypingStart;
+	fn deref(&self) -> &Self::Target { &self.typing_start }
+}
+
+/// Sent when an auto-moderation rule acts on a message, e.g. blocking it or
+/// posting an alert. `content` and `matched_content` are empty without the
+/// `MESSAGE_CONTENT` intent.
+#[derive(Debug, Clone, EntityEvent)]
+pub struct DiscordAutoModerationActionExecution {
+	entity: Entity,
+	pub execution: AutoModerationActionExecution,
+}
+
+impl DiscordAutoModerationActionExecution {
+	pub fn create(
+		execution: AutoModerationActionExecution,
+	) -> impl FnOnce(Entity) -> Self {
+		move |entity| Self { entity, execution }
+	}
+}
+
+impl std::ops::Deref for DiscordAutoModerationActionExecution {
+	type Target = AutoModerationActionExecution;
+	fn deref(&self) -> &Self::Target { &self.execution }
+}
+
+/// Sent when a user reacts to a message. `member` is only set in guilds.
+#[derive(Debug, Clone, EntityEvent)]
+pub struct DiscordReactionAdd {
+	entity: Entity,
+	pub reaction: ReactionAdd,
+}
+
+impl DiscordReactionAdd {
+	pub fn create(reaction: ReactionAdd) -> impl FnOnce(Entity) -> Self {
+		move |entity| Self { entity, reaction }
+	}
+}
+
+impl std::ops::Deref for DiscordReactionAdd {
+	type Target = ReactionAdd;
+	fn deref(&self) -> &Self::Target { &self.reaction }
+}
+
+/// Sent when a user removes their reaction from a message.
+#[derive(Debug, Clone, EntityEvent)]
+pub struct DiscordReactionRemove {
+	entity: Entity,
+	pub reaction: ReactionRemove,
+}
+
+impl DiscordReactionRemove {
+	pub fn create(reaction: ReactionRemove) -> impl FnOnce(Entity) -> Self {
+		move |entity| Self { entity, reaction }
+	}
+}
+
+impl std::ops::Deref for DiscordReactionRemove {
+	type Target = ReactionRemove;
+	fn deref(&self) -> &Self::Target { &self.reaction }
+}
+
+/// Sent when a user is banned from a guild, by the bot or anyone else.
+#[derive(Debug, Clone, EntityEvent)]
+pub struct DiscordBanAdd {
+	entity: Entity,
+	pub ban: BanAdd,
+}
+
+impl DiscordBanAdd {
+	pub fn create(ban: BanAdd) -> impl FnOnce(Entity) -> Self {
+		move |entity| Self { entity, ban }
+	}
+}
+
+impl std::ops::Deref for DiscordBanAdd {
+	type Target = BanAdd;
+	fn deref(&self) -> &Self::Target { &self.ban }
+}
+
+/// Sent when a user is unbanned from a guild.
+#[derive(Debug, Clone, EntityEvent)]
+pub struct DiscordBanRemove {
+	entity: Entity,
+	pub ban: BanRemove,
+}
+
+impl DiscordBanRemove {
+	pub fn create(ban: BanRemove) -> impl FnOnce(Entity) -> Self {
+		move |entity| Self { entity, ban }
+	}
+}
+
+impl std::ops::Deref for DiscordBanRemove {
+	type Target = BanRemove;
+	fn deref(&self) -> &Self::Target { &self.ban }
+}
+
+/// Sent when a guild member's roles, nickname or other details change,
+/// including the bot's own.
+#[derive(Debug, Clone, EntityEvent)]
+pub struct DiscordMemberUpdate {
+	entity: Entity,
+	pub member: MemberUpdate,
+}
+
+impl DiscordMemberUpdate {
+	pub fn create(member: MemberUpdate) -> impl FnOnce(Entity) -> Self {
+		move |entity| Self { entity, member }
+	}
+}
+
+impl std::ops::Deref for DiscordMemberUpdate {
+	type Target = MemberUpdate;
+	fn deref(&self) -> &Self::Target { &self.member }
+}
+
+/// Sent for dispatch events twilight-model can't parse yet, carrying the
+/// raw event name and payload for forward-compatible handlers.
+///
+/// These arrive on a separate channel from typed events, so their order
+/// relative to typed events isn't guaranteed.
+///
+/// ```ignore
+/// fn on_unknown(ev: On<DiscordUnknownDispatch>) {
+///     if ev.event_name == "SOME_NEW_EVENT" {
+///         info!(data = ?ev.data, "new event");
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, EntityEvent)]
+pub struct DiscordUnknownDispatch {
+	entity: Entity,
+	pub unknown: UnknownDispatch,
+}
+
+impl DiscordUnknownDispatch {
+	pub fn create(unknown: UnknownDispatch) -> impl FnOnce(Entity) -> Self {
+		move |entity| Self { entity, unknown }
+	}
+}
+
+impl std::ops::Deref for DiscordUnknownDispatch {
+	type Target = UnknownDispatch;
+	fn deref(&self) -> &Self::Target { &self.unknown }
+}
+
+/// Sent for every event the gateway delivers, just before the typed event
+/// it maps to, e.g. [`DiscordMessage`]. Observe this to handle dispatches
+/// that have no typed event yet, or to see every event in one place.
+///
+/// Internal plumbing like heartbeats is included. Each event is a copy of
+/// the one the typed events are built from, so the gateway listener only
+/// sends these to bots with the [`RawGatewayEvents`] marker.
+///
+/// ```ignore
+/// commands.spawn((DiscordBot::default(), RawGatewayEvents)).observe(on_raw);
+///
+/// fn on_raw(ev: On<DiscordGatewayEvent>) {
+///     if let GatewayEvent::Dispatch(_, DispatchEvent::ChannelCreate(channel)) =
+///         &ev.event
+///     {
+///         info!(channel_id = %channel.id, "channel created");
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, EntityEvent)]
+pub struct DiscordGatewayEvent {
+	entity: Entity,
+	pub event: GatewayEvent,
+}
+
+impl DiscordGatewayEvent {
+	pub fn create(event: GatewayEvent) -> impl FnOnce(Entity) -> Self {
+		move |entity| Self { entity, event }
+	}
+}
+
+impl std::ops::Deref for DiscordGatewayEvent {
+	type Target = GatewayEvent;
+	fn deref(&self) -> &Self::Target { &self.event }
+}
+
+/// Opts a bot entity into [`DiscordGatewayEvent`]s.
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct RawGatewayEvents;
@@ -13,8 +13,13 @@
 use twilight_model::application::command::Command;
 use twilight_model::application::command::CommandOption;
 use twilight_model::application::command::CommandOptionChoice;
+use twilight_model::application::command::CommandOptionChoiceValue;
 use twilight_model::application::command::CommandOptionType;
+use twilight_model::application::command::CommandOptionValue;
 use twilight_model::application::command::CommandType;
+use twilight_model::application::interaction::Interaction;
+use twilight_model::application::interaction::InteractionContextType;
+use twilight_model::application::monetization::Entitlement;
 use twilight_model::channel::message::component::ActionRow;
 use twilight_model::channel::message::component::Button;
 use twilight_model::channel::message::component::ButtonStyle;
@@ -30,17 +35,93 @@ use twilight_model::channel::message::embed::EmbedField;
 use twilight_model::channel::message::embed::EmbedFooter;
 use twilight_model::channel::message::embed::EmbedImage;
 use twilight_model::channel::message::embed::EmbedThumbnail;
+use twilight_model::channel::ChannelType;
+use twilight_model::channel::permission_overwrite::PermissionOverwrite;
+use twilight_model::channel::permission_overwrite::PermissionOverwriteType;
 use twilight_model::channel::message::MessageFlags;
+use twilight_model::gateway::presence::Activity;
+use twilight_model::gateway::presence::ActivityType;
+use twilight_model::gateway::presence::Presence;
+use twilight_model::gateway::presence::Status;
+use twilight_model::guild::Emoji;
 use twilight_model::guild::Guild;
+use twilight_model::guild::Member;
+use twilight_model::guild::Permissions;
 use twilight_model::http::interaction::InteractionResponse;
 use twilight_model::http::interaction::InteractionResponseData;
 use twilight_model::http::interaction::InteractionResponseType;
 use twilight_model::id::marker::CommandMarker;
+use twilight_model::id::marker::SkuMarker;
+use twilight_model::id::marker::StickerMarker;
 use twilight_model::id::marker::UserMarker;
+use twilight_model::oauth::ApplicationIntegrationType;
 use twilight_model::id::Id;
 use twilight_model::user::CurrentUser;
 use twilight_model::user::User;
 use twilight_model::util::ImageHash;
+use twilight_model::util::Timestamp;
+use twilight_model::util::datetime::TimestampParseError;
+
+use super::JsonError;
+use std::borrow::Cow;
+
+// ===========================================================================
+// CDN images
+// ===========================================================================
+
+/// Formats the Discord CDN serves static images in. Animated images are
+/// always served as GIF.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CdnImageFormat {
+	#[default]
+	Png,
+	Jpeg,
+	Webp,
+}
+
+impl CdnImageFormat {
+	/// The file extension used in CDN URLs, e.g. `"png"`.
+	pub fn extension(self) -> &'static str {
+		match self {
+			CdnImageFormat::Png => "png",
+			CdnImageFormat::Jpeg => "jpg",
+			CdnImageFormat::Webp => "webp",
+		}
+	}
+}
+
+/// Whether the CDN accepts `size` as an image size: a power of two from 16
+/// to 4096.
+pub fn is_valid_image_size(size: u16) -> bool {
+	size.is_power_of_two() && (16..=4096).contains(&size)
+}
+
+/// CDN URL for a user's avatar `hash` at `size` pixels.
+///
+/// Animated avatars (hash prefixed `a_`) are linked as `.gif`, everything
+/// else in `format`. Errors if `size` isn't [valid](is_valid_image_size).
+pub fn user_avatar_url(
+	user_id: Id<UserMarker>,
+	hash: &ImageHash,
+	size: u16,
+	format: CdnImageFormat,
+) -> Result<String, JsonError> {
+	if !is_valid_image_size(size) {
+		return Err(JsonError(format!(
+			"image size must be a power of two from 16 to 4096, got {}",
+			size
+		)));
+	}
+	let ext = if hash.is_animated() {
+		"gif"
+	} else {
+		format.extension()
+	};
+	Ok(format!(
+		"https://cdn.discordapp.com/avatars/{}/{}.{}?size={}",
+		user_id, hash, ext, size
+	))
+}
 
 // ===========================================================================
 // CurrentUserExt  (the bot's own user object, returned in READY)
@@ -82,6 +163,33 @@ impl User {
 		))
 	}
 
+	/// Returns the CDN URL for the user's avatar at `size` pixels, or
+	/// `None` if no avatar is set. See [`user_avatar_url`].
+	fn avatar_url_sized(
+		&self,
+		size: u16,
+		format: CdnImageFormat,
+	) -> Result<Option<String>, JsonError> {
+		self.avatar
+			.as_ref()
+			.map(|hash| user_avatar_url(self.id, hash, size, format))
+			.transpose()
+	}
+
+	/// Returns the CDN URL for the user's profile banner, or `None` if unset.
+	///
+	/// Animated banners (hash prefixed `a_`) are linked as `.gif`. Banners
+	/// are only included when the user was fetched directly, e.g. with
+	/// [`GetUser`](crate::prelude::GetUser), not in gateway payloads.
+	fn banner_url(&self) -> Option<String> {
+		let hash: &ImageHash = self.banner.as_ref()?;
+		let ext = if hash.is_animated() { "gif" } else { "png" };
+		Some(format!(
+			"https://cdn.discordapp.com/banners/{}/{}.{}",
+			self.id, hash, ext
+		))
+	}
+
 	/// `Username#Discriminator`, or just `Username` for the new username system.
 	fn tag(&self) -> String {
 		if self.discriminator == 0 {
@@ -96,19 +204,55 @@ impl User {
 // MessageExt
 // ===========================================================================
 
+/// Unix-millisecond creation time of any snowflake id.
+pub fn snowflake_timestamp_ms<T>(id: Id<T>) -> u64 {
+	// Right-shift by 22 to extract the timestamp portion, then add Discord epoch.
+	(id.get() >> 22) + 1_420_070_400_000
+}
+
 #[extend::ext(pub, name = MessageExt)]
 impl twilight_model::channel::message::Message {
 	/// Unix-millisecond timestamp derived from the message snowflake.
 	fn snowflake_timestamp_ms(&self) -> Option<u64> {
-		let sf = self.id.get();
-		// Right-shift by 22 to extract the timestamp portion, then add Discord epoch.
-		Some((sf >> 22) + 1_420_070_400_000)
+		Some(snowflake_timestamp_ms(self.id))
 	}
 
 	/// Whether a given user ID is mentioned in the message.
 	fn mentions_user(&self, user_id: Id<UserMarker>) -> bool {
 		self.mentions.iter().any(|m| m.id == user_id)
 	}
+
+	/// Content of the message this one replies to.
+	///
+	/// `None` both for non-replies and for replies whose original Discord
+	/// didn't resolve (usually because it was deleted); use
+	/// [`is_unresolved_reply`](MessageExt::is_unresolved_reply) to tell
+	/// them apart.
+	fn replied_content(&self) -> Option<&str> {
+		self.referenced_message
+			.as_deref()
+			.map(|message| message.content.as_str())
+	}
+
+	/// Whether this is a reply whose original message wasn't included.
+	fn is_unresolved_reply(&self) -> bool {
+		self.message_reference.is_some() && self.referenced_message.is_none()
+	}
+
+	/// IDs of the stickers sent with the message, in order. The full
+	/// entries, with name and format, are in `sticker_items`.
+	fn sticker_ids(&self) -> Vec<Id<StickerMarker>> {
+		self.sticker_items
+			.iter()
+			.map(|sticker| sticker.id)
+			.collect()
+	}
+
+	/// Whether the message was posted by a bot or through a webhook, which
+	/// command handling ignores. Webhook authors aren't flagged as bots.
+	fn is_automated(&self) -> bool {
+		self.author.bot || self.webhook_id.is_some()
+	}
 }
 
 // ===========================================================================
@@ -119,8 +263,238 @@ impl twilight_model::channel::message::Message {
 impl Guild {
 	/// Unix-millisecond timestamp derived from the guild snowflake.
 	fn created_at_ms(&self) -> Option<u64> {
-		let sf = self.id.get();
-		Some((sf >> 22) + 1_420_070_400_000)
+		Some(snowflake_timestamp_ms(self.id))
+	}
+
+	/// Whether the guild has `feature`, e.g. `"COMMUNITY"` or
+	/// `"VANITY_URL"`.
+	fn has_feature(&self, feature: &str) -> bool {
+		self.features
+			.iter()
+			.any(|f| Cow::from(f.clone()) == feature)
+	}
+
+	/// The guild's features as Discord names them, e.g. `"COMMUNITY"`.
+	fn feature_names(&self) -> Vec<String> {
+		self.features
+			.iter()
+			.map(|f| Cow::from(f.clone()).into_owned())
+			.collect()
+	}
+
+	/// Returns the CDN URL for the guild's icon, or `None` if unset.
+	///
+	/// Animated icons (hash prefixed `a_`) are linked as `.gif`.
+	fn icon_url(&self) -> Option<String> {
+		let hash: &ImageHash = self.icon.as_ref()?;
+		let ext = if hash.is_animated() { "gif" } else { "png" };
+		Some(format!(
+			"https://cdn.discordapp.com/icons/{}/{}.{}",
+			self.id, hash, ext
+		))
+	}
+
+	/// The guild's `discord.gg` vanity invite, if it has the `VANITY_URL`
+	/// feature and a code set.
+	fn vanity_url(&self) -> Option<String> {
+		if !self.has_feature("VANITY_URL") {
+			return None;
+		}
+		let code = self.vanity_url_code.as_ref()?;
+		Some(format!("https://discord.gg/{}", code))
+	}
+
+	/// `member`'s permissions in a channel with `overwrites`, following
+	/// Discord's order: role permissions, then the `@everyone`, role and
+	/// member overwrites. The owner and administrators get everything.
+	///
+	/// Threads use their parent channel's overwrites, and timeouts are not
+	/// taken into account.
+	fn channel_permissions(
+		&self,
+		member: &Member,
+		overwrites: &[PermissionOverwrite],
+	) -> Permissions {
+		if member.user.id == self.owner_id {
+			return Permissions::all();
+		}
+		// The @everyone role shares the guild's id.
+		let mut permissions = self
+			.roles
+			.iter()
+			.filter(|role| {
+				role.id.get() == self.id.get()
+					|| member.roles.contains(&role.id)
+			})
+			.fold(Permissions::empty(), |acc, role| acc | role.permissions);
+		if permissions.contains(Permissions::ADMINISTRATOR) {
+			return Permissions::all();
+		}
+
+		let apply = |permissions: Permissions, allow, deny| {
+			(permissions - deny) | allow
+		};
+		if let Some(everyone) =
+			overwrites.iter().find(|o| o.id.get() == self.id.get())
+		{
+			permissions = apply(permissions, everyone.allow, everyone.deny);
+		}
+		let (allow, deny) = overwrites
+			.iter()
+			.filter(|o| {
+				o.kind == PermissionOverwriteType::Role
+					&& member.roles.iter().any(|id| id.get() == o.id.get())
+			})
+			.fold((Permissions::empty(), Permissions::empty()), |acc, o| {
+				(acc.0 | o.allow, acc.1 | o.deny)
+			});
+		permissions = apply(permissions, allow, deny);
+		if let Some(own) = overwrites.iter().find(|o| {
+			o.kind == PermissionOverwriteType::Member
+				&& o.id.get() == member.user.id.get()
+		}) {
+			permissions = apply(permissions, own.allow, own.deny);
+		}
+		permissions
+	}
+}
+
+// ===========================================================================
+// Permissions
+// ===========================================================================
+
+/// `permissions` as a readable list, e.g. `"View Channel, Send Messages"`,
+/// or `"None"` when empty.
+pub fn format_permissions(permissions: Permissions) -> String {
+	let names: Vec<String> = permissions
+		.iter_names()
+		.map(|(name, _)| {
+			name.split('_')
+				.map(|word| {
+					let mut chars = word.chars();
+					let first = chars.next().unwrap_or_default();
+					format!("{}{}", first, chars.as_str().to_lowercase())
+				})
+				.collect::<Vec<_>>()
+				.join(" ")
+		})
+		.collect();
+	if names.is_empty() {
+		"None".to_string()
+	} else {
+		names.join(", ")
+	}
+}
+
+// ===========================================================================
+// ChannelTypeExt
+// ===========================================================================
+
+/// Channel classifiers to go with twilight's inherent
+/// [`ChannelType::is_thread`] and [`ChannelType::is_guild`].
+#[extend::ext(pub, name = ChannelTypeExt)]
+impl ChannelType {
+	/// Voice or stage channels.
+	fn is_voice(&self) -> bool {
+		matches!(self, ChannelType::GuildVoice | ChannelType::GuildStageVoice)
+	}
+
+	/// Channels whose main purpose is a message feed: text and announcement
+	/// channels, DMs and group DMs. Threads, forums and voice channels are
+	/// excluded.
+	fn is_text_like(&self) -> bool {
+		matches!(
+			self,
+			ChannelType::GuildText
+				| ChannelType::GuildAnnouncement
+				| ChannelType::Private
+				| ChannelType::Group
+		)
+	}
+
+	/// Categories that group other channels.
+	fn is_category(&self) -> bool { matches!(self, ChannelType::GuildCategory) }
+}
+
+// ===========================================================================
+// MemberExt
+// ===========================================================================
+
+#[extend::ext(pub, name = MemberExt)]
+impl Member {
+	/// Whether the member is currently boosting the guild.
+	///
+	/// Discord sets `premium_since` while a boost is active and clears it
+	/// when the boost ends.
+	fn is_booster(&self) -> bool { self.premium_since.is_some() }
+}
+
+// ===========================================================================
+// EmojiExt
+// ===========================================================================
+
+#[extend::ext(pub, name = EmojiExt)]
+impl Emoji {
+	/// CDN URL of the emoji image, `.gif` for animated emoji.
+	fn url(&self) -> String {
+		let ext = if self.animated { "gif" } else { "png" };
+		format!("https://cdn.discordapp.com/emojis/{}.{}", self.id, ext)
+	}
+
+	/// Markdown that renders the emoji, e.g. `<:blobcat:400>`.
+	fn mention(&self) -> String {
+		let prefix = if self.animated { "a" } else { "" };
+		format!("<{}:{}:{}>", prefix, self.name, self.id)
+	}
+}
+
+// ===========================================================================
+// PresenceExt
+// ===========================================================================
+
+#[extend::ext(pub, name = PresenceExt)]
+impl Presence {
+	/// The first game-like activity (playing or streaming), skipping custom
+	/// statuses, listening, watching, etc.
+	///
+	/// Also callable on `PresenceUpdate` via `Deref`.
+	fn primary_activity(&self) -> Option<&Activity> {
+		self.activities.iter().find(|a| {
+			matches!(a.kind, ActivityType::Playing | ActivityType::Streaming)
+		})
+	}
+}
+
+// ===========================================================================
+// StatusExt
+// ===========================================================================
+
+#[extend::ext(pub, name = StatusExt)]
+impl Status {
+	/// The wire name of the status, e.g. `"dnd"`.
+	fn as_str(&self) -> &'static str {
+		match self {
+			Status::DoNotDisturb => "dnd",
+			Status::Idle => "idle",
+			Status::Invisible => "invisible",
+			Status::Offline => "offline",
+			Status::Online => "online",
+		}
+	}
+}
+
+/// Parse a raw presence status string, treating anything unrecognised as
+/// [`Status::Offline`].
+///
+/// Gateway payloads deserialize straight into [`Status`]; this is for
+/// statuses that arrive as plain strings (config, commands, logs).
+pub fn parse_status(status: &str) -> Status {
+	match status {
+		"dnd" => Status::DoNotDisturb,
+		"idle" => Status::Idle,
+		"invisible" => Status::Invisible,
+		"online" => Status::Online,
+		_ => Status::Offline,
 	}
 }
 
@@ -137,6 +511,48 @@ impl<T> Id<T> {
 	fn value(&self) -> u64 { self.get() }
 }
 
+/// Parse a string snowflake into a typed [`Id`].
+///
+/// Returns `None` for empty, non-numeric, or zero ids. This is the bridge
+/// for code still carrying ids as plain strings; `Id<T>` also implements
+/// `FromStr` if the parse error is needed.
+pub fn to_typed<T>(s: &str) -> Option<Id<T>> {
+	s.trim().parse::<u64>().ok().and_then(Id::new_checked)
+}
+
+/// Render a typed [`Id`] as a plain string snowflake, the inverse of
+/// [`to_typed`].
+pub fn to_string_id<T>(id: Id<T>) -> String { id.get().to_string() }
+
+// ===========================================================================
+// TimestampExt
+// ===========================================================================
+
+#[extend::ext(pub, name = TimestampExt)]
+impl Timestamp {
+	/// The current UTC time.
+	fn now() -> Self {
+		Self::from_chrono(chrono::Utc::now())
+			.expect("the current time is within the supported range")
+	}
+
+	/// Convert a chrono UTC datetime, keeping microsecond precision.
+	///
+	/// Errors for dates outside the years Discord timestamps support.
+	fn from_chrono(
+		datetime: chrono::DateTime<chrono::Utc>,
+	) -> Result<Self, TimestampParseError> {
+		Self::from_micros(datetime.timestamp_micros())
+	}
+
+	/// This timestamp moved `seconds` forward, or back if negative, e.g.
+	/// when a reminder or timeout expires.
+	fn add_seconds(self, seconds: i64) -> Result<Self, TimestampParseError> {
+		let micros = seconds.saturating_mul(1_000_000);
+		Self::from_micros(self.as_micros().saturating_add(micros))
+	}
+}
+
 // ===========================================================================
 // CommandExt — replaces ApplicationCommandBuilder
 // ===========================================================================
@@ -254,12 +670,240 @@ impl Command {
 		self
 	}
 
+	/// Add an INTEGER or NUMBER option whose value Discord restricts to
+	/// `min..=max` in the client.
+	///
+	/// Ranges on other option types are caught by
+	/// [`validate`](CommandExt::validate).
+	fn with_ranged_option(
+		mut self,
+		kind: CommandOptionType,
+		name: impl Into<String>,
+		description: impl Into<String>,
+		required: bool,
+		min: CommandOptionValue,
+		max: CommandOptionValue,
+	) -> Self {
+		self.options.push(ranged_command_option(
+			kind,
+			name,
+			description,
+			required,
+			min,
+			max,
+		));
+		self
+	}
+
+	/// Add a SUB_COMMAND with its own `options`.
+	fn with_subcommand(
+		mut self,
+		name: impl Into<String>,
+		description: impl Into<String>,
+		options: Vec<CommandOption>,
+	) -> Self {
+		self.options.push(CommandOption {
+			autocomplete: None,
+			channel_types: None,
+			choices: None,
+			description: description.into(),
+			description_localizations: None,
+			kind: CommandOptionType::SubCommand,
+			max_length: None,
+			max_value: None,
+			min_length: None,
+			min_value: None,
+			name: name.into(),
+			name_localizations: None,
+			options: Some(options),
+			required: None,
+		});
+		self
+	}
+
 	/// Mark the command as NSFW.
 	#[allow(dead_code)]
 	fn with_nsfw(mut self, nsfw: bool) -> Self {
 		self.nsfw = Some(nsfw);
 		self
 	}
+
+	/// Restrict where the command can be used (guilds, the bot's DM,
+	/// private channels). An empty set leaves the Discord default in place,
+	/// since Discord rejects an empty `contexts` array.
+	fn with_contexts(
+		mut self,
+		contexts: impl IntoIterator<Item = InteractionContextType>,
+	) -> Self {
+		let contexts: Vec<_> = contexts.into_iter().collect();
+		self.contexts = (!contexts.is_empty()).then_some(contexts);
+		self
+	}
+
+	/// Set the installation types the command is available for (guild
+	/// install, user install). An empty set leaves the Discord default.
+	fn with_integration_types(
+		mut self,
+		integration_types: impl IntoIterator<Item = ApplicationIntegrationType>,
+	) -> Self {
+		let integration_types: Vec<_> = integration_types.into_iter().collect();
+		self.integration_types =
+			(!integration_types.is_empty()).then_some(integration_types);
+		self
+	}
+
+	/// Check the command definition before registering it.
+	///
+	/// Discord rejects inconsistent definitions with an opaque 400, and a
+	/// handler reading an option as the wrong type silently gets `None`, so
+	/// this catches choices whose values don't match their option type,
+	/// e.g. string choices on an INTEGER option, `min_value`/`max_value`
+	/// on options that aren't numeric, including in subcommands, and
+	/// subcommands mixed with plain options or nested where Discord doesn't
+	/// allow them.
+	fn validate(&self) -> Result<(), JsonError> {
+		validate_options(&self.name, &self.options)
+	}
+}
+
+fn validate_options(
+	parent: &str,
+	options: &[CommandOption],
+) -> Result<(), JsonError> {
+	validate_structure(parent, options)?;
+	for option in options {
+		let path = format!("{}.{}", parent, option.name);
+		for choice in option.choices.iter().flatten() {
+			let matches = match (option.kind, &choice.value) {
+				(
+					CommandOptionType::String,
+					CommandOptionChoiceValue::String(_),
+				) => true,
+				(
+					CommandOptionType::Integer,
+					CommandOptionChoiceValue::Integer(_),
+				) => true,
+				// Whole numbers are valid NUMBER choices.
+				(
+					CommandOptionType::Number,
+					CommandOptionChoiceValue::Number(_)
+					| CommandOptionChoiceValue::Integer(_),
+				) => true,
+				_ => false,
+			};
+			if !matches {
+				return Err(JsonError(format!(
+					"option `{}` is {:?} but choice `{}` has a {:?} value",
+					path, option.kind, choice.name, choice.value
+				)));
+			}
+		}
+		validate_range(&path, option)?;
+		if let Some(nested) = &option.options {
+			validate_options(&path, nested)?;
+		}
+	}
+	Ok(())
+}
+
+/// Subcommands and groups can't share a level with plain options, a
+/// SUB_COMMAND_GROUP may only contain SUB_COMMANDs, and a SUB_COMMAND can't
+/// nest either.
+fn validate_structure(
+	parent: &str,
+	options: &[CommandOption],
+) -> Result<(), JsonError> {
+	let is_subcommand = |option: &CommandOption| {
+		matches!(
+			option.kind,
+			CommandOptionType::SubCommand | CommandOptionType::SubCommandGroup
+		)
+	};
+	let plain = options.iter().find(|option| !is_subcommand(option));
+	let sub = options.iter().find(|option| is_subcommand(option));
+	if let (Some(plain), Some(sub)) = (plain, sub) {
+		return Err(JsonError(format!(
+			"`{}` mixes {:?} `{}` with {:?} option `{}`",
+			parent, sub.kind, sub.name, plain.kind, plain.name
+		)));
+	}
+	for option in options {
+		let mut nested = option.options.iter().flatten();
+		let invalid = match option.kind {
+			CommandOptionType::SubCommandGroup => {
+				nested.find(|child| child.kind != CommandOptionType::SubCommand)
+			}
+			CommandOptionType::SubCommand => {
+				nested.find(|child| is_subcommand(child))
+			}
+			_ => None,
+		};
+		if let Some(child) = invalid {
+			return Err(JsonError(format!(
+				"`{}.{}` is {:?} and can't contain {:?} `{}`",
+				parent, option.name, option.kind, child.kind, child.name
+			)));
+		}
+	}
+	Ok(())
+}
+
+/// `min_value`/`max_value` only apply to INTEGER and NUMBER options, and
+/// the minimum can't exceed the maximum.
+fn validate_range(path: &str, option: &CommandOption) -> Result<(), JsonError> {
+	if option.min_value.is_none() && option.max_value.is_none() {
+		return Ok(());
+	}
+	if !matches!(
+		option.kind,
+		CommandOptionType::Integer | CommandOptionType::Number
+	) {
+		return Err(JsonError(format!(
+			"option `{}` is {:?} and can't have min_value/max_value",
+			path, option.kind
+		)));
+	}
+	let as_f64 = |value: &CommandOptionValue| match value {
+		CommandOptionValue::Integer(v) => *v as f64,
+		CommandOptionValue::Number(v) => *v,
+	};
+	match (&option.min_value, &option.max_value) {
+		(Some(min), Some(max)) if as_f64(min) > as_f64(max) => {
+			Err(JsonError(format!(
+				"option `{}` has min_value {:?} above max_value {:?}",
+				path, min, max
+			)))
+		}
+		_ => Ok(()),
+	}
+}
+
+/// Convenience: build an INTEGER or NUMBER [`CommandOption`] restricted to
+/// `min..=max`, e.g. for a subcommand's options.
+pub fn ranged_command_option(
+	kind: CommandOptionType,
+	name: impl Into<String>,
+	description: impl Into<String>,
+	required: bool,
+	min: CommandOptionValue,
+	max: CommandOptionValue,
+) -> CommandOption {
+	CommandOption {
+		autocomplete: None,
+		channel_types: None,
+		choices: None,
+		description: description.into(),
+		description_localizations: None,
+		kind,
+		max_length: None,
+		max_value: Some(max),
+		min_length: None,
+		min_value: Some(min),
+		name: name.into(),
+		name_localizations: None,
+		options: None,
+		required: Some(required),
+	}
 }
 
 /// Convenience: build a [`CommandOption`] with choices.
@@ -327,6 +971,7 @@ impl Embed {
 	}
 
 	/// Set the embed color (as a 24-bit RGB integer, e.g. `0xFF6600`).
+	/// See [`colors`](super::colors) for the named palette.
 	fn with_color(mut self, color: u32) -> Self {
 		self.color = Some(color);
 		self
@@ -365,8 +1010,10 @@ impl Embed {
 		text: impl Into<String>,
 		icon_url: impl Into<String>,
 	) -> Self {
+		let icon_url = icon_url.into();
+		warn_invalid_embed_url("footer icon", &icon_url);
 		self.footer = Some(EmbedFooter {
-			icon_url: Some(icon_url.into()),
+			icon_url: Some(icon_url),
 			proxy_icon_url: None,
 			text: text.into(),
 		});
@@ -376,10 +1023,12 @@ impl Embed {
 	/// Set the thumbnail URL.
 	#[allow(dead_code)]
 	fn with_thumbnail(mut self, url: impl Into<String>) -> Self {
+		let url = url.into();
+		warn_invalid_embed_url("thumbnail", &url);
 		self.thumbnail = Some(EmbedThumbnail {
 			height: None,
 			proxy_url: None,
-			url: url.into(),
+			url,
 			width: None,
 		});
 		self
@@ -388,10 +1037,12 @@ impl Embed {
 	/// Set the image URL.
 	#[allow(dead_code)]
 	fn with_image(mut self, url: impl Into<String>) -> Self {
+		let url = url.into();
+		warn_invalid_embed_url("image", &url);
 		self.image = Some(EmbedImage {
 			height: None,
 			proxy_url: None,
-			url: url.into(),
+			url,
 			width: None,
 		});
 		self
@@ -425,6 +1076,123 @@ impl Embed {
 		self.url = Some(url.into());
 		self
 	}
+
+	/// Check that every image URL (image, thumbnail, author and footer
+	/// icons) is `http(s)://` or `attachment://`.
+	///
+	/// Discord silently drops anything else, e.g. `file://` or relative
+	/// paths, leaving a broken embed. The setters only log a warning, so
+	/// call this to turn it into an error.
+	fn validate(&self) -> Result<(), JsonError> {
+		let urls = [
+			("image", self.image.as_ref().map(|i| i.url.as_str())),
+			("thumbnail", self.thumbnail.as_ref().map(|t| t.url.as_str())),
+			(
+				"author icon",
+				self.author.as_ref().and_then(|a| a.icon_url.as_deref()),
+			),
+			(
+				"footer icon",
+				self.footer.as_ref().and_then(|f| f.icon_url.as_deref()),
+			),
+		];
+		for (field, url) in urls {
+			if let Some(url) = url.filter(|url| !is_embed_url(url)) {
+				return Err(JsonError(format!(
+					"embed {} URL must be http(s):// or attachment://, got `{}`",
+					field, url
+				)));
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Whether Discord can render `url` as an embed image.
+fn is_embed_url(url: &str) -> bool {
+	["http://", "https://", "attachment://"]
+		.iter()
+		.any(|scheme| url.starts_with(scheme))
+}
+
+fn warn_invalid_embed_url(field: &str, url: &str) {
+	if !is_embed_url(url) {
+		tracing::warn!(
+			field,
+			url,
+			"embed URL must be http(s):// or attachment://, Discord will drop it"
+		);
+	}
+}
+
+/// Most characters Discord accepts in an embed title.
+pub const EMBED_TITLE_MAX: usize = 256;
+/// Most characters Discord accepts in an embed description.
+pub const EMBED_DESCRIPTION_MAX: usize = 4096;
+
+/// Shorten `s` to at most `max` chars without breaking its markdown.
+///
+/// Cuts at the last word boundary where there is one near the limit and
+/// appends "…". If the cut falls inside a code block the fence is closed,
+/// so the rest of the message doesn't render as code.
+pub fn truncate_markdown_safe(s: &str, max: usize) -> String {
+	const ELLIPSIS: &str = "…";
+	const CLOSE_FENCE: &str = "\n```";
+	if s.chars().count() <= max {
+		return s.to_string();
+	}
+	let mut cut = truncate_at_word(s, max.saturating_sub(1));
+	let open_fence = cut.matches("```").count() % 2 == 1;
+	if open_fence {
+		// Make room for the closing fence.
+		cut = truncate_at_word(s, max.saturating_sub(1 + CLOSE_FENCE.len()));
+	}
+	let mut out = format!("{}{}", cut.trim_end(), ELLIPSIS);
+	if cut.matches("```").count() % 2 == 1 {
+		out.push_str(CLOSE_FENCE);
+	}
+	out
+}
+
+/// The first `max` chars of `s`, backing up to the last whitespace if
+/// that keeps at least half of them.
+fn truncate_at_word(s: &str, max: usize) -> &str {
+	let end = s.char_indices().nth(max).map_or(s.len(), |(i, _)| i);
+	let prefix = &s[..end];
+	if end == s.len() || s[end..].starts_with(char::is_whitespace) {
+		return prefix;
+	}
+	match prefix.rfind(char::is_whitespace) {
+		Some(i) if i >= end / 2 => &prefix[..i],
+		_ => prefix,
+	}
+}
+
+// ===========================================================================
+// Entitlements
+// ===========================================================================
+
+#[extend::ext(pub, name = EntitlementExt)]
+impl Entitlement {
+	/// Whether the entitlement grants access at `now`: not deleted and
+	/// within its start and end dates, which test entitlements don't set.
+	fn is_active_at(&self, now: Timestamp) -> bool {
+		!self.deleted
+			&& self.starts_at.is_none_or(|starts| starts <= now)
+			&& self.ends_at.is_none_or(|ends| now < ends)
+	}
+}
+
+#[extend::ext(pub, name = InteractionExt)]
+impl Interaction {
+	/// Whether the invoking user or guild currently has an entitlement to
+	/// `sku_id`, for gating premium commands.
+	fn has_entitlement(&self, sku_id: Id<SkuMarker>) -> bool {
+		let now = Timestamp::now();
+		self.entitlements
+			.iter()
+			.any(|e| e.sku_id == sku_id && e.is_active_at(now))
+	}
 }
 
 // ===========================================================================
@@ -634,6 +1402,29 @@ pub fn text_input(
 	})
 }
 
+// ===========================================================================
+// ComponentExt
+// ===========================================================================
+
+#[extend::ext(pub, name = ComponentExt)]
+impl Component {
+	/// Set the numeric component `id` used by components v2 to address a
+	/// specific component, e.g. when editing a message.
+	///
+	/// Applies to action rows, buttons, select menus, and text inputs; other
+	/// component kinds are returned unchanged.
+	fn with_component_id(mut self, component_id: i32) -> Self {
+		match &mut self {
+			Component::ActionRow(row) => row.id = Some(component_id),
+			Component::Button(button) => button.id = Some(component_id),
+			Component::SelectMenu(select) => select.id = Some(component_id),
+			Component::TextInput(input) => input.id = Some(component_id),
+			_ => {}
+		}
+		self
+	}
+}
+
 // ===========================================================================
 // Tests
 // ===========================================================================
@@ -641,6 +1432,10 @@ pub fn text_input(
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::test_fixtures::*;
+	use twilight_model::application::monetization::EntitlementType;
+	use twilight_model::channel::message::sticker::StickerFormatType;
+	use twilight_model::guild::Role;
 	use twilight_model::id::marker::GuildMarker;
 	use twilight_model::id::marker::MessageMarker;
 
@@ -702,6 +1497,200 @@ mod tests {
 		assert!(url.starts_with("https://cdn.discordapp.com/avatars/"));
 	}
 
+	#[test]
+	fn user_avatar_url_sized_static_and_animated() {
+		let user = |avatar: &str| -> User {
+			serde_json::from_value(serde_json::json!({
+				"id": "789",
+				"username": "alice",
+				"discriminator": "0",
+				"avatar": avatar,
+			}))
+			.unwrap()
+		};
+
+		let hash = "1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d";
+		assert_eq!(
+			user(hash)
+				.avatar_url_sized(4096, CdnImageFormat::Png)
+				.unwrap(),
+			Some(format!(
+				"https://cdn.discordapp.com/avatars/789/{}.png?size=4096",
+				hash
+			))
+		);
+		assert_eq!(
+			user(hash)
+				.avatar_url_sized(64, CdnImageFormat::Webp)
+				.unwrap(),
+			Some(format!(
+				"https://cdn.discordapp.com/avatars/789/{}.webp?size=64",
+				hash
+			))
+		);
+		// Animated avatars ignore the static format.
+		let animated = format!("a_{}", hash);
+		assert_eq!(
+			user(&animated)
+				.avatar_url_sized(1024, CdnImageFormat::Jpeg)
+				.unwrap(),
+			Some(format!(
+				"https://cdn.discordapp.com/avatars/789/{}.gif?size=1024",
+				animated
+			))
+		);
+		assert_eq!(
+			make_test_user()
+				.avatar_url_sized(128, CdnImageFormat::Png)
+				.unwrap(),
+			None
+		);
+	}
+
+	#[test]
+	fn image_size_validation() {
+		for size in [16, 32, 64, 128, 256, 512, 1024, 2048, 4096] {
+			assert!(is_valid_image_size(size), "{}", size);
+		}
+		for size in [0, 8, 100, 8192] {
+			assert!(!is_valid_image_size(size), "{}", size);
+		}
+		let hash: ImageHash =
+			"1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d".parse().unwrap();
+		assert!(
+			user_avatar_url(Id::new(1), &hash, 100, CdnImageFormat::Png)
+				.is_err()
+		);
+	}
+
+	#[test]
+	fn user_banner_url_static_and_animated() {
+		let user = |banner: &str| -> User {
+			serde_json::from_value(serde_json::json!({
+				"id": "789",
+				"username": "alice",
+				"discriminator": "0",
+				"avatar": null,
+				"banner": banner,
+			}))
+			.unwrap()
+		};
+
+		let hash = "1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d";
+		assert_eq!(
+			user(hash).banner_url().unwrap(),
+			format!("https://cdn.discordapp.com/banners/789/{}.png", hash)
+		);
+		let animated = format!("a_{}", hash);
+		assert_eq!(
+			user(&animated).banner_url().unwrap(),
+			format!("https://cdn.discordapp.com/banners/789/{}.gif", animated)
+		);
+		assert!(make_test_user().banner_url().is_none());
+	}
+
+	fn make_guild(features: &[&str], vanity_url_code: Option<&str>) -> Guild {
+		serde_json::from_value(serde_json::json!({
+			"id": "123",
+			"name": "Test Server",
+			"icon": null,
+			"owner_id": "456",
+			"channels": [],
+			"members": [],
+			"roles": [],
+			"emojis": [],
+			"features": features,
+			"vanity_url_code": vanity_url_code,
+			"afk_timeout": 300,
+			"preferred_locale": "en-US",
+			"premium_progress_bar_enabled": false,
+			"verification_level": 0,
+			"default_message_notifications": 0,
+			"explicit_content_filter": 0,
+			"mfa_level": 0,
+			"premium_tier": 0,
+			"nsfw_level": 0,
+			"system_channel_flags": 0,
+		}))
+		.expect("valid guild JSON")
+	}
+
+	#[test]
+	fn guild_features_parse_known_and_unknown() {
+		let guild = make_guild(&["COMMUNITY", "SOME_NEW_FEATURE"], None);
+		assert!(guild.has_feature("COMMUNITY"));
+		assert!(guild.has_feature("SOME_NEW_FEATURE"));
+		assert!(!guild.has_feature("VANITY_URL"));
+		assert_eq!(guild.feature_names(), ["COMMUNITY", "SOME_NEW_FEATURE"]);
+	}
+
+	#[test]
+	fn guild_vanity_url_requires_feature() {
+		let guild = make_guild(&["VANITY_URL"], Some("rust"));
+		assert_eq!(
+			guild.vanity_url().as_deref(),
+			Some("https://discord.gg/rust")
+		);
+		// A leftover code without the feature isn't usable.
+		assert!(make_guild(&[], Some("rust")).vanity_url().is_none());
+		assert!(make_guild(&["VANITY_URL"], None).vanity_url().is_none());
+	}
+
+	#[test]
+	fn guild_icon_url_static_and_animated() {
+		let hash = "1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d";
+		let mut guild = make_guild(&[], None);
+		assert!(guild.icon_url().is_none());
+		guild.icon = Some(hash.parse().unwrap());
+		assert_eq!(
+			guild.icon_url().unwrap(),
+			format!("https://cdn.discordapp.com/icons/123/{}.png", hash)
+		);
+		let animated = format!("a_{}", hash);
+		guild.icon = Some(animated.parse().unwrap());
+		assert_eq!(
+			guild.icon_url().unwrap(),
+			format!("https://cdn.discordapp.com/icons/123/{}.gif", animated)
+		);
+	}
+
+	#[test]
+	fn channel_type_classifiers() {
+		// (kind, thread, voice, text-like, category)
+		let cases = [
+			(ChannelType::GuildText, false, false, true, false),
+			(ChannelType::Private, false, false, true, false),
+			(ChannelType::GuildVoice, false, true, false, false),
+			(ChannelType::Group, false, false, true, false),
+			(ChannelType::GuildCategory, false, false, false, true),
+			(ChannelType::GuildAnnouncement, false, false, true, false),
+			(ChannelType::AnnouncementThread, true, false, false, false),
+			(ChannelType::PublicThread, true, false, false, false),
+			(ChannelType::PrivateThread, true, false, false, false),
+			(ChannelType::GuildStageVoice, false, true, false, false),
+			(ChannelType::GuildDirectory, false, false, false, false),
+			(ChannelType::GuildForum, false, false, false, false),
+			(ChannelType::GuildMedia, false, false, false, false),
+			(ChannelType::Unknown(99), false, false, false, false),
+		];
+		for (kind, thread, voice, text_like, category) in cases {
+			assert_eq!(kind.is_thread(), thread, "{:?}.is_thread()", kind);
+			assert_eq!(kind.is_voice(), voice, "{:?}.is_voice()", kind);
+			assert_eq!(
+				kind.is_text_like(),
+				text_like,
+				"{:?}.is_text_like()",
+				kind
+			);
+			assert_eq!(
+				kind.is_category(),
+				category,
+				"{:?}.is_category()",
+				kind
+			);
+		}
+	}
+
 	#[test]
 	fn guild_created_at_ms() {
 		let guild_id = Id::<GuildMarker>::new(175928847299117063);
@@ -711,6 +1700,192 @@ mod tests {
 		assert_eq!(ms, expected);
 	}
 
+	fn make_test_member(premium_since: Option<&str>) -> Member {
+		serde_json::from_value(serde_json::json!({
+			"deaf": false,
+			"mute": false,
+			"flags": 0,
+			"joined_at": "2021-01-01T00:00:00.000000+00:00",
+			"nick": null,
+			"communication_disabled_until": null,
+			"premium_since": premium_since,
+			"roles": [],
+			"user": {
+				"id": "789",
+				"username": "alice",
+				"discriminator": "0",
+				"avatar": null,
+			},
+		}))
+		.expect("valid member JSON")
+	}
+
+	#[test]
+	fn member_is_booster_with_premium_since() {
+		let member = make_test_member(Some("2021-03-16T14:29:19.046000+00:00"));
+		assert!(member.premium_since.is_some());
+		assert!(member.is_booster());
+	}
+
+	#[test]
+	fn member_is_not_booster_without_premium_since() {
+		let member = make_test_member(None);
+		assert!(member.premium_since.is_none());
+		assert!(!member.is_booster());
+	}
+
+	// -- EmojiExt -----------------------------------------------------------
+
+	#[test]
+	fn emoji_url_and_mention() {
+		let emoji: Emoji = serde_json::from_value(serde_json::json!({
+			"id": "400",
+			"name": "blobcat",
+		}))
+		.unwrap();
+		assert_eq!(emoji.url(), "https://cdn.discordapp.com/emojis/400.png");
+		assert_eq!(emoji.mention(), "<:blobcat:400>");
+
+		let animated = Emoji {
+			animated: true,
+			..emoji
+		};
+		assert_eq!(animated.url(), "https://cdn.discordapp.com/emojis/400.gif");
+		assert_eq!(animated.mention(), "<a:blobcat:400>");
+	}
+
+	fn make_role(id: u64, permissions: Permissions) -> Role {
+		serde_json::from_value(serde_json::json!({
+			"id": id.to_string(),
+			"name": "role",
+			"color": 0,
+			"colors": {
+				"primary_color": 0,
+				"secondary_color": null,
+				"tertiary_color": null,
+			},
+			"hoist": false,
+			"managed": false,
+			"mentionable": false,
+			"permissions": permissions.bits().to_string(),
+			"position": 0,
+			"flags": 0,
+		}))
+		.expect("valid role JSON")
+	}
+
+	fn overwrite(
+		id: u64,
+		kind: u8,
+		allow: Permissions,
+		deny: Permissions,
+	) -> PermissionOverwrite {
+		serde_json::from_value(serde_json::json!({
+			"id": id.to_string(),
+			"type": kind,
+			"allow": allow.bits().to_string(),
+			"deny": deny.bits().to_string(),
+		}))
+		.expect("valid overwrite JSON")
+	}
+
+	#[test]
+	fn channel_permissions_apply_overwrites_in_order() {
+		let mut guild = make_guild(&[], None);
+		guild.roles = vec![
+			// @everyone
+			make_role(
+				123,
+				Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES,
+			),
+			make_role(20, Permissions::MANAGE_MESSAGES),
+		];
+		let mut member = make_test_member(None);
+		member.roles.push(Id::new(20));
+		let none = Permissions::empty();
+
+		let muted = [overwrite(123, 0, none, Permissions::SEND_MESSAGES)];
+		assert_eq!(
+			guild.channel_permissions(&member, &muted),
+			Permissions::VIEW_CHANNEL | Permissions::MANAGE_MESSAGES
+		);
+
+		// Role overwrites beat @everyone, member overwrites beat roles.
+		let overwrites = [
+			overwrite(123, 0, none, Permissions::SEND_MESSAGES),
+			overwrite(20, 0, Permissions::SEND_MESSAGES, none),
+			overwrite(789, 1, none, Permissions::MANAGE_MESSAGES),
+		];
+		assert_eq!(
+			guild.channel_permissions(&member, &overwrites),
+			Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES
+		);
+
+		guild.owner_id = Id::new(789);
+		assert_eq!(
+			guild.channel_permissions(&member, &overwrites),
+			Permissions::all()
+		);
+	}
+
+	#[test]
+	fn format_permissions_lists_readable_names() {
+		assert_eq!(
+			format_permissions(
+				Permissions::SEND_MESSAGES | Permissions::VIEW_CHANNEL
+			),
+			"View Channel, Send Messages"
+		);
+		assert_eq!(format_permissions(Permissions::empty()), "None");
+	}
+
+	fn make_test_presence(activities: serde_json::Value) -> Presence {
+		serde_json::from_value(serde_json::json!({
+			"user": { "id": "789" },
+			"guild_id": "1",
+			"status": "online",
+			"client_status": {},
+			"activities": activities,
+		}))
+		.expect("valid presence JSON")
+	}
+
+	#[test]
+	fn presence_primary_activity_none_without_activities() {
+		let presence = make_test_presence(serde_json::json!([]));
+		assert!(presence.primary_activity().is_none());
+	}
+
+	#[test]
+	fn presence_primary_activity_prefers_game_over_custom_status() {
+		let presence = make_test_presence(serde_json::json!([
+			{ "name": "Custom Status", "type": 4, "state": "busy" },
+			{ "name": "Spotify", "type": 2 },
+			{ "name": "Minecraft", "type": 0 },
+		]));
+		let activity = presence.primary_activity().unwrap();
+		assert_eq!(activity.name, "Minecraft");
+	}
+
+	#[test]
+	fn presence_primary_activity_accepts_streaming() {
+		let presence = make_test_presence(serde_json::json!([
+			{ "name": "Custom Status", "type": 4 },
+			{ "name": "Twitch", "type": 1, "url": "https://twitch.tv/x" },
+		]));
+		let activity = presence.primary_activity().unwrap();
+		assert_eq!(activity.kind, ActivityType::Streaming);
+	}
+
+	#[test]
+	fn presence_primary_activity_ignores_non_games() {
+		let presence = make_test_presence(serde_json::json!([
+			{ "name": "Custom Status", "type": 4 },
+			{ "name": "Spotify", "type": 2 },
+		]));
+		assert!(presence.primary_activity().is_none());
+	}
+
 	#[test]
 	fn message_snowflake_timestamp() {
 		let msg_id = Id::<MessageMarker>::new(175928847299117063);
@@ -726,6 +1901,59 @@ mod tests {
 		assert_eq!(id.value(), 12345);
 	}
 
+	#[test]
+	fn parse_status_known_values() {
+		assert_eq!(parse_status("dnd"), Status::DoNotDisturb);
+		assert_eq!(parse_status("idle"), Status::Idle);
+		assert_eq!(parse_status("online"), Status::Online);
+	}
+
+	#[test]
+	fn parse_status_unknown_is_offline() {
+		assert_eq!(parse_status("busy"), Status::Offline);
+		assert_eq!(parse_status(""), Status::Offline);
+		assert_eq!(parse_status("Online"), Status::Offline);
+	}
+
+	#[test]
+	fn status_round_trips_through_as_str() {
+		for status in [
+			Status::DoNotDisturb,
+			Status::Idle,
+			Status::Invisible,
+			Status::Offline,
+			Status::Online,
+		] {
+			assert_eq!(parse_status(status.as_str()), status);
+			let json: Status =
+				serde_json::from_value(serde_json::json!(status.as_str()))
+					.unwrap();
+			assert_eq!(json, status);
+		}
+	}
+
+	#[test]
+	fn to_typed_round_trips_snowflake() {
+		let raw = "1234567890123456789";
+		let id: Id<GuildMarker> = to_typed(raw).unwrap();
+		assert_eq!(id.get(), 1234567890123456789);
+		assert_eq!(to_string_id(id), raw);
+		assert_eq!(to_typed::<GuildMarker>(&to_string_id(id)), Some(id));
+	}
+
+	#[test]
+	fn to_typed_rejects_invalid_ids() {
+		assert_eq!(to_typed::<UserMarker>(""), None);
+		assert_eq!(to_typed::<UserMarker>("0"), None);
+		assert_eq!(to_typed::<UserMarker>("abc"), None);
+		assert_eq!(to_typed::<UserMarker>("-5"), None);
+	}
+
+	#[test]
+	fn to_typed_trims_whitespace() {
+		assert_eq!(to_typed::<UserMarker>(" 42 "), Some(Id::new(42)));
+	}
+
 	#[test]
 	fn current_user_tag_with_discriminator() {
 		let user: CurrentUser = serde_json::from_value(serde_json::json!({
@@ -771,6 +1999,106 @@ mod tests {
 		assert!(user.avatar_url().is_none());
 	}
 
+	// -- MessageExt ---------------------------------------------------------
+
+	#[test]
+	fn replied_content_reads_referenced_message() {
+		let mut json = message_json("2", "what do you mean?");
+		json["type"] = 19.into();
+		json["message_reference"] = serde_json::json!({
+			"message_id": "1",
+			"channel_id": "100",
+		});
+		json["referenced_message"] = message_json("1", "the original");
+		let message = parse_message(json);
+		assert_eq!(message.replied_content(), Some("the original"));
+		assert!(!message.is_unresolved_reply());
+	}
+
+	#[test]
+	fn replied_content_unresolved_reference() {
+		let mut json = message_json("2", "replying to a deleted message");
+		json["type"] = 19.into();
+		json["message_reference"] = serde_json::json!({
+			"message_id": "1",
+			"channel_id": "100",
+		});
+		json["referenced_message"] = serde_json::Value::Null;
+		let message = parse_message(json);
+		assert_eq!(message.replied_content(), None);
+		assert!(message.is_unresolved_reply());
+	}
+
+	#[test]
+	fn replied_content_not_a_reply() {
+		let message = parse_message(message_json("1", "hello"));
+		assert_eq!(message.replied_content(), None);
+		assert!(!message.is_unresolved_reply());
+	}
+
+	#[test]
+	fn message_parses_sticker_items() {
+		let mut json = message_json("1", "");
+		json["sticker_items"] = serde_json::json!([
+			{ "id": "900", "name": "wave", "format_type": 1 },
+			{ "id": "901", "name": "party", "format_type": 3 },
+		]);
+		let message = parse_message(json);
+		assert_eq!(message.sticker_items[0].name, "wave");
+		assert_eq!(
+			message.sticker_items[1].format_type,
+			StickerFormatType::Lottie
+		);
+		assert_eq!(message.sticker_ids(), [Id::new(900), Id::new(901)]);
+		let plain = parse_message(message_json("2", "hi"));
+		assert!(plain.sticker_ids().is_empty());
+	}
+
+	#[test]
+	fn message_parses_webhook_and_application_ids() {
+		let mut json = message_json("1", "!ping");
+		json["webhook_id"] = "300".into();
+		json["application_id"] = "400".into();
+		let message = parse_message(json);
+		assert_eq!(message.webhook_id, Some(Id::new(300)));
+		assert_eq!(message.application_id, Some(Id::new(400)));
+		assert!(message.is_automated());
+		let plain = parse_message(message_json("2", "!ping"));
+		assert_eq!(plain.webhook_id, None);
+		assert!(!plain.is_automated());
+	}
+
+	// -- TimestampExt -------------------------------------------------------
+
+	#[test]
+	fn timestamp_now_round_trips_through_iso_8601() {
+		let now = Timestamp::now();
+		let parsed = Timestamp::parse(&now.iso_8601().to_string()).unwrap();
+		assert_eq!(parsed, now);
+		assert!(now.as_secs() > 1_600_000_000);
+	}
+
+	#[test]
+	fn timestamp_add_seconds_crosses_day_boundary() {
+		let ts = Timestamp::parse("2021-12-31T23:30:00.000000+00:00").unwrap();
+		let later = ts.add_seconds(3600).unwrap();
+		assert_eq!(
+			later.iso_8601().to_string(),
+			"2022-01-01T00:30:00.000000+00:00"
+		);
+		assert_eq!(later.add_seconds(-3600).unwrap(), ts);
+		assert!(ts.add_seconds(i64::MAX).is_err());
+	}
+
+	#[test]
+	fn timestamp_from_chrono_keeps_micros() {
+		let datetime =
+			chrono::DateTime::from_timestamp_micros(1_609_459_200_123_456)
+				.unwrap();
+		let ts = Timestamp::from_chrono(datetime).unwrap();
+		assert_eq!(ts.as_micros(), 1_609_459_200_123_456);
+	}
+
 	// -- CommandExt ---------------------------------------------------------
 
 	#[test]
@@ -798,6 +2126,202 @@ mod tests {
 		assert_eq!(cmd.options[0].required, Some(false));
 	}
 
+	#[test]
+	fn command_ext_contexts_and_integration_types_serialize() {
+		let cmd = Command::chat_input("ping", "Check latency")
+			.with_contexts([
+				InteractionContextType::Guild,
+				InteractionContextType::BotDm,
+				InteractionContextType::PrivateChannel,
+			])
+			.with_integration_types([
+				ApplicationIntegrationType::GuildInstall,
+				ApplicationIntegrationType::UserInstall,
+			]);
+		let json = serde_json::to_value(&cmd).unwrap();
+		assert_eq!(json["contexts"], serde_json::json!([0, 1, 2]));
+		assert_eq!(json["integration_types"], serde_json::json!([0, 1]));
+	}
+
+	#[test]
+	fn command_ext_empty_contexts_are_unset() {
+		let cmd = Command::chat_input("ping", "Check latency")
+			.with_contexts(Vec::new())
+			.with_integration_types(Vec::new());
+		assert!(cmd.contexts.is_none());
+		assert!(cmd.integration_types.is_none());
+	}
+
+	fn choice(
+		name: &str,
+		value: CommandOptionChoiceValue,
+	) -> CommandOptionChoice {
+		CommandOptionChoice {
+			name: name.to_string(),
+			name_localizations: None,
+			value,
+		}
+	}
+
+	#[test]
+	fn command_ext_validate_accepts_matching_choices() {
+		let cmd = Command::chat_input("roll", "Roll a dice").with_option(
+			command_option_with_choices(
+				CommandOptionType::Integer,
+				"sides",
+				"Number of sides",
+				false,
+				vec![
+					choice("d6", CommandOptionChoiceValue::Integer(6)),
+					choice("d20", CommandOptionChoiceValue::Integer(20)),
+				],
+			),
+		);
+		assert!(cmd.validate().is_ok());
+	}
+
+	#[test]
+	fn command_ext_validate_rejects_integer_with_string_choice() {
+		let cmd = Command::chat_input("roll", "Roll a dice").with_option(
+			command_option_with_choices(
+				CommandOptionType::Integer,
+				"sides",
+				"Number of sides",
+				false,
+				vec![choice(
+					"six",
+					CommandOptionChoiceValue::String("6".to_string()),
+				)],
+			),
+		);
+		let err = cmd.validate().unwrap_err();
+		assert!(err.0.contains("roll.sides"), "{}", err.0);
+		assert!(err.0.contains("six"), "{}", err.0);
+	}
+
+	#[test]
+	fn command_ext_validate_rejects_string_with_number_choice() {
+		let cmd = Command::chat_input("color", "Pick a color").with_option(
+			command_option_with_choices(
+				CommandOptionType::String,
+				"name",
+				"Color name",
+				true,
+				vec![choice("red", CommandOptionChoiceValue::Number(1.0))],
+			),
+		);
+		assert!(cmd.validate().is_err());
+	}
+
+	#[test]
+	fn command_ext_validate_checks_subcommands() {
+		let mut sub = command_option_with_choices(
+			CommandOptionType::SubCommand,
+			"dice",
+			"Roll dice",
+			false,
+			Vec::new(),
+		);
+		sub.choices = None;
+		sub.options = Some(vec![command_option_with_choices(
+			CommandOptionType::Integer,
+			"sides",
+			"Number of sides",
+			false,
+			vec![choice("six", CommandOptionChoiceValue::String("6".into()))],
+		)]);
+		let err = Command::chat_input("roll", "Roll")
+			.with_option(sub)
+			.validate()
+			.unwrap_err();
+		assert!(err.0.contains("roll.dice.sides"), "{}", err.0);
+	}
+
+	#[test]
+	fn command_ext_validate_rejects_subcommand_beside_plain_option() {
+		let err = Command::chat_input("tag", "Tags")
+			.with_subcommand("list", "List tags", Vec::new())
+			.with_simple_option(
+				CommandOptionType::String,
+				"name",
+				"Tag name",
+				false,
+			)
+			.validate()
+			.unwrap_err();
+		assert!(err.0.contains("`tag` mixes SubCommand `list`"), "{}", err.0);
+	}
+
+	#[test]
+	fn command_ext_validate_rejects_plain_option_in_group() {
+		let mut group = command_option_with_choices(
+			CommandOptionType::SubCommandGroup,
+			"config",
+			"Configure",
+			false,
+			Vec::new(),
+		);
+		group.choices = None;
+		group.options = Some(vec![command_option_with_choices(
+			CommandOptionType::String,
+			"key",
+			"Setting",
+			true,
+			Vec::new(),
+		)]);
+		let err = Command::chat_input("admin", "Admin")
+			.with_option(group)
+			.validate()
+			.unwrap_err();
+		assert!(err.0.contains("admin.config"), "{}", err.0);
+		assert!(err.0.contains("String `key`"), "{}", err.0);
+	}
+
+	#[test]
+	fn with_ranged_option_serializes_bounds() {
+		let cmd = Command::chat_input("roll", "Roll").with_ranged_option(
+			CommandOptionType::Integer,
+			"sides",
+			"Number of sides",
+			false,
+			CommandOptionValue::Integer(2),
+			CommandOptionValue::Integer(1000),
+		);
+		assert!(cmd.validate().is_ok());
+		let json = serde_json::to_value(&cmd).unwrap();
+		assert_eq!(json["options"][0]["min_value"], 2);
+		assert_eq!(json["options"][0]["max_value"], 1000);
+	}
+
+	#[test]
+	fn command_ext_validate_rejects_range_on_string_option() {
+		let err = Command::chat_input("say", "Say")
+			.with_ranged_option(
+				CommandOptionType::String,
+				"text",
+				"Text",
+				true,
+				CommandOptionValue::Integer(1),
+				CommandOptionValue::Integer(5),
+			)
+			.validate()
+			.unwrap_err();
+		assert!(err.0.contains("say.text"), "{}", err.0);
+	}
+
+	#[test]
+	fn command_ext_validate_rejects_inverted_range() {
+		let cmd = Command::chat_input("pick", "Pick").with_ranged_option(
+			CommandOptionType::Number,
+			"n",
+			"N",
+			true,
+			CommandOptionValue::Number(5.0),
+			CommandOptionValue::Integer(1),
+		);
+		assert!(cmd.validate().is_err());
+	}
+
 	// -- EmbedExt -----------------------------------------------------------
 
 	#[test]
@@ -828,6 +2352,151 @@ mod tests {
 		assert!(!embed.fields[1].inline);
 	}
 
+	#[test]
+	fn embed_validate_accepts_https_url() {
+		let embed = Embed::new()
+			.with_image("https://example.com/a.png")
+			.with_footer_icon("f", "http://example.com/i.png");
+		assert!(embed.validate().is_ok());
+	}
+
+	#[test]
+	fn embed_validate_accepts_attachment_url() {
+		let embed = Embed::new().with_thumbnail("attachment://logo.png");
+		assert!(embed.validate().is_ok());
+	}
+
+	#[test]
+	fn embed_validate_rejects_file_url() {
+		let err = Embed::new()
+			.with_image("file:///etc/passwd")
+			.validate()
+			.unwrap_err();
+		assert!(err.0.contains("image"), "{}", err.0);
+		assert!(err.0.contains("file:///etc/passwd"), "{}", err.0);
+	}
+
+	#[test]
+	fn embed_validate_checks_author_icon() {
+		let mut embed = Embed::new().with_author("me");
+		embed.author.as_mut().unwrap().icon_url = Some("logo.png".into());
+		assert!(embed.validate().is_err());
+	}
+
+	// -- truncate_markdown_safe ----------------------------------------------
+
+	#[test]
+	fn truncate_keeps_short_text() {
+		assert_eq!(truncate_markdown_safe("hello", 5), "hello");
+	}
+
+	#[test]
+	fn truncate_cuts_at_word_boundary() {
+		let text = truncate_markdown_safe("the quick brown fox", 13);
+		assert_eq!(text, "the quick…");
+		assert!(text.chars().count() <= 13);
+	}
+
+	#[test]
+	fn truncate_hard_cuts_long_words() {
+		let text = truncate_markdown_safe("abcdefghijklmnop", 6);
+		assert_eq!(text, "abcde…");
+	}
+
+	#[test]
+	fn truncate_closes_open_code_fence() {
+		let input = "See:\n```rust\nfn main() { println!(\"hi\"); }\n```";
+		let text = truncate_markdown_safe(input, 30);
+		assert_eq!(text, "See:\n```rust\nfn main() {…\n```");
+		assert!(text.chars().count() <= 30);
+		assert_eq!(text.matches("```").count(), 2);
+	}
+
+	#[test]
+	fn truncate_leaves_closed_code_fence() {
+		let input = "```a```\nmore words follow here";
+		let text = truncate_markdown_safe(input, 20);
+		assert_eq!(text, "```a```\nmore words…");
+	}
+
+	// -- Entitlements -------------------------------------------------------
+
+	const ENTITLEMENT_START: &str = "2024-01-01T00:00:00.000000+00:00";
+	const ENTITLEMENT_END: &str = "2024-02-01T00:00:00.000000+00:00";
+
+	fn interaction_with_entitlements(
+		entitlements: serde_json::Value,
+	) -> Interaction {
+		serde_json::from_value(serde_json::json!({
+			"id": "1",
+			"application_id": "2",
+			"type": 2,
+			"token": "tok",
+			"authorizing_integration_owners": {},
+			"entitlements": entitlements,
+			"data": { "id": "3", "name": "premium", "type": 1 },
+		}))
+		.expect("valid interaction JSON")
+	}
+
+	fn entitlement_json(
+		sku_id: &str,
+		ends_at: Option<&str>,
+		deleted: bool,
+	) -> serde_json::Value {
+		serde_json::json!({
+			"id": "70",
+			"application_id": "2",
+			"sku_id": sku_id,
+			"user_id": "42",
+			"type": 8,
+			"deleted": deleted,
+			"starts_at": ENTITLEMENT_START,
+			"ends_at": ends_at,
+		})
+	}
+
+	#[test]
+	fn interaction_parses_entitlements() {
+		let interaction = interaction_with_entitlements(serde_json::json!([
+			entitlement_json("500", Some(ENTITLEMENT_END), false),
+		]));
+		let entitlement = &interaction.entitlements[0];
+		assert_eq!(entitlement.sku_id, Id::new(500));
+		assert_eq!(entitlement.kind, EntitlementType::ApplicationSubscription);
+		assert_eq!(
+			entitlement.starts_at,
+			Some(Timestamp::parse(ENTITLEMENT_START).unwrap())
+		);
+		assert!(entitlement.ends_at.is_some());
+	}
+
+	#[test]
+	fn entitlement_gating_checks_sku_dates_and_deletion() {
+		let interaction = interaction_with_entitlements(serde_json::json!([
+			entitlement_json("500", None, false),
+			entitlement_json("501", Some(ENTITLEMENT_END), false),
+			entitlement_json("502", None, true),
+		]));
+		assert!(interaction.has_entitlement(Id::new(500)));
+		// Expired, deleted and missing SKUs don't grant access.
+		assert!(!interaction.has_entitlement(Id::new(501)));
+		assert!(!interaction.has_entitlement(Id::new(502)));
+		assert!(!interaction.has_entitlement(Id::new(503)));
+
+		let expiring = &interaction.entitlements[1];
+		let during =
+			Timestamp::parse("2024-01-15T00:00:00.000000+00:00").unwrap();
+		let before =
+			Timestamp::parse("2023-12-31T00:00:00.000000+00:00").unwrap();
+		assert!(expiring.is_active_at(during));
+		assert!(!expiring.is_active_at(before));
+		assert!(
+			!interaction_with_entitlements(serde_json::json!([]))
+				.has_entitlement(Id::new(500))
+		);
+	}
+
 	// -- InteractionResponseExt ---------------------------------------------
 
 	#[test]
@@ -918,4 +2587,31 @@ mod tests {
 			_ => panic!("expected TextInput"),
 		}
 	}
+
+	// -- ComponentExt ------------------------------------------------------
+
+	#[test]
+	fn component_id_omitted_by_default() {
+		let json = serde_json::to_value(button(1, "Go", "go")).unwrap();
+		assert!(json.get("id").is_none());
+	}
+
+	#[test]
+	fn component_id_serialized_when_set() {
+		let json =
+			serde_json::to_value(button(1, "Go", "go").with_component_id(7))
+				.unwrap();
+		assert_eq!(json["id"], 7);
+	}
+
+	#[test]
+	fn component_id_on_action_row_and_select() {
+		let row = action_row(vec![
+			string_select("pick", "Pick one", vec![]).with_component_id(2),
+		])
+		.with_component_id(1);
+		let json = serde_json::to_value(row).unwrap();
+		assert_eq!(json["id"], 1);
+		assert_eq!(json["components"][0]["id"], 2);
+	}
 }
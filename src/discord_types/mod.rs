@@ -12,6 +12,8 @@
 /// rate-limit info, etc.)
 mod custom;
 pub use custom::*;
+/// Named embed color constants.
+pub mod colors;
 mod events;
 pub use events::*;
 mod ext;
@@ -0,0 +1,127 @@
+//! Crate-level error type.
+//!
+//! Each layer keeps its own error ([`HttpError`](crate::discord_io::HttpError)
+//! for REST calls, [`JsonError`] for request building, …); [`Error`] wraps
+//! them so code spanning several layers can propagate with `?`.
+//!
+//! These are deliberately not part of the prelude: `Result` would clash with
+//! beet's `Result` alias, so import them by path:
+//!
+//! ```ignore
+//! use hello_discord::error::Error;
+//!
+//! fn build(channel_id: Id<ChannelMarker>) -> hello_discord::error::Result<()> {
+//!     CreateMessage::new(channel_id).content("hi").validate()?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::discord_types::JsonError;
+use twilight_model::util::datetime::TimestampParseError;
+
+/// Result alias using the crate [`Error`].
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Any error produced by this crate.
+#[derive(Debug)]
+pub enum Error {
+	/// A REST call failed.
+	#[cfg(feature = "io")]
+	Http(crate::discord_io::HttpError),
+	/// The gateway connection could not be established or was lost.
+	Gateway(String),
+	/// A Discord ISO 8601 timestamp could not be parsed.
+	Timestamp(TimestampParseError),
+	/// A JSON body could not be built or parsed.
+	Json(JsonError),
+}
+
+impl std::fmt::Display for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			#[cfg(feature = "io")]
+			Error::Http(e) => write!(f, "{}", e),
+			Error::Gateway(e) => write!(f, "gateway error: {}", e),
+			Error::Timestamp(e) => write!(f, "timestamp error: {}", e),
+			Error::Json(e) => write!(f, "JSON error: {}", e),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			#[cfg(feature = "io")]
+			Error::Http(e) => Some(e),
+			Error::Gateway(_) => None,
+			Error::Timestamp(e) => Some(e),
+			Error::Json(e) => Some(e),
+		}
+	}
+}
+
+#[cfg(feature = "io")]
+impl From<crate::discord_io::HttpError> for Error {
+	fn from(e: crate::discord_io::HttpError) -> Self {
+		Error::Http(e)
+	}
+}
+
+impl From<TimestampParseError> for Error {
+	fn from(e: TimestampParseError) -> Self { Error::Timestamp(e) }
+}
+
+impl From<JsonError> for Error {
+	fn from(e: JsonError) -> Self { Error::Json(e) }
+}
+
+impl From<serde_json::Error> for Error {
+	fn from(e: serde_json::Error) -> Self {
+		Error::Json(JsonError(e.to_string()))
+	}
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use twilight_model::util::Timestamp;
+
+	#[cfg(feature = "io")]
+	#[test]
+	fn from_http_error() {
+		fn fails() -> Result<()> {
+			Err(crate::discord_io::HttpError::Transport("offline".into()))?
+		}
+		let err = fails().unwrap_err();
+		assert!(matches!(err, Error::Http(_)));
+		assert!(err.to_string().contains("offline"));
+	}
+
+	#[test]
+	fn from_serde_json_error() {
+		fn fails() -> Result<serde_json::Value> {
+			Ok(serde_json::from_str("{not json")?)
+		}
+		let err = fails().unwrap_err();
+		assert!(matches!(err, Error::Json(_)));
+		assert!(err.to_string().starts_with("JSON error:"));
+	}
+
+	#[test]
+	fn from_timestamp_parse_error() {
+		fn fails() -> Result<Timestamp> { Ok(Timestamp::parse("yesterday")?) }
+		assert!(matches!(fails().unwrap_err(), Error::Timestamp(_)));
+	}
+
+	#[test]
+	fn source_is_preserved() {
+		use std::error::Error as _;
+		let err = Error::from(JsonError("bad".into()));
+		assert!(err.source().is_some());
+		assert!(Error::Gateway("down".into()).source().is_none());
+	}
+}
@@ -19,7 +19,9 @@
 //! ```
 //!
 //! `use crate::prelude::*;` (or `use hello_discord::prelude::*;`) brings in
-//! all types and extension traits *defined in this crate*.
+//! all types and extension traits *defined in this crate*, except the
+//! crate-level [`error::Error`] and [`error::Result`], which are imported
+//! by path to avoid clashing with beet's `Result`.
 
 #[cfg(feature = "io")]
 pub mod bot;
@@ -27,7 +29,10 @@ pub mod common_handlers;
 #[cfg(feature = "io")]
 pub mod discord_io;
 pub mod discord_types;
+pub mod error;
 pub mod request_types;
+#[cfg(test)]
+mod test_fixtures;
 pub mod tw_gateway;
 pub mod tw_http;
 
@@ -35,8 +40,7 @@ pub mod prelude {
 	#[cfg(feature = "io")]
 	pub use crate::bot::*;
 	pub use crate::common_handlers;
-	pub use crate::common_handlers::BotChannels;
-	pub use crate::common_handlers::BotState;
+	pub use crate::common_handlers::*;
 	#[cfg(feature = "io")]
 	pub use crate::discord_io::*;
 	pub use crate::discord_types::CommandExt;
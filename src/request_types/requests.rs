@@ -11,6 +11,8 @@ use crate::prelude::*;
 use beet::prelude::*;
 use twilight_model::application::command::Command as ApplicationCommand;
 use twilight_model::channel::Channel;
+use twilight_model::channel::message::AllowedMentions;
+use twilight_model::channel::message::MentionType;
 use twilight_model::channel::message::Message;
 use twilight_model::channel::message::component::Component;
 use twilight_model::channel::message::embed::Embed;
@@ -22,6 +24,7 @@ use twilight_model::id::marker::ChannelMarker;
 use twilight_model::id::marker::GuildMarker;
 use twilight_model::id::marker::InteractionMarker;
 use twilight_model::id::marker::MessageMarker;
+use twilight_model::oauth::Application;
 use twilight_model::user::CurrentUser;
 use twilight_model::user::CurrentUserGuild;
 
@@ -29,6 +32,24 @@ use twilight_model::user::CurrentUserGuild;
 // Messages
 // ===========================================================================
 
+// ---- Allowed mentions -----------------------------------------------------
+
+/// The mentions a [`CreateMessage`] may ping when it doesn't set
+/// [`CreateMessage::allowed_mentions`], unless the client sending it was
+/// given others with
+/// [`with_allowed_mentions`](crate::discord_io::DiscordHttpClient::with_allowed_mentions).
+///
+/// Discord parses every mention in content by default, so a bot echoing
+/// user input could ping `@everyone`. Here users and roles are parsed and
+/// replies ping their author, but `@everyone` and `@here` don't ping.
+pub fn default_allowed_mentions() -> AllowedMentions {
+	AllowedMentions {
+		parse: vec![MentionType::Users, MentionType::Roles],
+		replied_user: true,
+		..Default::default()
+	}
+}
+
 // ---- CreateMessage --------------------------------------------------------
 
 /// Create a message in a channel.
@@ -53,6 +74,12 @@ pub struct CreateMessage {
 	pub components: Option<Vec<Component>>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub flags: Option<u32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub poll: Option<CreatePoll>,
+	/// Falls back to the sending client's default when unset, see
+	/// [`default_allowed_mentions`].
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub allowed_mentions: Option<AllowedMentions>,
 }
 
 impl CreateMessage {
@@ -64,6 +91,8 @@ impl CreateMessage {
 			message_reference: None,
 			components: None,
 			flags: None,
+			poll: None,
+			allowed_mentions: None,
 		}
 	}
 
@@ -101,12 +130,64 @@ impl CreateMessage {
 		self.flags = Some(flags);
 		self
 	}
+
+	/// Attach a native poll to the message.
+	pub fn poll(mut self, poll: CreatePoll) -> Self {
+		self.poll = Some(poll);
+		self
+	}
+
+	/// Set which mentions in the content may ping, instead of the client's
+	/// [`default_allowed_mentions`]. Messages that really need to ping
+	/// `@everyone` opt in here.
+	///
+	/// ```ignore
+	/// let msg = CreateMessage::new(channel_id)
+	///     .content("@everyone the server restarts in 5 minutes")
+	///     .allowed_mentions(AllowedMentions {
+	///         parse: vec![MentionType::Everyone],
+	///         ..Default::default()
+	///     });
+	/// ```
+	pub fn allowed_mentions(mut self, mentions: AllowedMentions) -> Self {
+		self.allowed_mentions = Some(mentions);
+		self
+	}
+
+	/// Check that the message has something to send.
+	///
+	/// Discord rejects messages without content, embeds, components, or a
+	/// poll with an opaque 400, so catch it before the request goes out.
+	/// Empty content counts as no content. Embeds are checked with
+	/// [`EmbedExt::validate`].
+	pub fn validate(&self) -> Result<(), JsonError> {
+		if let Some(poll) = &self.poll {
+			poll.validate()?;
+		}
+		for embed in self.embeds.iter().flatten() {
+			embed.validate()?;
+		}
+		let has_content =
+			self.content.as_deref().is_some_and(|c| !c.is_empty());
+		let has_embeds = self.embeds.as_ref().is_some_and(|e| !e.is_empty());
+		let has_components =
+			self.components.as_ref().is_some_and(|c| !c.is_empty());
+		if has_content || has_embeds || has_components || self.poll.is_some() {
+			Ok(())
+		} else {
+			Err(JsonError(
+				"CreateMessage requires content, an embed, a component, or a poll"
+					.to_string(),
+			))
+		}
+	}
 }
 
 impl IntoDiscordRequest for CreateMessage {
 	type Output = Message;
 
 	fn into_discord_request(self) -> Result<DiscordRequest, JsonError> {
+		self.validate()?;
 		let path = format!("channels/{}/messages", self.channel_id);
 		let route_key = format!("POST /channels/{}/messages", self.channel_id);
 		Ok(DiscordRequest {
@@ -282,6 +363,11 @@ impl IntoDiscordRequest for GetChannelMessages {
 
 /// Get a single message by ID.
 ///
+/// The route key is `GET /channels/{channel_id}/messages` with the real
+/// channel id, the major parameter Discord buckets by. The message id is
+/// left out, as for the other per-message routes, so fetches share their
+/// channel's bucket instead of each message getting its own.
+///
 /// ```ignore
 /// let msg: Message = http.send(GetMessage::new(channel_id, message_id)).await?;
 /// ```
@@ -427,6 +513,24 @@ impl EditMessage {
 		self.components.get_or_insert_with(Vec::new).push(row);
 		self
 	}
+
+	/// An edit setting the content, embeds, components and flags `message`
+	/// sets, leaving the others unchanged. Replies and polls can't be
+	/// edited, so those are ignored.
+	pub fn from_create(
+		channel_id: Id<ChannelMarker>,
+		message_id: Id<MessageMarker>,
+		message: &CreateMessage,
+	) -> Self {
+		Self {
+			channel_id,
+			message_id,
+			content: message.content.clone(),
+			embeds: message.embeds.clone(),
+			components: message.components.clone(),
+			flags: message.flags,
+		}
+	}
 }
 
 impl IntoDiscordRequest for EditMessage {
@@ -902,7 +1006,8 @@ impl IntoDiscordRequest for CreateInteractionResponse {
 			"interactions/{}/{}/callback",
 			self.interaction_id, self.interaction_token
 		);
-		let route_key = "POST /interactions/callback".to_string();
+		let route_key =
+			format!("POST /interactions/{}/callback", self.interaction_id);
 		let body = serde_json::to_value(&self.response)
 			.map(RequestBody::Json)
 			.map_err(|e| JsonError(e.to_string()))?;
@@ -941,6 +1046,8 @@ pub struct EditOriginalInteractionResponse {
 	pub embeds: Option<Vec<Embed>>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub components: Option<Vec<Component>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub flags: Option<u64>,
 }
 
 impl EditOriginalInteractionResponse {
@@ -954,6 +1061,7 @@ impl EditOriginalInteractionResponse {
 			content: None,
 			embeds: None,
 			components: None,
+			flags: None,
 		}
 	}
 
@@ -971,6 +1079,15 @@ impl EditOriginalInteractionResponse {
 		self.components.get_or_insert_with(Vec::new).push(row);
 		self
 	}
+
+	/// Set message flags, e.g. `SUPPRESS_EMBEDS`. Ephemerality is fixed
+	/// by the initial response and can't be changed by an edit, so pass
+	/// `EPHEMERAL` along when editing an ephemeral message to keep it
+	/// consistent.
+	pub fn flags(mut self, flags: u64) -> Self {
+		self.flags = Some(flags);
+		self
+	}
 }
 
 impl IntoDiscordRequest for EditOriginalInteractionResponse {
@@ -981,8 +1098,11 @@ impl IntoDiscordRequest for EditOriginalInteractionResponse {
 			"webhooks/{}/{}/messages/@original",
 			self.application_id, self.interaction_token
 		);
-		let route_key =
-			"PATCH /webhooks/interaction/messages/@original".to_string();
+		let route_key = format!(
+			"PATCH /webhooks/{}/{}/messages/@original",
+			self.application_id,
+			token_route_id(&self.interaction_token)
+		);
 		Ok(DiscordRequest {
 			method: HttpMethod::Patch,
 			path,
@@ -1000,6 +1120,33 @@ impl IntoDiscordRequest for EditOriginalInteractionResponse {
 // Application Commands
 // ===========================================================================
 
+// ---- GetCurrentApplication ------------------------------------------------
+
+/// Get the bot's own application, including its owner or team.
+///
+/// ```ignore
+/// let app: Application = http.send(GetCurrentApplication).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct GetCurrentApplication;
+
+impl IntoDiscordRequest for GetCurrentApplication {
+	type Output = Application;
+
+	fn into_discord_request(self) -> Result<DiscordRequest, JsonError> {
+		Ok(DiscordRequest {
+			method: HttpMethod::Get,
+			path: "applications/@me".to_string(),
+			route_key: "GET /applications/@me".to_string(),
+			body: RequestBody::None,
+		})
+	}
+
+	fn parse_response(bytes: &[u8]) -> Result<Application, JsonError> {
+		parse_json(bytes)
+	}
+}
+
 // ---- SetGlobalCommands ----------------------------------------------------
 
 /// Register (or overwrite) global application commands.
@@ -1226,7 +1373,7 @@ impl IntoDiscordRequest for DeleteOwnReaction {
 
 // ---- DeleteAllReactions ---------------------------------------------------
 
-/// Remove all reactions from a message.
+/// Remove all reactions from a message. Requires `MANAGE_MESSAGES`.
 ///
 /// ```ignore
 /// http.send(DeleteAllReactions::new(channel_id, msg_id)).await?;
@@ -1318,6 +1465,100 @@ mod tests {
 		assert!(matches!(req.body, RequestBody::Json(_)));
 	}
 
+	#[test]
+	fn create_message_leaves_default_mentions_to_the_client() {
+		let req = CreateMessage::new(Id::new(42))
+			.content("@everyone look at this")
+			.into_discord_request()
+			.unwrap();
+		match req.body {
+			RequestBody::Json(body) => {
+				assert!(body.get("allowed_mentions").is_none());
+			}
+			other => panic!("expected JSON body, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn create_message_can_opt_in_to_everyone() {
+		let req = CreateMessage::new(Id::new(42))
+			.content("@everyone maintenance tonight")
+			.allowed_mentions(AllowedMentions {
+				parse: vec![MentionType::Everyone],
+				..Default::default()
+			})
+			.into_discord_request()
+			.unwrap();
+		match req.body {
+			RequestBody::Json(body) => {
+				let parse = &body["allowed_mentions"]["parse"];
+				assert_eq!(parse, &serde_json::json!(["everyone"]));
+			}
+			other => panic!("expected JSON body, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn create_message_empty_is_rejected() {
+		let err = CreateMessage::new(Id::new(42))
+			.into_discord_request()
+			.unwrap_err();
+		assert!(err.0.contains("requires content"));
+	}
+
+	#[test]
+	fn create_message_empty_content_is_rejected() {
+		let msg = CreateMessage::new(Id::new(42)).content("");
+		assert!(msg.validate().is_err());
+	}
+
+	#[test]
+	fn create_message_embed_only_passes() {
+		let msg = CreateMessage::new(Id::new(42)).embed(Embed::new());
+		assert!(msg.validate().is_ok());
+		assert!(msg.into_discord_request().is_ok());
+	}
+
+	#[test]
+	fn create_message_embed_with_file_url_is_rejected() {
+		let msg = CreateMessage::new(Id::new(42))
+			.embed(Embed::new().with_thumbnail("file:///etc/passwd"));
+		let err = msg.into_discord_request().unwrap_err();
+		assert!(err.0.contains("thumbnail"));
+	}
+
+	#[test]
+	fn create_message_component_only_passes() {
+		let msg = CreateMessage::new(Id::new(42))
+			.component_row(action_row(vec![button(1, "Click", "click")]));
+		assert!(msg.validate().is_ok());
+	}
+
+	#[test]
+	fn create_message_poll_serialises_as_poll() {
+		let msg = CreateMessage::new(Id::new(42))
+			.poll(CreatePoll::new("Q").answer("A1").answer("A2"));
+		let req = msg.into_discord_request().unwrap();
+		match req.body {
+			RequestBody::Json(body) => {
+				assert_eq!(body["poll"]["question"]["text"], "Q");
+				assert_eq!(
+					body["poll"]["answers"][1]["poll_media"]["text"],
+					"A2"
+				);
+				assert!(body.get("content").is_none());
+			}
+			other => panic!("expected JSON body, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn create_message_poll_without_answers_is_rejected() {
+		let msg = CreateMessage::new(Id::new(42)).poll(CreatePoll::new("Q"));
+		let err = msg.into_discord_request().unwrap_err();
+		assert!(err.0.contains("answers"));
+	}
+
 	// ---- CreateMessageWithFile -------------------------------------------
 
 	#[test]
@@ -1377,6 +1618,9 @@ mod tests {
 			.unwrap();
 		assert!(matches!(req.method, HttpMethod::Delete));
 		assert_eq!(req.path, "channels/10/messages/20");
+		// Shares the bucket of every delete in the channel.
+		assert_eq!(req.route_key, "DELETE /channels/10/messages");
+		assert!(matches!(req.body, RequestBody::None));
 	}
 
 	// ---- EditMessage -----------------------------------------------------
@@ -1400,6 +1644,31 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn edit_message_from_create_only_sends_set_fields() {
+		let create = CreateMessage::new(Id::new(10))
+			.content("3 votes")
+			.reply_to(Id::new(5));
+		let req = EditMessage::from_create(Id::new(10), Id::new(20), &create)
+			.into_discord_request()
+			.unwrap();
+		assert_eq!(req.path, "channels/10/messages/20");
+		let RequestBody::Json(body) = req.body else {
+			panic!("expected Json body");
+		};
+		assert_eq!(body, serde_json::json!({ "content": "3 votes" }));
+
+		// An empty edit mustn't clear anything.
+		let empty = CreateMessage::new(Id::new(10));
+		let req = EditMessage::from_create(Id::new(10), Id::new(20), &empty)
+			.into_discord_request()
+			.unwrap();
+		let RequestBody::Json(body) = req.body else {
+			panic!("expected Json body");
+		};
+		assert_eq!(body, serde_json::json!({}));
+	}
+
 	// ---- GetChannel ------------------------------------------------------
 
 	#[test]
@@ -1420,6 +1689,20 @@ mod tests {
 		assert_eq!(req.path, "channels/7/typing");
 	}
 
+	// ---- GetMessage ------------------------------------------------------
+
+	#[test]
+	fn get_message_path_and_route() {
+		let req = GetMessage::new(Id::new(1), Id::new(2))
+			.into_discord_request()
+			.unwrap();
+		assert!(matches!(req.method, HttpMethod::Get));
+		assert_eq!(req.path, "channels/1/messages/2");
+		// Message ids stay out of the route key so every message shares
+		// the channel's rate-limit bucket.
+		assert_eq!(req.route_key, "GET /channels/1/messages");
+	}
+
 	// ---- GetGuild --------------------------------------------------------
 
 	#[test]
@@ -1434,7 +1717,9 @@ mod tests {
 			.with_counts(false)
 			.into_discord_request()
 			.unwrap();
-		assert!(!req.path.contains("with_counts"));
+		assert_eq!(req.path, "guilds/3");
+		// Both share a rate-limit route.
+		assert_eq!(req.route_key, "GET /guilds/3");
 	}
 
 	// ---- GetGuildChannels ------------------------------------------------
@@ -1482,6 +1767,7 @@ mod tests {
 			.unwrap();
 		assert!(matches!(req.method, HttpMethod::Post));
 		assert_eq!(req.path, "interactions/1/token123/callback");
+		assert_eq!(req.route_key, "POST /interactions/1/callback");
 	}
 
 	// ---- SetGlobalCommands -----------------------------------------------
@@ -1495,6 +1781,15 @@ mod tests {
 		assert_eq!(req.path, "applications/1/commands");
 	}
 
+	// ---- GetCurrentApplication -------------------------------------------
+
+	#[test]
+	fn get_current_application_into_request() {
+		let req = GetCurrentApplication.into_discord_request().unwrap();
+		assert!(matches!(req.method, HttpMethod::Get));
+		assert_eq!(req.path, "applications/@me");
+	}
+
 	// ---- SetGuildCommands ------------------------------------------------
 
 	#[test]
@@ -1526,6 +1821,18 @@ mod tests {
 		assert!(req.path.contains("blob:12345"));
 	}
 
+	// ---- DeleteOwnReaction ----------------------------------------------
+
+	#[test]
+	fn delete_own_reaction_encodes_emoji() {
+		let req = DeleteOwnReaction::new(Id::new(1), Id::new(2), "✅")
+			.into_discord_request()
+			.unwrap();
+		assert!(matches!(req.method, HttpMethod::Delete));
+		assert_eq!(req.path, "channels/1/messages/2/reactions/%E2%9C%85/@me");
+		assert!(matches!(req.body, RequestBody::None));
+	}
+
 	// ---- DeleteAllReactions ----------------------------------------------
 
 	#[test]
@@ -1535,6 +1842,7 @@ mod tests {
 			.unwrap();
 		assert!(matches!(req.method, HttpMethod::Delete));
 		assert_eq!(req.path, "channels/1/messages/2/reactions");
+		assert_eq!(req.route_key, "DELETE /channels/1/messages/reactions");
 	}
 
 	// ---- Pins ------------------------------------------------------------
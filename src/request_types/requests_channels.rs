@@ -603,7 +603,8 @@ impl IntoDiscordRequest for DeleteUserReaction {
 
 // ---- DeleteAllReactionsForEmoji -------------------------------------------
 
-/// Remove all reactions of a specific emoji from a message.
+/// Remove all reactions of a specific emoji from a message. Requires
+/// `MANAGE_MESSAGES`.
 #[derive(Debug, Clone)]
 pub struct DeleteAllReactionsForEmoji {
 	channel_id: Id<ChannelMarker>,
@@ -811,4 +812,22 @@ mod tests {
 		assert!(dr.path.ends_with("/reactions/blobcat:999"));
 		assert_eq!(dr.route_key, "DELETE /channels/111/messages/reactions");
 	}
+
+	#[test]
+	fn delete_all_reactions_for_unicode_emoji() {
+		let dr =
+			DeleteAllReactionsForEmoji::new(Id::new(111), Id::new(222), "👍")
+				.into_discord_request()
+				.unwrap();
+		assert!(matches!(dr.method, HttpMethod::Delete));
+		assert_eq!(dr.path, "channels/111/messages/222/reactions/%F0%9F%91%8D");
+		// Shares a bucket with the other reaction deletes in the channel.
+		assert_eq!(
+			dr.route_key,
+			DeleteAllReactions::new(Id::new(111), Id::new(222))
+				.into_discord_request()
+				.unwrap()
+				.route_key
+		);
+	}
 }
@@ -9,10 +9,13 @@ use beet::prelude::*;
 use twilight_model::channel::Channel;
 use twilight_model::guild::Ban;
 use twilight_model::guild::Guild;
+use twilight_model::guild::GuildIntegration;
 use twilight_model::guild::GuildPreview;
 use twilight_model::guild::GuildPrune;
 use twilight_model::guild::Member;
 use twilight_model::guild::Role;
+use twilight_model::guild::widget::GuildWidget;
+use twilight_model::guild::widget::GuildWidgetSettings;
 use twilight_model::id::Id;
 use twilight_model::id::marker::ChannelMarker;
 use twilight_model::id::marker::GenericMarker;
@@ -334,6 +337,107 @@ impl IntoDiscordRequest for CreateGuildChannel {
 	}
 }
 
+// ---- UpdateGuildChannelPositions ------------------------------------------
+
+/// New position (and optionally parent) for one channel in a
+/// [`UpdateGuildChannelPositions`] request.
+#[derive(Debug, Clone, Serialize)]
+pub struct GuildChannelPosition {
+	pub id: Id<ChannelMarker>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub position: Option<u16>,
+	/// Sync permissions with the new parent when moving categories.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub lock_permissions: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub parent_id: Option<Id<ChannelMarker>>,
+}
+
+impl GuildChannelPosition {
+	pub fn new(id: Id<ChannelMarker>, position: u16) -> Self {
+		Self {
+			id,
+			position: Some(position),
+			lock_permissions: None,
+			parent_id: None,
+		}
+	}
+
+	/// Move the channel under another category.
+	pub fn parent_id(mut self, id: Id<ChannelMarker>) -> Self {
+		self.parent_id = Some(id);
+		self
+	}
+
+	/// Sync permissions with the new parent category.
+	pub fn lock_permissions(mut self, lock: bool) -> Self {
+		self.lock_permissions = Some(lock);
+		self
+	}
+}
+
+/// Reorder channels in a guild. Only the listed channels are moved.
+///
+/// ```ignore
+/// http.send(
+///     UpdateGuildChannelPositions::new(guild_id)
+///         .position(GuildChannelPosition::new(rules_id, 0))
+///         .position(GuildChannelPosition::new(general_id, 1)),
+/// ).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct UpdateGuildChannelPositions {
+	guild_id: Id<GuildMarker>,
+	positions: Vec<GuildChannelPosition>,
+}
+
+impl UpdateGuildChannelPositions {
+	pub fn new(guild_id: Id<GuildMarker>) -> Self {
+		Self {
+			guild_id,
+			positions: Vec::new(),
+		}
+	}
+
+	/// Append a channel to move.
+	pub fn position(mut self, position: GuildChannelPosition) -> Self {
+		self.positions.push(position);
+		self
+	}
+
+	/// Check there is at least one channel to move.
+	pub fn validate(&self) -> Result<(), JsonError> {
+		if self.positions.is_empty() {
+			Err(JsonError(
+				"UpdateGuildChannelPositions requires at least one channel"
+					.to_string(),
+			))
+		} else {
+			Ok(())
+		}
+	}
+}
+
+impl IntoDiscordRequest for UpdateGuildChannelPositions {
+	type Output = ();
+
+	fn into_discord_request(self) -> Result<DiscordRequest, JsonError> {
+		self.validate()?;
+		let path = format!("guilds/{}/channels", self.guild_id);
+		let route_key = format!("PATCH /guilds/{}/channels", self.guild_id);
+		Ok(DiscordRequest {
+			method: HttpMethod::Patch,
+			path,
+			route_key,
+			body: json_body(&self.positions)?,
+		})
+	}
+
+	fn parse_response(bytes: &[u8]) -> Result<(), JsonError> {
+		parse_empty(bytes)
+	}
+}
+
 // ---- GetGuildPreview ------------------------------------------------------
 
 /// Get a guild preview.
@@ -365,6 +469,73 @@ impl IntoDiscordRequest for GetGuildPreview {
 	}
 }
 
+// ---- GetGuildWidget -------------------------------------------------------
+
+/// Get a guild's public widget: name, online member count, voice channels
+/// and an invite if one is configured. The guild must have its widget
+/// enabled, in which case no auth is needed.
+#[derive(Debug, Clone)]
+pub struct GetGuildWidget {
+	guild_id: Id<GuildMarker>,
+}
+
+impl GetGuildWidget {
+	pub fn new(guild_id: Id<GuildMarker>) -> Self { Self { guild_id } }
+}
+
+impl IntoDiscordRequest for GetGuildWidget {
+	type Output = GuildWidget;
+
+	const AUTHENTICATED: bool = false;
+
+	fn into_discord_request(self) -> Result<DiscordRequest, JsonError> {
+		let path = format!("guilds/{}/widget.json", self.guild_id);
+		let route_key = format!("GET /guilds/{}/widget.json", self.guild_id);
+		Ok(DiscordRequest {
+			method: HttpMethod::Get,
+			path,
+			route_key,
+			body: RequestBody::None,
+		})
+	}
+
+	fn parse_response(bytes: &[u8]) -> Result<GuildWidget, JsonError> {
+		parse_json(bytes)
+	}
+}
+
+// ---- GetGuildWidgetSettings -----------------------------------------------
+
+/// Get whether a guild's widget is enabled and which channel its invite
+/// points to. Requires `MANAGE_GUILD`.
+#[derive(Debug, Clone)]
+pub struct GetGuildWidgetSettings {
+	guild_id: Id<GuildMarker>,
+}
+
+impl GetGuildWidgetSettings {
+	pub fn new(guild_id: Id<GuildMarker>) -> Self { Self { guild_id } }
+}
+
+impl IntoDiscordRequest for GetGuildWidgetSettings {
+	type Output = GuildWidgetSettings;
+
+	fn into_discord_request(self) -> Result<DiscordRequest, JsonError> {
+		let path = format!("guilds/{}/widget", self.guild_id);
+		let route_key = format!("GET /guilds/{}/widget", self.guild_id);
+		Ok(DiscordRequest {
+			method: HttpMethod::Get,
+			path,
+			route_key,
+			body: RequestBody::None,
+		})
+	}
+
+	fn parse_response(bytes: &[u8]) -> Result<GuildWidgetSettings, JsonError> {
+		parse_json(bytes)
+	}
+}
+
 // ---- GetGuildWebhooks -----------------------------------------------------
 
 /// Get all webhooks in a guild.
@@ -586,6 +757,10 @@ impl IntoDiscordRequest for RemoveGuildMember {
 // ---- UpdateGuildMember ----------------------------------------------------
 
 /// Update attributes of a guild member.
+///
+/// [`roles`](Self::roles) replaces the member's whole role list in one
+/// request, see
+/// [`DiscordHttpClient::set_member_roles`](crate::prelude::DiscordHttpClient::set_member_roles).
 #[derive(Debug, Clone, Serialize)]
 pub struct UpdateGuildMember {
 	#[serde(skip)]
@@ -1556,7 +1731,8 @@ impl IntoDiscordRequest for CreateGuildPrune {
 
 // ---- GetGuildIntegrations -------------------------------------------------
 
-/// Get guild integrations.
+/// Get a guild's integrations, e.g. Twitch or YouTube subscriber roles and
+/// installed applications. Requires `MANAGE_GUILD`.
 #[derive(Debug, Clone)]
 pub struct GetGuildIntegrations {
 	guild_id: Id<GuildMarker>,
@@ -1567,7 +1743,7 @@ impl GetGuildIntegrations {
 }
 
 impl IntoDiscordRequest for GetGuildIntegrations {
-	type Output = Vec<serde_json::Value>;
+	type Output = Vec<GuildIntegration>;
 
 	fn into_discord_request(self) -> Result<DiscordRequest, JsonError> {
 		let path = format!("guilds/{}/integrations", self.guild_id);
@@ -1582,7 +1758,7 @@ impl IntoDiscordRequest for GetGuildIntegrations {
 
 	fn parse_response(
 		bytes: &[u8],
-	) -> Result<Vec<serde_json::Value>, JsonError> {
+	) -> Result<Vec<GuildIntegration>, JsonError> {
 		parse_json(bytes)
 	}
 }
@@ -1630,3 +1806,187 @@ impl IntoDiscordRequest for DeleteGuildIntegration {
 		parse_empty(bytes)
 	}
 }
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use twilight_model::guild::GuildIntegrationType;
+
+	// ---- UpdateGuildChannelPositions -------------------------------------
+
+	#[test]
+	fn update_channel_positions_serialises_array() {
+		let req = UpdateGuildChannelPositions::new(Id::new(10))
+			.position(GuildChannelPosition::new(Id::new(1), 0))
+			.position(
+				GuildChannelPosition::new(Id::new(2), 1)
+					.parent_id(Id::new(3))
+					.lock_permissions(true),
+			)
+			.into_discord_request()
+			.unwrap();
+		assert!(matches!(req.method, HttpMethod::Patch));
+		assert_eq!(req.path, "guilds/10/channels");
+		assert_eq!(req.route_key, "PATCH /guilds/10/channels");
+		match req.body {
+			RequestBody::Json(body) => assert_eq!(
+				body,
+				serde_json::json!([
+					{ "id": "1", "position": 0 },
+					{
+						"id": "2",
+						"position": 1,
+						"lock_permissions": true,
+						"parent_id": "3",
+					},
+				])
+			),
+			other => panic!("expected JSON body, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn update_channel_positions_rejects_empty() {
+		let err = UpdateGuildChannelPositions::new(Id::new(10))
+			.into_discord_request()
+			.unwrap_err();
+		assert!(err.0.contains("at least one channel"));
+	}
+
+	// ---- UpdateGuildMember -----------------------------------------------
+
+	#[test]
+	fn update_guild_member_serializes_roles() {
+		let req = UpdateGuildMember::new(Id::new(10), Id::new(20))
+			.roles(Some(vec![Id::new(111), Id::new(222)]));
+		let json = serde_json::to_value(&req).unwrap();
+		assert_eq!(json, serde_json::json!({ "roles": ["111", "222"] }));
+
+		let cleared = UpdateGuildMember::new(Id::new(10), Id::new(20))
+			.roles(Some(Vec::new()))
+			.nick(None);
+		let json = serde_json::to_value(&cleared).unwrap();
+		assert_eq!(json, serde_json::json!({ "roles": [], "nick": null }));
+	}
+
+	#[test]
+	fn update_guild_member_into_request() {
+		let req = UpdateGuildMember::new(Id::new(10), Id::new(20))
+			.mute(true)
+			.into_discord_request()
+			.unwrap();
+		assert!(matches!(req.method, HttpMethod::Patch));
+		assert_eq!(req.path, "guilds/10/members/20");
+		// Shares a bucket with every member of the guild.
+		assert_eq!(req.route_key, "PATCH /guilds/10/members");
+	}
+
+	// ---- GetGuildMember --------------------------------------------------
+
+	#[test]
+	fn get_guild_member_into_request() {
+		let req = GetGuildMember::new(Id::new(10), Id::new(20))
+			.into_discord_request()
+			.unwrap();
+		assert!(matches!(req.method, HttpMethod::Get));
+		assert_eq!(req.path, "guilds/10/members/20");
+		assert_eq!(req.route_key, "GET /guilds/10/members");
+		assert!(matches!(req.body, RequestBody::None));
+	}
+
+	#[test]
+	fn get_guild_member_parses_member() {
+		let bytes = br#"{
+			"deaf": false,
+			"mute": false,
+			"flags": 0,
+			"joined_at": "2021-01-01T00:00:00.000000+00:00",
+			"nick": "ally",
+			"communication_disabled_until": null,
+			"roles": ["111", "222"],
+			"user": {
+				"id": "789",
+				"username": "alice",
+				"discriminator": "0",
+				"avatar": null
+			}
+		}"#;
+		let member = GetGuildMember::parse_response(bytes).unwrap();
+		assert_eq!(member.user.id.get(), 789);
+		assert_eq!(member.nick.as_deref(), Some("ally"));
+		let roles: Vec<u64> = member.roles.iter().map(|id| id.get()).collect();
+		assert_eq!(roles, vec![111, 222]);
+	}
+
+	#[test]
+	fn get_guild_widget_is_unauthenticated() {
+		assert!(!GetGuildWidget::AUTHENTICATED);
+		assert!(GetGuildWidgetSettings::AUTHENTICATED);
+		let req = GetGuildWidget::new(Id::new(10))
+			.into_discord_request()
+			.unwrap();
+		assert_eq!(req.path, "guilds/10/widget.json");
+		assert_eq!(req.route_key, "GET /guilds/10/widget.json");
+	}
+
+	#[test]
+	fn get_guild_widget_parses_widget() {
+		let bytes = br#"{
+			"id": "10",
+			"name": "hello",
+			"instant_invite": null,
+			"channels": [
+				{ "id": "20", "name": "General", "position": 0 }
+			],
+			"members": [{
+				"id": "0",
+				"username": "alice",
+				"discriminator": "0000",
+				"avatar": null,
+				"avatar_url": "https://cdn.discordapp.com/widget-avatars/a",
+				"status": "online"
+			}],
+			"presence_count": 1
+		}"#;
+		let widget = GetGuildWidget::parse_response(bytes).unwrap();
+		assert_eq!(widget.id.get(), 10);
+		assert_eq!(widget.presence_count, 1);
+		assert_eq!(widget.channels[0].name, "General");
+		assert_eq!(widget.members[0].name, "alice");
+	}
+
+	#[test]
+	fn get_guild_integrations_parses_entries() {
+		let bytes = br#"[
+			{
+				"id": "30",
+				"name": "streamer",
+				"type": "twitch",
+				"enabled": true,
+				"syncing": true,
+				"role_id": "40",
+				"expire_behavior": 0,
+				"expire_grace_period": 7,
+				"account": { "id": "twitch-id", "name": "streamer" }
+			},
+			{
+				"id": "31",
+				"name": "helper",
+				"type": "discord",
+				"enabled": true,
+				"account": { "id": "50", "name": "helper" }
+			}
+		]"#;
+		let integrations = GetGuildIntegrations::parse_response(bytes).unwrap();
+		assert_eq!(integrations.len(), 2);
+		assert_eq!(integrations[0].kind, GuildIntegrationType::Twitch);
+		assert_eq!(integrations[0].account.name, "streamer");
+		assert_eq!(integrations[0].role_id.map(|id| id.get()), Some(40));
+		assert_eq!(integrations[1].kind, GuildIntegrationType::Discord);
+		assert_eq!(integrations[1].enabled, Some(true));
+	}
+}
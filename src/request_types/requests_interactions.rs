@@ -51,8 +51,11 @@ impl IntoDiscordRequest for GetOriginalInteractionResponse {
 			"webhooks/{}/{}/messages/@original",
 			self.application_id, self.interaction_token
 		);
-		let route_key =
-			"GET /webhooks/interaction/messages/@original".to_string();
+		let route_key = format!(
+			"GET /webhooks/{}/{}/messages/@original",
+			self.application_id,
+			token_route_id(&self.interaction_token)
+		);
 		Ok(DiscordRequest {
 			method: HttpMethod::Get,
 			path,
@@ -95,8 +98,11 @@ impl IntoDiscordRequest for DeleteOriginalInteractionResponse {
 			"webhooks/{}/{}/messages/@original",
 			self.application_id, self.interaction_token
 		);
-		let route_key =
-			"DELETE /webhooks/interaction/messages/@original".to_string();
+		let route_key = format!(
+			"DELETE /webhooks/{}/{}/messages/@original",
+			self.application_id,
+			token_route_id(&self.interaction_token)
+		);
 		Ok(DiscordRequest {
 			method: HttpMethod::Delete,
 			path,
@@ -177,7 +183,11 @@ impl IntoDiscordRequest for CreateFollowup {
 			"webhooks/{}/{}",
 			self.application_id, self.interaction_token
 		);
-		let route_key = "POST /webhooks/interaction".to_string();
+		let route_key = format!(
+			"POST /webhooks/{}/{}",
+			self.application_id,
+			token_route_id(&self.interaction_token)
+		);
 		Ok(DiscordRequest {
 			method: HttpMethod::Post,
 			path,
@@ -223,7 +233,11 @@ impl IntoDiscordRequest for GetFollowup {
 			"webhooks/{}/{}/messages/{}",
 			self.application_id, self.interaction_token, self.message_id
 		);
-		let route_key = "GET /webhooks/interaction/messages".to_string();
+		let route_key = format!(
+			"GET /webhooks/{}/{}/messages",
+			self.application_id,
+			token_route_id(&self.interaction_token)
+		);
 		Ok(DiscordRequest {
 			method: HttpMethod::Get,
 			path,
@@ -254,6 +268,8 @@ pub struct UpdateFollowup {
 	pub embeds: Option<Vec<Embed>>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub components: Option<Vec<Component>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub flags: Option<u64>,
 }
 
 impl UpdateFollowup {
@@ -269,6 +285,7 @@ impl UpdateFollowup {
 			content: None,
 			embeds: None,
 			components: None,
+			flags: None,
 		}
 	}
 
@@ -286,6 +303,13 @@ impl UpdateFollowup {
 		self.components.get_or_insert_with(Vec::new).push(row);
 		self
 	}
+
+	/// Set message flags, as for
+	/// [`EditOriginalInteractionResponse::flags`].
+	pub fn flags(mut self, flags: u64) -> Self {
+		self.flags = Some(flags);
+		self
+	}
 }
 
 impl IntoDiscordRequest for UpdateFollowup {
@@ -296,7 +320,11 @@ impl IntoDiscordRequest for UpdateFollowup {
 			"webhooks/{}/{}/messages/{}",
 			self.application_id, self.interaction_token, self.message_id
 		);
-		let route_key = "PATCH /webhooks/interaction/messages".to_string();
+		let route_key = format!(
+			"PATCH /webhooks/{}/{}/messages",
+			self.application_id,
+			token_route_id(&self.interaction_token)
+		);
 		Ok(DiscordRequest {
 			method: HttpMethod::Patch,
 			path,
@@ -342,7 +370,11 @@ impl IntoDiscordRequest for DeleteFollowup {
 			"webhooks/{}/{}/messages/{}",
 			self.application_id, self.interaction_token, self.message_id
 		);
-		let route_key = "DELETE /webhooks/interaction/messages".to_string();
+		let route_key = format!(
+			"DELETE /webhooks/{}/{}/messages",
+			self.application_id,
+			token_route_id(&self.interaction_token)
+		);
 		Ok(DiscordRequest {
 			method: HttpMethod::Delete,
 			path,
@@ -440,6 +472,102 @@ impl IntoDiscordRequest for GetGlobalCommand {
 	}
 }
 
+// ---- CreateGlobalCommand --------------------------------------------------
+
+/// Create a single global application command.
+///
+/// Creating a command with the same name as an existing one overwrites it.
+///
+/// ```ignore
+/// let cmd = Command::chat_input("ping", "Check latency");
+/// let created: Command =
+///     http.send(CreateGlobalCommand::new(app_id, cmd)).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct CreateGlobalCommand {
+	application_id: Id<ApplicationMarker>,
+	command: ApplicationCommand,
+}
+
+impl CreateGlobalCommand {
+	pub fn new(
+		application_id: Id<ApplicationMarker>,
+		command: ApplicationCommand,
+	) -> Self {
+		Self {
+			application_id,
+			command,
+		}
+	}
+}
+
+impl IntoDiscordRequest for CreateGlobalCommand {
+	type Output = ApplicationCommand;
+
+	fn into_discord_request(self) -> Result<DiscordRequest, JsonError> {
+		let path = format!("applications/{}/commands", self.application_id);
+		let route_key =
+			format!("POST /applications/{}/commands", self.application_id);
+		Ok(DiscordRequest {
+			method: HttpMethod::Post,
+			path,
+			route_key,
+			body: json_body(&self.command)?,
+		})
+	}
+
+	fn parse_response(bytes: &[u8]) -> Result<ApplicationCommand, JsonError> {
+		parse_json(bytes)
+	}
+}
+
+// ---- UpdateGlobalCommand --------------------------------------------------
+
+/// Edit a single global application command in place.
+#[derive(Debug, Clone)]
+pub struct UpdateGlobalCommand {
+	application_id: Id<ApplicationMarker>,
+	command_id: Id<CommandMarker>,
+	command: ApplicationCommand,
+}
+
+impl UpdateGlobalCommand {
+	pub fn new(
+		application_id: Id<ApplicationMarker>,
+		command_id: Id<CommandMarker>,
+		command: ApplicationCommand,
+	) -> Self {
+		Self {
+			application_id,
+			command_id,
+			command,
+		}
+	}
+}
+
+impl IntoDiscordRequest for UpdateGlobalCommand {
+	type Output = ApplicationCommand;
+
+	fn into_discord_request(self) -> Result<DiscordRequest, JsonError> {
+		let path = format!(
+			"applications/{}/commands/{}",
+			self.application_id, self.command_id
+		);
+		let route_key =
+			format!("PATCH /applications/{}/commands", self.application_id);
+		Ok(DiscordRequest {
+			method: HttpMethod::Patch,
+			path,
+			route_key,
+			body: json_body(&self.command)?,
+		})
+	}
+
+	fn parse_response(bytes: &[u8]) -> Result<ApplicationCommand, JsonError> {
+		parse_json(bytes)
+	}
+}
+
 // ---- DeleteGlobalCommand --------------------------------------------------
 
 /// Delete a global application command.
@@ -585,6 +713,107 @@ impl IntoDiscordRequest for GetGuildCommand {
 	}
 }
 
+// ---- CreateGuildCommand ---------------------------------------------------
+
+/// Create a single guild-scoped application command.
+#[derive(Debug, Clone)]
+pub struct CreateGuildCommand {
+	application_id: Id<ApplicationMarker>,
+	guild_id: Id<GuildMarker>,
+	command: ApplicationCommand,
+}
+
+impl CreateGuildCommand {
+	pub fn new(
+		application_id: Id<ApplicationMarker>,
+		guild_id: Id<GuildMarker>,
+		command: ApplicationCommand,
+	) -> Self {
+		Self {
+			application_id,
+			guild_id,
+			command,
+		}
+	}
+}
+
+impl IntoDiscordRequest for CreateGuildCommand {
+	type Output = ApplicationCommand;
+
+	fn into_discord_request(self) -> Result<DiscordRequest, JsonError> {
+		let path = format!(
+			"applications/{}/guilds/{}/commands",
+			self.application_id, self.guild_id
+		);
+		let route_key = format!(
+			"POST /applications/{}/guilds/{}/commands",
+			self.application_id, self.guild_id
+		);
+		Ok(DiscordRequest {
+			method: HttpMethod::Post,
+			path,
+			route_key,
+			body: json_body(&self.command)?,
+		})
+	}
+
+	fn parse_response(bytes: &[u8]) -> Result<ApplicationCommand, JsonError> {
+		parse_json(bytes)
+	}
+}
+
+// ---- UpdateGuildCommand ---------------------------------------------------
+
+/// Edit a single guild-scoped application command in place.
+#[derive(Debug, Clone)]
+pub struct UpdateGuildCommand {
+	application_id: Id<ApplicationMarker>,
+	guild_id: Id<GuildMarker>,
+	command_id: Id<CommandMarker>,
+	command: ApplicationCommand,
+}
+
+impl UpdateGuildCommand {
+	pub fn new(
+		application_id: Id<ApplicationMarker>,
+		guild_id: Id<GuildMarker>,
+		command_id: Id<CommandMarker>,
+		command: ApplicationCommand,
+	) -> Self {
+		Self {
+			application_id,
+			guild_id,
+			command_id,
+			command,
+		}
+	}
+}
+
+impl IntoDiscordRequest for UpdateGuildCommand {
+	type Output = ApplicationCommand;
+
+	fn into_discord_request(self) -> Result<DiscordRequest, JsonError> {
+		let path = format!(
+			"applications/{}/guilds/{}/commands/{}",
+			self.application_id, self.guild_id, self.command_id
+		);
+		let route_key = format!(
+			"PATCH /applications/{}/guilds/{}/commands",
+			self.application_id, self.guild_id
+		);
+		Ok(DiscordRequest {
+			method: HttpMethod::Patch,
+			path,
+			route_key,
+			body: json_body(&self.command)?,
+		})
+	}
+
+	fn parse_response(bytes: &[u8]) -> Result<ApplicationCommand, JsonError> {
+		parse_json(bytes)
+	}
+}
+
 // ---- DeleteGuildCommand ---------------------------------------------------
 
 /// Delete a guild-scoped application command.
@@ -760,7 +989,10 @@ mod tests {
 		assert_eq!(req.path, "webhooks/100/tok123/messages/@original");
 		assert_eq!(
 			req.route_key,
-			"GET /webhooks/interaction/messages/@original"
+			format!(
+				"GET /webhooks/100/{}/messages/@original",
+				token_route_id("tok123")
+			)
 		);
 		assert!(matches!(req.method, HttpMethod::Get));
 		assert!(matches!(req.body, RequestBody::None));
@@ -774,12 +1006,27 @@ mod tests {
 		assert_eq!(req.path, "webhooks/100/tok123/messages/@original");
 		assert_eq!(
 			req.route_key,
-			"DELETE /webhooks/interaction/messages/@original"
+			format!(
+				"DELETE /webhooks/100/{}/messages/@original",
+				token_route_id("tok123")
+			)
 		);
 		assert!(matches!(req.method, HttpMethod::Delete));
 		assert!(matches!(req.body, RequestBody::None));
 	}
 
+	#[test]
+	fn followup_route_keys_differ_per_interaction() {
+		let a = CreateFollowup::new(app_id(), "tok-a")
+			.into_discord_request()
+			.unwrap();
+		let b = CreateFollowup::new(app_id(), "tok-b")
+			.into_discord_request()
+			.unwrap();
+		assert_ne!(a.route_key, b.route_key);
+		assert!(!a.route_key.contains("tok-a"));
+	}
+
 	// ---- Followups ----
 
 	#[test]
@@ -790,7 +1037,10 @@ mod tests {
 			.into_discord_request()
 			.unwrap();
 		assert_eq!(req.path, "webhooks/100/tok123");
-		assert_eq!(req.route_key, "POST /webhooks/interaction");
+		assert_eq!(
+			req.route_key,
+			format!("POST /webhooks/100/{}", token_route_id("tok123"))
+		);
 		assert!(matches!(req.method, HttpMethod::Post));
 		match &req.body {
 			RequestBody::Json(v) => {
@@ -823,7 +1073,10 @@ mod tests {
 			.into_discord_request()
 			.unwrap();
 		assert_eq!(req.path, "webhooks/100/tok123/messages/400");
-		assert_eq!(req.route_key, "GET /webhooks/interaction/messages");
+		assert_eq!(
+			req.route_key,
+			format!("GET /webhooks/100/{}/messages", token_route_id("tok123"))
+		);
 		assert!(matches!(req.method, HttpMethod::Get));
 		assert!(matches!(req.body, RequestBody::None));
 	}
@@ -835,7 +1088,13 @@ mod tests {
 			.into_discord_request()
 			.unwrap();
 		assert_eq!(req.path, "webhooks/100/tok123/messages/400");
-		assert_eq!(req.route_key, "PATCH /webhooks/interaction/messages");
+		assert_eq!(
+			req.route_key,
+			format!(
+				"PATCH /webhooks/100/{}/messages",
+				token_route_id("tok123")
+			)
+		);
 		assert!(matches!(req.method, HttpMethod::Patch));
 		match &req.body {
 			RequestBody::Json(v) => {
@@ -851,7 +1110,13 @@ mod tests {
 			.into_discord_request()
 			.unwrap();
 		assert_eq!(req.path, "webhooks/100/tok123/messages/400");
-		assert_eq!(req.route_key, "DELETE /webhooks/interaction/messages");
+		assert_eq!(
+			req.route_key,
+			format!(
+				"DELETE /webhooks/100/{}/messages",
+				token_route_id("tok123")
+			)
+		);
 		assert!(matches!(req.method, HttpMethod::Delete));
 		assert!(matches!(req.body, RequestBody::None));
 	}
@@ -879,6 +1144,33 @@ mod tests {
 		assert!(matches!(req.method, HttpMethod::Get));
 	}
 
+	#[test]
+	fn create_global_command_into_request() {
+		let cmd = ApplicationCommand::chat_input("ping", "Check latency");
+		let req = CreateGlobalCommand::new(app_id(), cmd)
+			.into_discord_request()
+			.unwrap();
+		assert_eq!(req.path, "applications/100/commands");
+		assert_eq!(req.route_key, "POST /applications/100/commands");
+		assert!(matches!(req.method, HttpMethod::Post));
+		match &req.body {
+			RequestBody::Json(v) => assert_eq!(v["name"], "ping"),
+			_ => panic!("expected Json body"),
+		}
+	}
+
+	#[test]
+	fn update_global_command_into_request() {
+		let cmd = ApplicationCommand::chat_input("ping", "Check latency");
+		let req = UpdateGlobalCommand::new(app_id(), cmd_id(), cmd)
+			.into_discord_request()
+			.unwrap();
+		assert_eq!(req.path, "applications/100/commands/300");
+		assert_eq!(req.route_key, "PATCH /applications/100/commands");
+		assert!(matches!(req.method, HttpMethod::Patch));
+		assert!(matches!(req.body, RequestBody::Json(_)));
+	}
+
 	#[test]
 	fn delete_global_command_into_request() {
 		let req = DeleteGlobalCommand::new(app_id(), cmd_id())
@@ -911,6 +1203,33 @@ mod tests {
 		assert_eq!(req.route_key, "GET /applications/100/guilds/200/commands");
 	}
 
+	#[test]
+	fn create_guild_command_into_request() {
+		let cmd = ApplicationCommand::chat_input("ping", "Check latency");
+		let req = CreateGuildCommand::new(app_id(), guild_id(), cmd)
+			.into_discord_request()
+			.unwrap();
+		assert_eq!(req.path, "applications/100/guilds/200/commands");
+		assert_eq!(req.route_key, "POST /applications/100/guilds/200/commands");
+		assert!(matches!(req.method, HttpMethod::Post));
+		assert!(matches!(req.body, RequestBody::Json(_)));
+	}
+
+	#[test]
+	fn update_guild_command_into_request() {
+		let cmd = ApplicationCommand::chat_input("ping", "Check latency");
+		let req = UpdateGuildCommand::new(app_id(), guild_id(), cmd_id(), cmd)
+			.into_discord_request()
+			.unwrap();
+		assert_eq!(req.path, "applications/100/guilds/200/commands/300");
+		assert_eq!(
+			req.route_key,
+			"PATCH /applications/100/guilds/200/commands"
+		);
+		assert!(matches!(req.method, HttpMethod::Patch));
+		assert!(matches!(req.body, RequestBody::Json(_)));
+	}
+
 	#[test]
 	fn delete_guild_command_into_request() {
 		let req = DeleteGuildCommand::new(app_id(), guild_id(), cmd_id())
@@ -208,6 +208,12 @@ impl IntoDiscordRequest for UpdateWebhook {
 // ---- ExecuteWebhook -------------------------------------------------------
 
 /// Execute a webhook (send a message via webhook).
+///
+/// Only webhooks created by an application (the bot's own, or interaction
+/// followups) may send [`components`](Self::components); other webhooks get
+/// a 400. Send through
+/// [`DiscordHttpClient::execute_webhook`](crate::prelude::DiscordHttpClient::execute_webhook)
+/// to have that cause named in the error.
 #[derive(Debug, Clone, Serialize)]
 pub struct ExecuteWebhook {
 	#[serde(skip)]
@@ -226,6 +232,10 @@ pub struct ExecuteWebhook {
 	pub username: Option<String>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub avatar_url: Option<String>,
+	/// Name of the forum post to create, see
+	/// [`thread_name`](Self::thread_name).
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub thread_name: Option<String>,
 }
 
 impl ExecuteWebhook {
@@ -242,6 +252,7 @@ impl ExecuteWebhook {
 			components: None,
 			username: None,
 			avatar_url: None,
+			thread_name: None,
 		}
 	}
 
@@ -257,12 +268,19 @@ impl ExecuteWebhook {
 		self
 	}
 
-	/// Append a component row.
+	/// Append a component row. Requires an application-owned webhook.
 	pub fn component_row(mut self, row: Component) -> Self {
 		self.components.get_or_insert_with(Vec::new).push(row);
 		self
 	}
 
+	/// Whether any component rows were added.
+	pub fn has_components(&self) -> bool {
+		self.components
+			.as_ref()
+			.is_some_and(|rows| !rows.is_empty())
+	}
+
 	/// Override the webhook's default username.
 	pub fn username(mut self, username: impl Into<String>) -> Self {
 		self.username = Some(username.into());
@@ -275,6 +293,14 @@ impl ExecuteWebhook {
 		self
 	}
 
+	/// Create a new post named `name` in the webhook's forum or media
+	/// channel, with this message as its first message. Other channel types
+	/// reject it.
+	pub fn thread_name(mut self, name: impl Into<String>) -> Self {
+		self.thread_name = Some(name.into());
+		self
+	}
+
 	/// If true, the response will include a Message object.
 	pub fn wait_(mut self, wait: bool) -> Self {
 		self.wait_ = wait;
@@ -293,7 +319,8 @@ impl IntoDiscordRequest for ExecuteWebhook {
 		);
 		let route_key = format!(
 			"POST /webhooks/{}/{}",
-			self.webhook_id, self.webhook_token
+			self.webhook_id,
+			token_route_id(&self.webhook_token)
 		);
 		Ok(DiscordRequest {
 			method: HttpMethod::Post,
@@ -342,7 +369,8 @@ impl IntoDiscordRequest for GetWebhookMessage {
 		);
 		let route_key = format!(
 			"GET /webhooks/{}/{}/messages",
-			self.webhook_id, self.webhook_token
+			self.webhook_id,
+			token_route_id(&self.webhook_token)
 		);
 		Ok(DiscordRequest {
 			method: HttpMethod::Get,
@@ -421,7 +449,8 @@ impl IntoDiscordRequest for UpdateWebhookMessage {
 		);
 		let route_key = format!(
 			"PATCH /webhooks/{}/{}/messages",
-			self.webhook_id, self.webhook_token
+			self.webhook_id,
+			token_route_id(&self.webhook_token)
 		);
 		Ok(DiscordRequest {
 			method: HttpMethod::Patch,
@@ -470,7 +499,8 @@ impl IntoDiscordRequest for DeleteWebhookMessage {
 		);
 		let route_key = format!(
 			"DELETE /webhooks/{}/{}/messages",
-			self.webhook_id, self.webhook_token
+			self.webhook_id,
+			token_route_id(&self.webhook_token)
 		);
 		Ok(DiscordRequest {
 			method: HttpMethod::Delete,
@@ -749,6 +779,123 @@ impl IntoDiscordRequest for CreateForumThread {
 	}
 }
 
+// ---- UpdateThread ---------------------------------------------------------
+
+/// Auto-archive durations (in minutes) Discord accepts for threads.
+pub const THREAD_AUTO_ARCHIVE_DURATIONS: [u16; 4] = [60, 1440, 4320, 10080];
+
+/// Edit a thread's name or metadata (archive/lock state, auto-archive
+/// duration, etc.).
+///
+/// ```ignore
+/// let thread: Channel = http.send(
+///     UpdateThread::new(thread_id).archived(true).locked(true)
+/// ).await?;
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateThread {
+	#[serde(skip)]
+	channel_id: Id<ChannelMarker>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub archived: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub locked: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub auto_archive_duration: Option<u16>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub invitable: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub rate_limit_per_user: Option<u16>,
+}
+
+impl UpdateThread {
+	pub fn new(channel_id: Id<ChannelMarker>) -> Self {
+		Self {
+			channel_id,
+			name: None,
+			archived: None,
+			locked: None,
+			auto_archive_duration: None,
+			invitable: None,
+			rate_limit_per_user: None,
+		}
+	}
+
+	/// Rename the thread.
+	pub fn name(mut self, name: impl Into<String>) -> Self {
+		self.name = Some(name.into());
+		self
+	}
+
+	/// Archive or unarchive the thread.
+	pub fn archived(mut self, archived: bool) -> Self {
+		self.archived = Some(archived);
+		self
+	}
+
+	/// Lock the thread so only moderators can unarchive it.
+	pub fn locked(mut self, locked: bool) -> Self {
+		self.locked = Some(locked);
+		self
+	}
+
+	/// Set auto-archive duration in minutes, one of
+	/// [`THREAD_AUTO_ARCHIVE_DURATIONS`].
+	pub fn auto_archive_duration(mut self, minutes: u16) -> Self {
+		self.auto_archive_duration = Some(minutes);
+		self
+	}
+
+	/// Whether non-moderators can add other non-moderators (private threads).
+	pub fn invitable(mut self, invitable: bool) -> Self {
+		self.invitable = Some(invitable);
+		self
+	}
+
+	/// Slowmode rate limit per user in seconds.
+	pub fn rate_limit_per_user(mut self, seconds: u16) -> Self {
+		self.rate_limit_per_user = Some(seconds);
+		self
+	}
+
+	/// Check the auto-archive duration is one Discord accepts.
+	pub fn validate(&self) -> Result<(), JsonError> {
+		match self.auto_archive_duration {
+			Some(minutes)
+				if !THREAD_AUTO_ARCHIVE_DURATIONS.contains(&minutes) =>
+			{
+				Err(JsonError(format!(
+					"invalid auto_archive_duration {}, expected one of {:?}",
+					minutes, THREAD_AUTO_ARCHIVE_DURATIONS
+				)))
+			}
+			_ => Ok(()),
+		}
+	}
+}
+
+impl IntoDiscordRequest for UpdateThread {
+	type Output = Channel;
+
+	fn into_discord_request(self) -> Result<DiscordRequest, JsonError> {
+		self.validate()?;
+		let path = format!("channels/{}", self.channel_id);
+		let route_key = format!("PATCH /channels/{}", self.channel_id);
+		Ok(DiscordRequest {
+			method: HttpMethod::Patch,
+			path,
+			route_key,
+			body: json_body(&self)?,
+		})
+	}
+
+	fn parse_response(bytes: &[u8]) -> Result<Channel, JsonError> {
+		parse_json(bytes)
+	}
+}
+
 // ---- JoinThread -----------------------------------------------------------
 
 /// Join a thread (add current user).
@@ -2504,6 +2651,35 @@ impl IntoDiscordRequest for GetVoiceRegions {
 	}
 }
 
+// ---- GetGuildVoiceRegions -------------------------------------------------
+
+/// List voice regions for a guild, including VIP regions it has access to.
+#[derive(Debug, Clone)]
+pub struct GetGuildVoiceRegions {
+	guild_id: Id<GuildMarker>,
+}
+
+impl GetGuildVoiceRegions {
+	pub fn new(guild_id: Id<GuildMarker>) -> Self { Self { guild_id } }
+}
+
+impl IntoDiscordRequest for GetGuildVoiceRegions {
+	type Output = Vec<VoiceRegion>;
+
+	fn into_discord_request(self) -> Result<DiscordRequest, JsonError> {
+		Ok(DiscordRequest {
+			method: HttpMethod::Get,
+			path: format!("guilds/{}/regions", self.guild_id),
+			route_key: format!("GET /guilds/{}/regions", self.guild_id),
+			body: RequestBody::None,
+		})
+	}
+
+	fn parse_response(bytes: &[u8]) -> Result<Vec<VoiceRegion>, JsonError> {
+		parse_json(bytes)
+	}
+}
+
 // ---- GetAuditLog ----------------------------------------------------------
 
 /// Get a guild's audit log.
@@ -2788,3 +2964,194 @@ impl IntoDiscordRequest for GetGuildOnboarding {
 		parse_json(bytes)
 	}
 }
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// ---- ExecuteWebhook --------------------------------------------------
+
+	#[test]
+	fn execute_webhook_serializes_components() {
+		let req = ExecuteWebhook::new(Id::new(5), "tok")
+			.content("pick one")
+			.component_row(action_row(vec![button(1, "Yes", "vote:yes")]));
+		assert!(req.has_components());
+		let json = serde_json::to_value(&req).unwrap();
+		assert_eq!(json["content"], "pick one");
+		assert_eq!(json["components"][0]["type"], 1);
+		assert_eq!(
+			json["components"][0]["components"][0]["custom_id"],
+			"vote:yes"
+		);
+		// Routing fields stay out of the body.
+		assert!(json.get("webhook_token").is_none());
+	}
+
+	#[test]
+	fn execute_webhook_omits_empty_components() {
+		let req = ExecuteWebhook::new(Id::new(5), "tok").content("hi");
+		assert!(!req.has_components());
+		let json = serde_json::to_value(&req).unwrap();
+		assert!(json.get("components").is_none());
+		assert!(json.get("thread_name").is_none());
+	}
+
+	#[test]
+	fn execute_webhook_serializes_thread_name() {
+		let req = ExecuteWebhook::new(Id::new(5), "tok")
+			.content("First post")
+			.thread_name("Release notes");
+		let json = serde_json::to_value(&req).unwrap();
+		assert_eq!(json["thread_name"], "Release notes");
+		assert_eq!(json["content"], "First post");
+	}
+
+	// ---- GetGuildVoiceRegions --------------------------------------------
+
+	#[test]
+	fn get_guild_voice_regions_into_request() {
+		let req = GetGuildVoiceRegions::new(Id::new(10))
+			.into_discord_request()
+			.unwrap();
+		assert!(matches!(req.method, HttpMethod::Get));
+		assert_eq!(req.path, "guilds/10/regions");
+		assert_eq!(req.route_key, "GET /guilds/10/regions");
+	}
+
+	#[test]
+	fn get_guild_voice_regions_parses_regions() {
+		let bytes = br#"[{
+			"id": "us-west",
+			"name": "US West",
+			"optimal": true,
+			"deprecated": false,
+			"custom": false
+		}]"#;
+		let regions = GetGuildVoiceRegions::parse_response(bytes).unwrap();
+		assert_eq!(regions.len(), 1);
+		assert_eq!(regions[0].id, "us-west");
+		assert!(regions[0].optimal);
+	}
+
+	// ---- Invites ---------------------------------------------------------
+
+	#[test]
+	fn create_invite_serializes_options() {
+		let invite = CreateInvite::new(Id::new(77))
+			.max_age(3600)
+			.max_uses(10)
+			.temporary(true)
+			.unique(true);
+		let json = serde_json::to_value(&invite).unwrap();
+		assert_eq!(
+			json,
+			serde_json::json!({
+				"max_age": 3600,
+				"max_uses": 10,
+				"temporary": true,
+				"unique": true,
+			})
+		);
+	}
+
+	#[test]
+	fn create_invite_empty_body_is_object() {
+		let req = CreateInvite::new(Id::new(77))
+			.into_discord_request()
+			.unwrap();
+		assert_eq!(req.path, "channels/77/invites");
+		assert_eq!(req.route_key, "POST /channels/77/invites");
+		match req.body {
+			RequestBody::Json(v) => assert_eq!(v, serde_json::json!({})),
+			other => panic!("expected Json body, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn get_guild_invites_into_request() {
+		let req = GetGuildInvites::new(Id::new(5))
+			.into_discord_request()
+			.unwrap();
+		assert!(matches!(req.method, HttpMethod::Get));
+		assert_eq!(req.path, "guilds/5/invites");
+	}
+
+	#[test]
+	fn invite_deserializes_tracking_fields() {
+		let bytes = br#"{
+			"code": "abc123",
+			"type": 0,
+			"channel": { "id": "77", "name": "general", "type": 0 },
+			"inviter": {
+				"id": "789",
+				"username": "alice",
+				"discriminator": "0",
+				"avatar": null
+			},
+			"uses": 3,
+			"max_uses": 10,
+			"max_age": 3600,
+			"temporary": false,
+			"created_at": "2024-01-01T00:00:00.000000+00:00",
+			"expires_at": "2024-01-01T01:00:00.000000+00:00"
+		}"#;
+		let invite = CreateInvite::parse_response(bytes).unwrap();
+		assert_eq!(invite.code, "abc123");
+		assert_eq!(invite.uses, Some(3));
+		assert_eq!(invite.max_uses, Some(10));
+		assert_eq!(invite.inviter.map(|u| u.id.get()), Some(789));
+		assert!(invite.expires_at.is_some());
+	}
+
+	// ---- UpdateThread ----------------------------------------------------
+
+	#[test]
+	fn update_thread_into_request() {
+		let req = UpdateThread::new(Id::new(55))
+			.archived(true)
+			.locked(true)
+			.into_discord_request()
+			.unwrap();
+		assert!(matches!(req.method, HttpMethod::Patch));
+		assert_eq!(req.path, "channels/55");
+		assert_eq!(req.route_key, "PATCH /channels/55");
+	}
+
+	#[test]
+	fn update_thread_serializes_only_set_fields() {
+		let update = UpdateThread::new(Id::new(55))
+			.name("renamed")
+			.auto_archive_duration(1440);
+		let json = serde_json::to_value(&update).unwrap();
+		assert_eq!(
+			json,
+			serde_json::json!({
+				"name": "renamed",
+				"auto_archive_duration": 1440,
+			})
+		);
+	}
+
+	#[test]
+	fn update_thread_accepts_allowed_durations() {
+		for minutes in THREAD_AUTO_ARCHIVE_DURATIONS {
+			let update =
+				UpdateThread::new(Id::new(55)).auto_archive_duration(minutes);
+			assert!(update.validate().is_ok(), "{} rejected", minutes);
+		}
+	}
+
+	#[test]
+	fn update_thread_rejects_invalid_duration() {
+		let err = UpdateThread::new(Id::new(55))
+			.auto_archive_duration(90)
+			.into_discord_request()
+			.unwrap_err();
+		assert!(err.0.contains("auto_archive_duration"));
+	}
+}
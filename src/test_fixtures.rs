@@ -0,0 +1,54 @@
+//! Discord payload fixtures shared by the test modules.
+use serde_json::Value;
+use twilight_model::channel::Message;
+use twilight_model::gateway::payload::incoming::Ready;
+
+/// A plain message `id` from user `789` (alice) in channel `100`, as JSON
+/// so a test can change fields before [`parse_message`].
+pub fn message_json(id: &str, content: &str) -> Value {
+	serde_json::json!({
+		"id": id,
+		"channel_id": "100",
+		"author": {
+			"id": "789",
+			"username": "alice",
+			"discriminator": "0",
+			"avatar": null,
+		},
+		"content": content,
+		"timestamp": "2021-01-01T00:00:00.000000+00:00",
+		"edited_timestamp": null,
+		"embeds": [],
+		"attachments": [],
+		"mention_everyone": false,
+		"mention_roles": [],
+		"mentions": [],
+		"pinned": false,
+		"tts": false,
+		"type": 0,
+	})
+}
+
+pub fn parse_message(json: Value) -> Message {
+	serde_json::from_value(json).expect("valid message JSON")
+}
+
+/// The READY payload for a bot whose user id is `user_id`.
+pub fn ready(user_id: &str) -> Ready {
+	serde_json::from_value(serde_json::json!({
+		"application": { "id": "1", "flags": 0 },
+		"guilds": [],
+		"resume_gateway_url": "wss://gateway.discord.gg",
+		"session_id": "session",
+		"user": {
+			"id": user_id,
+			"username": "bot",
+			"discriminator": "0",
+			"avatar": null,
+			"bot": true,
+			"mfa_enabled": false,
+		},
+		"v": 10,
+	}))
+	.expect("valid ready JSON")
+}
@@ -17,6 +17,7 @@ use std::time::Instant;
 // (intentionally not re-exported — callers import twilight_model directly)
 // =========================================================================
 
+use twilight_model::gateway::event::EventType;
 use twilight_model::gateway::event::GatewayEvent;
 use twilight_model::gateway::event::GatewayEventDeserializer;
 use twilight_model::gateway::CloseCode;
@@ -113,6 +114,40 @@ mod tests {
 		assert_eq!(payload.op, OpCode::Hello);
 	}
 
+	/// The envelope's `op` is twilight's typed [`OpCode`], so every code the
+	/// driver sends or matches on must survive the wire format unchanged.
+	#[test]
+	fn gateway_opcodes_round_trip() {
+		let codes = [
+			(0, OpCode::Dispatch),
+			(1, OpCode::Heartbeat),
+			(2, OpCode::Identify),
+			(3, OpCode::PresenceUpdate),
+			(4, OpCode::VoiceStateUpdate),
+			(6, OpCode::Resume),
+			(7, OpCode::Reconnect),
+			(8, OpCode::RequestGuildMembers),
+			(9, OpCode::InvalidSession),
+			(10, OpCode::Hello),
+			(11, OpCode::HeartbeatAck),
+		];
+		for (code, op) in codes {
+			assert_eq!(OpCode::from(code), Some(op));
+			assert_eq!(op as u8, code);
+			let payload = GatewayPayload {
+				op,
+				d: None,
+				s: None,
+				t: None,
+			};
+			let json = serde_json::to_string(&payload).unwrap();
+			assert!(json.contains(&format!("\"op\":{}", code)), "{}", json);
+			let parsed: GatewayPayload = serde_json::from_str(&json).unwrap();
+			assert_eq!(parsed.op, op);
+		}
+		assert_eq!(OpCode::from(5), None);
+	}
+
 	#[test]
 	fn close_action_fatal_codes() {
 		assert_eq!(CloseAction::from_code(4004), CloseAction::Fatal);
@@ -357,6 +392,45 @@ pub fn parse_gateway_event(json: &str) -> Result<GatewayEvent, String> {
 		.map_err(|e| format!("failed to deserialize gateway event: {}", e))
 }
 
+// =========================================================================
+// Unknown dispatch events
+// =========================================================================
+
+/// A dispatch whose event name twilight-model doesn't recognise, e.g. one
+/// Discord added after the pinned twilight release.
+///
+/// [`parse_gateway_event`] rejects these, so the gateway driver forwards
+/// them separately with the raw payload intact.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownDispatch {
+	/// The `t` field, e.g. `"SOME_NEW_EVENT"`.
+	pub event_name: String,
+	/// The `s` field.
+	pub sequence: Option<u64>,
+	/// The raw `d` field.
+	pub data: Option<serde_json::Value>,
+}
+
+/// Extract an [`UnknownDispatch`] from a raw gateway message.
+///
+/// Returns `None` for non-dispatch payloads and for event names twilight
+/// knows, so a malformed known event is still reported as a parse error.
+pub fn parse_unknown_dispatch(json: &str) -> Option<UnknownDispatch> {
+	let payload: GatewayPayload = serde_json::from_str(json).ok()?;
+	if payload.op != OpCode::Dispatch {
+		return None;
+	}
+	let event_name = payload.t?;
+	if EventType::try_from(event_name.as_str()).is_ok() {
+		return None;
+	}
+	Some(UnknownDispatch {
+		event_name,
+		sequence: payload.s,
+		data: payload.d,
+	})
+}
+
 // =========================================================================
 // Tests (continued)
 // =========================================================================
@@ -495,4 +569,238 @@ mod tests_parse {
 		let result = parse_gateway_event(json);
 		assert!(result.is_err());
 	}
+
+	fn presence_update_json(user_id: &str) -> String {
+		format!(
+			r#"{{"op":0,"s":3,"t":"PRESENCE_UPDATE","d":{{"user":{{"id":"{}"}},"guild_id":"1","status":"online","activities":[],"client_status":{{}}}}}}"#,
+			user_id
+		)
+	}
+
+	#[test]
+	fn parse_presence_update_event() {
+		use twilight_model::gateway::event::DispatchEvent;
+		let event = parse_gateway_event(&presence_update_json("42")).unwrap();
+		match event {
+			GatewayEvent::Dispatch(3, DispatchEvent::PresenceUpdate(p)) => {
+				assert_eq!(p.user.id().get(), 42);
+			}
+			other => panic!("expected PresenceUpdate, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn parse_presence_update_with_empty_user_id_fails() {
+		// Rejected here so it never reaches the greeted-users set.
+		assert!(parse_gateway_event(&presence_update_json("")).is_err());
+	}
+
+	#[test]
+	fn parse_presence_update_with_invalid_user_id_fails() {
+		assert!(parse_gateway_event(&presence_update_json("0")).is_err());
+		assert!(parse_gateway_event(&presence_update_json("abc")).is_err());
+	}
+
+	// -- role events -------------------------------------------------------
+
+	const ROLE_JSON: &str = r#"{"id":"20","name":"mods","color":255,"colors":{"primary_color":255,"secondary_color":null,"tertiary_color":null},"hoist":true,"managed":false,"mentionable":true,"permissions":"8","position":3,"flags":0}"#;
+
+	#[test]
+	fn parse_role_create_event() {
+		use twilight_model::gateway::event::DispatchEvent;
+		let json = format!(
+			r#"{{"op":0,"s":4,"t":"GUILD_ROLE_CREATE","d":{{"guild_id":"1","role":{}}}}}"#,
+			ROLE_JSON
+		);
+		match parse_gateway_event(&json).unwrap() {
+			GatewayEvent::Dispatch(4, DispatchEvent::RoleCreate(ev)) => {
+				assert_eq!(ev.guild_id.get(), 1);
+				assert_eq!(ev.role.id.get(), 20);
+				assert_eq!(ev.role.name, "mods");
+			}
+			other => panic!("expected RoleCreate, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn parse_role_update_event() {
+		use twilight_model::gateway::event::DispatchEvent;
+		let json = format!(
+			r#"{{"op":0,"s":5,"t":"GUILD_ROLE_UPDATE","d":{{"guild_id":"1","role":{}}}}}"#,
+			ROLE_JSON
+		);
+		match parse_gateway_event(&json).unwrap() {
+			GatewayEvent::Dispatch(5, DispatchEvent::RoleUpdate(ev)) => {
+				assert_eq!(ev.guild_id.get(), 1);
+				assert_eq!(ev.role.position, 3);
+				assert!(ev.role.hoist);
+			}
+			other => panic!("expected RoleUpdate, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn parse_role_delete_event() {
+		use twilight_model::gateway::event::DispatchEvent;
+		let json = r#"{"op":0,"s":6,"t":"GUILD_ROLE_DELETE","d":{"guild_id":"1","role_id":"20"}}"#;
+		match parse_gateway_event(json).unwrap() {
+			GatewayEvent::Dispatch(6, DispatchEvent::RoleDelete(ev)) => {
+				assert_eq!(ev.guild_id.get(), 1);
+				assert_eq!(ev.role_id.get(), 20);
+			}
+			other => panic!("expected RoleDelete, got {:?}", other),
+		}
+	}
+
+	// -- auto moderation ---------------------------------------------------
+
+	#[test]
+	fn parse_auto_moderation_action_execution() {
+		use twilight_model::gateway::event::DispatchEvent;
+		use twilight_model::guild::auto_moderation::AutoModerationActionType;
+		use twilight_model::guild::auto_moderation::AutoModerationTriggerType;
+		let json = r#"{"op":0,"s":10,"t":"AUTO_MODERATION_ACTION_EXECUTION","d":{"guild_id":"1","action":{"type":1,"metadata":{"custom_message":"Keep it civil"}},"rule_id":"30","rule_trigger_type":1,"user_id":"42","channel_id":"2","message_id":"50","alert_system_message_id":null,"content":"a bad word","matched_keyword":"bad","matched_content":"bad"}}"#;
+		match parse_gateway_event(json).unwrap() {
+			GatewayEvent::Dispatch(
+				10,
+				DispatchEvent::AutoModerationActionExecution(ev),
+			) => {
+				assert_eq!(ev.guild_id.get(), 1);
+				assert_eq!(ev.rule_id.get(), 30);
+				assert_eq!(ev.user_id.get(), 42);
+				assert_eq!(ev.channel_id.map(|id| id.get()), Some(2));
+				assert_eq!(
+					ev.rule_trigger_type,
+					AutoModerationTriggerType::Keyword
+				);
+				assert_eq!(
+					ev.action.kind,
+					AutoModerationActionType::BlockMessage
+				);
+				assert_eq!(ev.matched_keyword.as_deref(), Some("bad"));
+				assert_eq!(ev.content, "a bad word");
+			}
+			other => {
+				panic!(
+					"expected AutoModerationActionExecution, got {:?}",
+					other
+				)
+			}
+		}
+	}
+
+	// -- reactions --------------------------------------------------------
+
+	#[test]
+	fn parse_reaction_add_and_remove() {
+		use twilight_model::channel::message::EmojiReactionType;
+		use twilight_model::gateway::event::DispatchEvent;
+		let json = r#"{"op":0,"s":11,"t":"MESSAGE_REACTION_ADD","d":{"user_id":"42","channel_id":"2","message_id":"50","guild_id":"1","burst":false,"type":0,"emoji":{"id":null,"name":"👍"}}}"#;
+		match parse_gateway_event(json).unwrap() {
+			GatewayEvent::Dispatch(11, DispatchEvent::ReactionAdd(ev)) => {
+				assert_eq!(ev.user_id.get(), 42);
+				assert_eq!(ev.message_id.get(), 50);
+				assert_eq!(ev.guild_id.map(|id| id.get()), Some(1));
+				assert_eq!(ev.emoji, EmojiReactionType::Unicode {
+					name: "👍".into()
+				});
+			}
+			other => panic!("expected ReactionAdd, got {:?}", other),
+		}
+		let json = r#"{"op":0,"s":12,"t":"MESSAGE_REACTION_REMOVE","d":{"user_id":"42","channel_id":"2","message_id":"50","guild_id":"1","burst":false,"type":0,"emoji":{"id":"60","name":"blobcat"}}}"#;
+		match parse_gateway_event(json).unwrap() {
+			GatewayEvent::Dispatch(12, DispatchEvent::ReactionRemove(ev)) => {
+				assert!(matches!(
+					ev.emoji,
+					EmojiReactionType::Custom { id, .. } if id.get() == 60
+				));
+			}
+			other => panic!("expected ReactionRemove, got {:?}", other),
+		}
+	}
+
+	// -- bans -------------------------------------------------------------
+
+	#[test]
+	fn parse_guild_ban_add_and_remove() {
+		use twilight_model::gateway::event::DispatchEvent;
+		let json = r#"{"op":0,"s":13,"t":"GUILD_BAN_ADD","d":{"guild_id":"1","user":{"id":"42","username":"spammer","discriminator":"0","avatar":null}}}"#;
+		match parse_gateway_event(json).unwrap() {
+			GatewayEvent::Dispatch(13, DispatchEvent::BanAdd(ev)) => {
+				assert_eq!(ev.guild_id.get(), 1);
+				assert_eq!(ev.user.id.get(), 42);
+				assert_eq!(ev.user.name, "spammer");
+			}
+			other => panic!("expected BanAdd, got {:?}", other),
+		}
+		let json = r#"{"op":0,"s":14,"t":"GUILD_BAN_REMOVE","d":{"guild_id":"1","user":{"id":"42","username":"spammer","discriminator":"0","avatar":null}}}"#;
+		match parse_gateway_event(json).unwrap() {
+			GatewayEvent::Dispatch(14, DispatchEvent::BanRemove(ev)) => {
+				assert_eq!(ev.guild_id.get(), 1);
+				assert_eq!(ev.user.id.get(), 42);
+			}
+			other => panic!("expected BanRemove, got {:?}", other),
+		}
+	}
+
+	// -- typing start -----------------------------------------------------
+
+	#[test]
+	fn parse_typing_start_in_guild() {
+		use twilight_model::gateway::event::DispatchEvent;
+		let json = r#"{"op":0,"s":8,"t":"TYPING_START","d":{"channel_id":"2","guild_id":"1","user_id":"42","timestamp":1700000000,"member":{"user":{"id":"42","username":"alice","discriminator":"0","avatar":null},"roles":[],"joined_at":"2021-01-01T00:00:00.000000+00:00","deaf":false,"mute":false,"flags":0}}}"#;
+		match parse_gateway_event(json).unwrap() {
+			GatewayEvent::Dispatch(8, DispatchEvent::TypingStart(ev)) => {
+				assert_eq!(ev.channel_id.get(), 2);
+				assert_eq!(ev.guild_id.map(|id| id.get()), Some(1));
+				assert_eq!(ev.user_id.get(), 42);
+				assert_eq!(ev.timestamp, 1700000000);
+				assert_eq!(ev.member.unwrap().user.name, "alice");
+			}
+			other => panic!("expected TypingStart, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn parse_typing_start_in_dm() {
+		use twilight_model::gateway::event::DispatchEvent;
+		let json = r#"{"op":0,"s":9,"t":"TYPING_START","d":{"channel_id":"2","user_id":"42","timestamp":1700000000}}"#;
+		match parse_gateway_event(json).unwrap() {
+			GatewayEvent::Dispatch(9, DispatchEvent::TypingStart(ev)) => {
+				assert_eq!(ev.channel_id.get(), 2);
+				assert!(ev.guild_id.is_none());
+				assert!(ev.member.is_none());
+			}
+			other => panic!("expected TypingStart, got {:?}", other),
+		}
+	}
+
+	// -- parse_unknown_dispatch() ------------------------------------------
+
+	#[test]
+	fn unknown_dispatch_keeps_raw_data() {
+		let json = r#"{"op":0,"s":7,"t":"SOME_FUTURE_EVENT","d":{"user_id":"42","nested":{"a":[1,2]}}}"#;
+		assert!(parse_gateway_event(json).is_err());
+		let unknown = parse_unknown_dispatch(json).unwrap();
+		assert_eq!(unknown.event_name, "SOME_FUTURE_EVENT");
+		assert_eq!(unknown.sequence, Some(7));
+		assert_eq!(
+			unknown.data,
+			Some(serde_json::json!({"user_id": "42", "nested": {"a": [1, 2]}}))
+		);
+	}
+
+	#[test]
+	fn unknown_dispatch_ignores_known_events() {
+		// Known names stay parse errors even when the payload is malformed.
+		assert!(parse_unknown_dispatch(&presence_update_json("")).is_none());
+		let typing = r#"{"op":0,"s":1,"t":"TYPING_START","d":{}}"#;
+		assert!(parse_unknown_dispatch(typing).is_none());
+	}
+
+	#[test]
+	fn unknown_dispatch_ignores_non_dispatch_ops() {
+		let json = r#"{"op":11,"d":null,"s":null,"t":null}"#;
+		assert!(parse_unknown_dispatch(json).is_none());
+	}
 }